@@ -0,0 +1,718 @@
+//! Changelogs of branches, made by comparing the branch files with an older version from Git.
+
+use crate::config::{BRANCH_FILES_FILE_NAME, BranchFiles, Modpack};
+use crate::modrinth::{File, FullVersion};
+use crate::{GitUtils, PackrinthError, PackrinthResult};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// The sections of a changelog, in the order they are listed.
+const SECTIONS: [&str; 3] = ["Added", "Updated", "Removed"];
+
+/// A change of one project or manual file in a branch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectChange {
+    Added {
+        name: String,
+        file: File,
+    },
+    Removed {
+        name: String,
+        file: File,
+    },
+    Updated {
+        name: String,
+        old_file: File,
+        new_file: File,
+
+        /// The Modrinth changelog of the new version, if it was requested.
+        changelog: Option<String>,
+    },
+}
+
+impl ProjectChange {
+    /// Returns the name of the changed project or manual file.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            ProjectChange::Added { name, .. }
+            | ProjectChange::Removed { name, .. }
+            | ProjectChange::Updated { name, .. } => name,
+        }
+    }
+
+    /// Returns the Modrinth project ID of the changed file, or its path if it isn't from Modrinth.
+    /// Changes of the same project in different branches have the same key.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        let file = change_file(self);
+        file.modrinth_project_id().unwrap_or(&file.path)
+    }
+
+    /// Returns the title of the changelog section this change is listed in.
+    const fn section(&self) -> &'static str {
+        match self {
+            ProjectChange::Added { .. } => "Added",
+            ProjectChange::Updated { .. } => "Updated",
+            ProjectChange::Removed { .. } => "Removed",
+        }
+    }
+}
+
+/// All changes of a branch since a Git revision.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BranchChangelog {
+    pub branch: String,
+
+    /// The Git revision the branch files are compared with.
+    pub since: String,
+    pub changes: Vec<ProjectChange>,
+}
+
+impl BranchChangelog {
+    /// Compares the branch files of a branch in the working tree with the branch files
+    /// at a Git revision.
+    ///
+    /// If the branch files didn't exist at the revision, all projects are added.
+    ///
+    /// # Errors
+    /// - Errors of [`BranchFiles::from_directory`]
+    /// - Errors of [`branch_files_at_revision`]
+    pub fn from_git(modpack: &Modpack, branch: &str, since: &str) -> PackrinthResult<Self> {
+        let new_branch_files = BranchFiles::from_directory(&modpack.directory, branch)?;
+        let old_branch_files =
+            branch_files_at_revision(modpack, branch, since)?.unwrap_or_default();
+
+        Ok(Self::between(
+            branch,
+            since,
+            &old_branch_files,
+            &new_branch_files,
+        ))
+    }
+
+    /// Compares two versions of the branch files of a branch.
+    ///
+    /// Files from Modrinth are matched by their project, so a new version of a project is
+    /// an update. Other files are matched by their path.
+    #[must_use]
+    pub fn between(branch: &str, since: &str, old: &BranchFiles, new: &BranchFiles) -> Self {
+        let mut changes = Vec::new();
+        for new_file in &new.files {
+            match old
+                .files
+                .iter()
+                .find(|old_file| Self::same_project(old_file, new_file))
+            {
+                Some(old_file) if old_file.hashes.sha512 != new_file.hashes.sha512 => {
+                    changes.push(ProjectChange::Updated {
+                        name: Self::project_name(new, new_file),
+                        old_file: old_file.clone(),
+                        new_file: new_file.clone(),
+                        changelog: None,
+                    });
+                }
+                Some(_old_file) => {}
+                None => changes.push(ProjectChange::Added {
+                    name: Self::project_name(new, new_file),
+                    file: new_file.clone(),
+                }),
+            }
+        }
+        for old_file in &old.files {
+            if !new
+                .files
+                .iter()
+                .any(|new_file| Self::same_project(old_file, new_file))
+            {
+                changes.push(ProjectChange::Removed {
+                    name: Self::project_name(old, old_file),
+                    file: old_file.clone(),
+                });
+            }
+        }
+
+        Self {
+            branch: branch.to_string(),
+            since: since.to_string(),
+            changes,
+        }
+    }
+
+    fn same_project(a: &File, b: &File) -> bool {
+        match (a.modrinth_project_id(), b.modrinth_project_id()) {
+            (Some(a_id), Some(b_id)) => a_id == b_id,
+            (None, None) => a.path == b.path,
+            _ => false,
+        }
+    }
+
+    /// Returns the name of the project of a file, or the file name if the project isn't known.
+    fn project_name(branch_files: &BranchFiles, file: &File) -> String {
+        let file_name = || file.file_name().to_string();
+        let Some(project_id) = file.modrinth_project_id() else {
+            return file_name();
+        };
+        branch_files
+            .projects
+            .iter()
+            .find(|project| project.id.as_deref() == Some(project_id))
+            .map_or_else(file_name, |project| project.name.clone())
+    }
+
+    /// Requests the Modrinth changelogs of the new versions of updated projects.
+    ///
+    /// # Errors
+    /// - Errors of [`FullVersion::from_sha512_hash`]
+    pub fn fetch_changelogs(&mut self) -> PackrinthResult<()> {
+        for change in &mut self.changes {
+            if let ProjectChange::Updated {
+                new_file,
+                changelog,
+                ..
+            } = change
+                && new_file.modrinth_project_id().is_some()
+            {
+                *changelog = FullVersion::from_sha512_hash(&new_file.hashes.sha512)?.changelog;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a short summary of the changes, like `2 added, 1 updated`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let counts: Vec<String> = SECTIONS
+            .iter()
+            .filter_map(|section| {
+                let count = self
+                    .changes
+                    .iter()
+                    .filter(|change| change.section() == *section)
+                    .count();
+                (count > 0).then(|| format!("{count} {}", section.to_lowercase()))
+            })
+            .collect();
+        if counts.is_empty() {
+            "no changes".to_string()
+        } else {
+            counts.join(", ")
+        }
+    }
+
+    /// Renders the changelog as Markdown for release notes.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        writeln!(
+            markdown,
+            "## Changes in {} since {}",
+            self.branch, self.since
+        )
+        .unwrap();
+
+        if self.changes.is_empty() {
+            writeln!(markdown).unwrap();
+            writeln!(markdown, "No projects have changed.").unwrap();
+            return markdown;
+        }
+
+        for section in SECTIONS {
+            let changes: Vec<&ProjectChange> = self
+                .changes
+                .iter()
+                .filter(|change| change.section() == section)
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+
+            // Changelogs of updated projects already end with an empty line.
+            if !markdown.ends_with("\n\n") {
+                writeln!(markdown).unwrap();
+            }
+            writeln!(markdown, "### {section}").unwrap();
+            writeln!(markdown).unwrap();
+            for change in changes {
+                match change {
+                    ProjectChange::Added { name, file } | ProjectChange::Removed { name, file } => {
+                        writeln!(markdown, "- {name} (`{}`)", file.file_name()).unwrap();
+                    }
+                    ProjectChange::Updated {
+                        name,
+                        old_file,
+                        new_file,
+                        changelog,
+                    } => {
+                        writeln!(
+                            markdown,
+                            "- {name}: `{}` → `{}`",
+                            old_file.file_name(),
+                            new_file.file_name()
+                        )
+                        .unwrap();
+                        if let Some(changelog) = changelog
+                            && !changelog.trim().is_empty()
+                        {
+                            writeln!(markdown).unwrap();
+                            for line in changelog.trim().lines() {
+                                writeln!(markdown, "  > {line}").unwrap();
+                            }
+                            writeln!(markdown).unwrap();
+                        }
+                    }
+                }
+            }
+        }
+
+        markdown
+    }
+}
+
+/// All changes of one project or manual file across branches, so they can be committed
+/// and reviewed on their own.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectUpdate {
+    pub name: String,
+
+    /// The Modrinth project ID of the project, or [`None`] for manual files.
+    pub project_id: Option<String>,
+
+    /// The branches the project changed in, with its change in that branch.
+    pub changes: Vec<(String, ProjectChange)>,
+}
+
+impl ProjectUpdate {
+    /// Groups the changes of branch changelogs by project, in the order they first appear.
+    #[must_use]
+    pub fn from_changelogs(changelogs: &[BranchChangelog]) -> Vec<Self> {
+        let mut updates: Vec<(String, Self)> = Vec::new();
+        for changelog in changelogs {
+            for change in &changelog.changes {
+                let branch_change = (changelog.branch.clone(), change.clone());
+                if let Some((_key, update)) =
+                    updates.iter_mut().find(|(key, _)| key == change.key())
+                {
+                    update.changes.push(branch_change);
+                } else {
+                    updates.push((
+                        change.key().to_string(),
+                        Self {
+                            name: change.name().to_string(),
+                            project_id: change_file(change)
+                                .modrinth_project_id()
+                                .map(ToString::to_string),
+                            changes: vec![branch_change],
+                        },
+                    ));
+                }
+            }
+        }
+        updates.into_iter().map(|(_key, update)| update).collect()
+    }
+
+    /// Returns the name of the Git branch this update is committed to, like
+    /// `packrinth/update-fabric-api-P7dR8mSH` for a Modrinth project or
+    /// `packrinth/update-mods-example.jar` for a manual file.
+    ///
+    /// Because it contains the project ID or the path of the manual file, updates of
+    /// different projects with the same name are committed to different Git branches.
+    #[must_use]
+    pub fn git_branch(&self) -> String {
+        let name = match &self.project_id {
+            Some(project_id) => format!(
+                "{}-{}",
+                git_branch_part(&self.name.to_lowercase()),
+                git_branch_part(project_id)
+            ),
+            None => git_branch_part(
+                self.changes
+                    .first()
+                    .map_or(self.name.as_str(), |(_branch, change)| change.key()),
+            ),
+        };
+        format!("packrinth/update-{name}")
+    }
+
+    /// Creates a Git commit message with the change of the project in every branch.
+    #[must_use]
+    pub fn commit_message(&self) -> String {
+        let mut message = format!("Update {}", self.name);
+        writeln!(message).unwrap();
+        for (branch, change) in &self.changes {
+            write!(message, "\n- {branch}: {}", Self::describe(change)).unwrap();
+        }
+        message
+    }
+
+    /// Renders a summary of the update as Markdown, for example as the description of a pull request.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        writeln!(markdown, "# Update {}", self.name).unwrap();
+        writeln!(markdown).unwrap();
+        writeln!(markdown, "| Branch | Change |").unwrap();
+        writeln!(markdown, "|--------|--------|").unwrap();
+        for (branch, change) in &self.changes {
+            writeln!(markdown, "| {branch} | {} |", Self::describe(change)).unwrap();
+        }
+        writeln!(markdown).unwrap();
+        writeln!(
+            markdown,
+            "The changes are committed to the Git branch `{}`.",
+            self.git_branch()
+        )
+        .unwrap();
+        markdown
+    }
+
+    fn describe(change: &ProjectChange) -> String {
+        match change {
+            ProjectChange::Added { file, .. } => format!("added `{}`", file.file_name()),
+            ProjectChange::Removed { file, .. } => format!("removed `{}`", file.file_name()),
+            ProjectChange::Updated {
+                old_file, new_file, ..
+            } => format!("`{}` → `{}`", old_file.file_name(), new_file.file_name()),
+        }
+    }
+
+    /// Applies only the change of this project in a branch to the old branch files of that branch.
+    ///
+    /// The project entry of an added project is taken from the new branch files.
+    #[must_use]
+    pub fn apply(&self, branch: &str, old: &BranchFiles, new: &BranchFiles) -> BranchFiles {
+        let mut branch_files = old.clone();
+        for (_branch, change) in self.changes.iter().filter(|(b, _)| b == branch) {
+            match change {
+                ProjectChange::Added { file, .. } => {
+                    if let Some(project_id) = file.modrinth_project_id()
+                        && let Some(project) = new
+                            .projects
+                            .iter()
+                            .find(|project| project.id.as_deref() == Some(project_id))
+                    {
+                        branch_files.projects.push(project.clone());
+                    }
+                    branch_files.files.push(file.clone());
+                }
+                ProjectChange::Removed { file, .. } => {
+                    if let Some(project_id) = file.modrinth_project_id() {
+                        branch_files
+                            .projects
+                            .retain(|project| project.id.as_deref() != Some(project_id));
+                    }
+                    branch_files
+                        .files
+                        .retain(|old_file| old_file.path != file.path);
+                }
+                ProjectChange::Updated {
+                    old_file, new_file, ..
+                } => {
+                    for file in &mut branch_files.files {
+                        if file.path == old_file.path {
+                            file.clone_from(new_file);
+                        }
+                    }
+                }
+            }
+        }
+        branch_files
+    }
+}
+
+/// Returns the file that was added, removed or updated to.
+const fn change_file(change: &ProjectChange) -> &File {
+    match change {
+        ProjectChange::Added { file, .. } | ProjectChange::Removed { file, .. } => file,
+        ProjectChange::Updated { new_file, .. } => new_file,
+    }
+}
+
+/// Replaces everything except letters, digits, `.` and `_` in text by single `-`,
+/// so it can be used in the name of a Git branch.
+fn git_branch_part(text: &str) -> String {
+    let mut part = String::new();
+    for c in text.chars() {
+        // Git branch names can't contain `..`.
+        if c.is_ascii_alphanumeric() || c == '_' || (c == '.' && !part.ends_with('.')) {
+            part.push(c);
+        } else if c != '.' && !part.is_empty() && !part.ends_with('-') {
+            part.push('-');
+        }
+    }
+    part.trim_end_matches('-').to_string()
+}
+
+/// Reads the branch files of a branch at a Git revision.
+///
+/// If the branch files didn't exist at the revision, `None` is returned.
+///
+/// # Errors
+/// - Errors of [`GitUtils::read_file_at_revision`]
+/// - [`PackrinthError::FailedToParseConfigJson`] if the branch files were invalid
+pub fn branch_files_at_revision(
+    modpack: &Modpack,
+    branch: &str,
+    revision: &str,
+) -> PackrinthResult<Option<BranchFiles>> {
+    let branch_files_path = modpack.directory.join(branch).join(BRANCH_FILES_FILE_NAME);
+    let Some(contents) = GitUtils::read_file_at_revision(&branch_files_path, revision)? else {
+        return Ok(None);
+    };
+    parse_branch_files_at_revision(&branch_files_path, revision, &contents).map(Some)
+}
+
+/// Parses branch files that were read from a Git revision with [`GitUtils::read_file_at_revision`].
+///
+/// # Errors
+/// - [`PackrinthError::FailedToParseConfigJson`] if the branch files were invalid
+pub fn parse_branch_files_at_revision(
+    branch_files_path: &Path,
+    revision: &str,
+    contents: &str,
+) -> PackrinthResult<BranchFiles> {
+    match serde_json::from_str(contents) {
+        Ok(branch_files) => Ok(branch_files),
+        Err(error) => Err(PackrinthError::FailedToParseConfigJson {
+            config_path: format!("{}@{revision}", branch_files_path.display()),
+            error_message: error.to_string(),
+        }),
+    }
+}
+
+/// Creates a Git commit message with a subject line and the changes of every branch.
+#[must_use]
+pub fn commit_message(subject: &str, changelogs: &[BranchChangelog]) -> String {
+    let mut message = subject.to_string();
+    for changelog in changelogs {
+        writeln!(message).unwrap();
+        writeln!(message).unwrap();
+        write!(message, "{}: {}", changelog.branch, changelog.summary()).unwrap();
+        for section in SECTIONS {
+            for change in changelog
+                .changes
+                .iter()
+                .filter(|change| change.section() == section)
+            {
+                write!(message, "\n- {section} {}", change.name()).unwrap();
+            }
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BranchFilesProject;
+    use crate::test_utils::file;
+    use pretty_assertions::assert_eq;
+
+    fn branch_files(files: Vec<File>) -> BranchFiles {
+        let mut branch_files = BranchFiles::default();
+        branch_files.projects = vec![
+            BranchFilesProject {
+                name: "Sodium".to_string(),
+                id: Some("AANobbMI".to_string()),
+            },
+            BranchFilesProject {
+                name: "Lithium".to_string(),
+                id: Some("gvQqBUqZ".to_string()),
+            },
+        ];
+        branch_files.files = files;
+        branch_files
+    }
+
+    #[test]
+    fn changelog_between_branch_files() {
+        let old = branch_files(vec![
+            file(
+                "mods/sodium-0.6.12.jar",
+                "a",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/a/sodium-0.6.12.jar",
+            ),
+            file(
+                "mods/lithium-0.18.0.jar",
+                "b",
+                "https://cdn.modrinth.com/data/gvQqBUqZ/versions/b/lithium-0.18.0.jar",
+            ),
+            file(
+                "mods/old-manual.jar",
+                "c",
+                "https://github.com/old-manual.jar",
+            ),
+        ]);
+        let new = branch_files(vec![
+            file(
+                "mods/sodium-0.6.13.jar",
+                "d",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/d/sodium-0.6.13.jar",
+            ),
+            file(
+                "mods/lithium-0.18.0.jar",
+                "b",
+                "https://cdn.modrinth.com/data/gvQqBUqZ/versions/b/lithium-0.18.0.jar",
+            ),
+            file(
+                "mods/new-manual.jar",
+                "e",
+                "https://github.com/new-manual.jar",
+            ),
+        ]);
+
+        let mut changelog = BranchChangelog::between("1.21.8", "v1.0.0", &old, &new);
+        assert_eq!(
+            vec!["Sodium", "new-manual.jar", "old-manual.jar"],
+            changelog
+                .changes
+                .iter()
+                .map(ProjectChange::name)
+                .collect::<Vec<_>>()
+        );
+
+        if let ProjectChange::Updated { changelog, .. } = &mut changelog.changes[0] {
+            *changelog = Some("- Fixed a crash\n- Faster chunk meshing".to_string());
+        }
+        assert_eq!(
+            "## Changes in 1.21.8 since v1.0.0
+
+### Added
+
+- new-manual.jar (`new-manual.jar`)
+
+### Updated
+
+- Sodium: `sodium-0.6.12.jar` → `sodium-0.6.13.jar`
+
+  > - Fixed a crash
+  > - Faster chunk meshing
+
+### Removed
+
+- old-manual.jar (`old-manual.jar`)
+",
+            changelog.to_markdown()
+        );
+
+        assert_eq!(
+            "## Changes in 1.21.8 since HEAD\n\nNo projects have changed.\n",
+            BranchChangelog::between("1.21.8", "HEAD", &new, &new).to_markdown()
+        );
+
+        assert_eq!("1 added, 1 updated, 1 removed", changelog.summary());
+        assert_eq!(
+            "Update 1.21.8, 1.21.1
+
+1.21.8: 1 added, 1 updated, 1 removed
+- Added new-manual.jar
+- Updated Sodium
+- Removed old-manual.jar
+
+1.21.1: no changes",
+            commit_message(
+                "Update 1.21.8, 1.21.1",
+                &[
+                    changelog,
+                    BranchChangelog::between("1.21.1", "HEAD", &new, &new)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn project_updates_only_change_their_project() {
+        let sodium_old = file(
+            "mods/sodium-0.6.12.jar",
+            "a",
+            "https://cdn.modrinth.com/data/AANobbMI/versions/a/sodium-0.6.12.jar",
+        );
+        let sodium_new = file(
+            "mods/sodium-0.6.13.jar",
+            "d",
+            "https://cdn.modrinth.com/data/AANobbMI/versions/d/sodium-0.6.13.jar",
+        );
+        let lithium = file(
+            "mods/lithium-0.18.0.jar",
+            "b",
+            "https://cdn.modrinth.com/data/gvQqBUqZ/versions/b/lithium-0.18.0.jar",
+        );
+        let mut old = branch_files(vec![sodium_old.clone()]);
+        old.projects.retain(|project| project.name == "Sodium");
+        let new = branch_files(vec![sodium_new.clone(), lithium.clone()]);
+
+        let updates = ProjectUpdate::from_changelogs(&[
+            BranchChangelog::between("1.21.8", "HEAD", &old, &new),
+            BranchChangelog::between("1.21.1", "HEAD", &old, &new),
+        ]);
+        assert_eq!(
+            vec!["Sodium", "Lithium"],
+            updates
+                .iter()
+                .map(|update| update.name.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let sodium = &updates[0];
+        assert_eq!("packrinth/update-sodium-AANobbMI", sodium.git_branch());
+        assert_eq!(
+            "Update Sodium
+
+- 1.21.8: `sodium-0.6.12.jar` → `sodium-0.6.13.jar`
+- 1.21.1: `sodium-0.6.12.jar` → `sodium-0.6.13.jar`",
+            sodium.commit_message()
+        );
+        assert_eq!(
+            "# Update Sodium
+
+| Branch | Change |
+|--------|--------|
+| 1.21.8 | `sodium-0.6.12.jar` → `sodium-0.6.13.jar` |
+| 1.21.1 | `sodium-0.6.12.jar` → `sodium-0.6.13.jar` |
+
+The changes are committed to the Git branch `packrinth/update-sodium-AANobbMI`.
+",
+            sodium.to_markdown()
+        );
+        let mut expected = old.clone();
+        expected.files = vec![sodium_new];
+        assert_eq!(expected, sodium.apply("1.21.8", &old, &new));
+
+        let lithium_update = &updates[1];
+        let applied = lithium_update.apply("1.21.1", &old, &new);
+        assert_eq!(vec![sodium_old, lithium], applied.files);
+        assert_eq!(
+            vec!["Sodium", "Lithium"],
+            applied
+                .projects
+                .iter()
+                .map(|project| project.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(old, lithium_update.apply("1.21.20", &old, &new));
+
+        let mut renamed = lithium_update.clone();
+        renamed.name = "Fabric API (Legacy)".to_string();
+        assert_eq!(
+            "packrinth/update-fabric-api-legacy-gvQqBUqZ",
+            renamed.git_branch()
+        );
+
+        let manual_file = file(
+            "mods/Example Mod.jar",
+            "e",
+            "https://github.com/example.jar",
+        );
+        let updates = ProjectUpdate::from_changelogs(&[BranchChangelog::between(
+            "1.21.8",
+            "HEAD",
+            &old,
+            &branch_files(vec![old.files[0].clone(), manual_file]),
+        )]);
+        assert_eq!(None, updates[0].project_id);
+        assert_eq!(
+            "packrinth/update-mods-Example-Mod.jar",
+            updates[0].git_branch()
+        );
+    }
+}
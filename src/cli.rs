@@ -21,21 +21,51 @@ pub enum SubCommand {
     /// Add or remove Modrinth projects and tweak them for your branches
     Project(ProjectArgs),
 
+    /// Search Modrinth for projects that support your branches
+    Search(SearchArgs),
+
     /// Create and remove branches that separate your Modpack for various versions
     Branch(BranchArgs),
 
+    /// Add, list, remove and refresh files that aren't added from Modrinth projects
+    Manual(ManualArgs),
+
     /// Update branches with the newest project versions
     Update(UpdateArgs),
 
     /// Export a branch to a Modrinth modpack
     Export(ExportArgs),
 
+    /// Generate a Markdown changelog of a branch since a Git revision
+    Changelog(ChangelogArgs),
+
+    /// Bump the versions of branches, export them and create a Git commit and tag
+    Release(ReleaseArgs),
+
+    /// Upload exported branches as new versions of the modpack project on Modrinth
+    Publish(PublishArgs),
+
+    /// Check which projects are available for Minecraft versions and loaders, without creating a branch
+    Check(CheckArgs),
+
+    /// Check the modpack configuration, branch configurations and branch files for problems
+    Validate(ValidateArgs),
+
+    /// Check the licenses of projects and files before redistributing the modpack
+    Licenses(LicensesArgs),
+
+    /// Upgrade the modpack configuration files to the pack format of this Packrinth version
+    Migrate(MigrateArgs),
+
     /// Removes the target directory
     Clean(CleanArgs),
 
     /// Generate Markdown documentation
     Doc(DocArgs),
 
+    /// Print or save the JSON Schemas of the configuration files
+    Schema(SchemaArgs),
+
     /// Generate shell completion for Packrinth
     Completions(CompletionsArgs),
 
@@ -70,6 +100,8 @@ pub struct InitArgs {
     pub force: bool,
 }
 
+// Allow because it is just a CLI.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 pub struct ImportArgs {
     /// Location of the Modrinth modpack to import
@@ -86,6 +118,10 @@ pub struct ImportArgs {
     /// If the modpack is in a Git repository, allow importing even if there are uncommitted changes
     #[clap(short = 'D', long)]
     pub allow_dirty: bool,
+
+    /// Commit the imported branch to the Git repository of the modpack
+    #[clap(short, long, conflicts_with = "allow_dirty")]
+    pub commit: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -103,6 +139,9 @@ pub enum ProjectSubCommand {
     #[clap(visible_alias = "ls")]
     List(ListProjectsArgs),
 
+    /// Show information from Modrinth about projects and their files in the branches
+    Info(ProjectInfoArgs),
+
     /// Add projects to this modpack
     Add(AddProjectsArgs),
 
@@ -115,13 +154,31 @@ pub enum ProjectSubCommand {
     /// Add exclusions to a project in this modpack
     Exclusions(ExclusionsProjectArgs),
 
+    /// Add projects in this modpack to groups or remove them from groups
+    Group(GroupProjectArgs),
+
     /// Remove projects from this modpack
     #[clap(visible_alias = "rm")]
     Remove(RemoveProjectsArgs),
 }
 
 #[derive(Parser, Debug)]
-pub struct ListProjectsArgs;
+pub struct ListProjectsArgs {
+    /// Only list projects in this group
+    #[clap(short, long)]
+    pub group: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ProjectInfoArgs {
+    /// Projects to show information about. The projects don't have to be added to the modpack.
+    #[arg(required = true)]
+    pub projects: Vec<String>,
+
+    /// Only show the files of these branches. If no branches are specified, all branches will be used.
+    #[clap(short, long)]
+    pub branches: Option<Vec<String>>,
+}
 
 #[derive(Parser, Debug)]
 pub struct AddProjectsArgs {
@@ -148,6 +205,13 @@ pub struct AddProjectsArgs {
     /// For a project, you can only have inclusions OR exclusions.
     #[clap(short, long, group = "include_or_exclude")]
     pub exclusions: Option<Vec<String>>,
+
+    /// Add the projects to these groups
+    ///
+    /// Groups that don't exist yet will be created. Settings of a group can be changed
+    /// in the modpack configuration file.
+    #[clap(short, long)]
+    pub groups: Option<Vec<String>>,
 }
 
 #[derive(Parser, Debug)]
@@ -275,6 +339,42 @@ pub struct RemoveExclusionsArgs {
     pub all: bool,
 }
 
+#[derive(Parser, Debug)]
+pub struct GroupProjectArgs {
+    #[clap(subcommand)]
+    pub command: GroupSubCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum GroupSubCommand {
+    /// Add a project to groups
+    Add(AddGroupsArgs),
+
+    /// Remove a project from groups
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveGroupsArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct AddGroupsArgs {
+    /// Project to add to the groups
+    pub project: String,
+
+    /// Groups to add the project to. Groups that don't exist yet will be created.
+    #[arg(required = true)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct RemoveGroupsArgs {
+    /// Project to remove from the groups
+    pub project: String,
+
+    /// Groups to remove the project from
+    #[arg(required = true)]
+    pub groups: Vec<String>,
+}
+
 #[derive(Parser, Debug)]
 pub struct RemoveProjectsArgs {
     /// Projects to remove from the modpack
@@ -308,6 +408,24 @@ pub struct UpdateArgs {
     /// If the modpack is in a Git repository, allow updating even if there are uncommitted changes
     #[clap(short = 'D', long)]
     pub allow_dirty: bool,
+
+    /// Commit the updated branches to the Git repository of the modpack, with a summary of the changes
+    #[clap(short, long, conflicts_with = "allow_dirty")]
+    pub commit: bool,
+
+    /// Commit the change of every project to its own Git branch, and write a Markdown summary
+    /// of every change to target/updates, for example to open pull requests from CI.
+    /// The working tree is left unchanged.
+    #[clap(long, conflicts_with_all = ["allow_dirty", "commit", "loader"])]
+    pub per_project_commits: bool,
+
+    /// Also set the main mod loader version of the branches to the latest stable version
+    #[clap(long)]
+    pub loader: bool,
+
+    /// Don't check the modpack configuration for problems before updating
+    #[clap(long)]
+    pub no_validate: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -332,6 +450,20 @@ pub enum BranchSubCommand {
     /// Remove branches
     #[clap(visible_alias = "rm")]
     Remove(RemoveBranchesArgs),
+
+    /// Rename a branch and update all project settings that refer to it
+    #[clap(visible_alias = "mv")]
+    Rename(RenameBranchArgs),
+
+    /// Copy a branch to a new branch, including its configuration and overrides
+    #[clap(visible_alias = "cp")]
+    Copy(CopyBranchArgs),
+
+    /// Copy a branch to a new branch for another Minecraft version and report which projects are available for it
+    Port(PortBranchArgs),
+
+    /// Set the main mod loader version of branches to the latest stable version
+    UpdateLoader(UpdateLoaderArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -342,6 +474,10 @@ pub struct AddBranchesArgs {
     /// Names of new branches to add
     #[arg(required = true)]
     pub branches: Vec<String>,
+
+    /// Let the new branches extend this branch, so they inherit its configuration
+    #[clap(short, long)]
+    pub extends: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -351,12 +487,279 @@ pub struct RemoveBranchesArgs {
     pub branches: Vec<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct RenameBranchArgs {
+    /// Current name of the branch
+    pub branch: String,
+
+    /// New name of the branch
+    pub new_branch: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct CopyBranchArgs {
+    /// Branch to copy
+    pub branch: String,
+
+    /// Name of the new branch
+    pub new_branch: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct PortBranchArgs {
+    /// Branch to port
+    pub branch: String,
+
+    /// Name of the new branch
+    pub new_branch: String,
+
+    /// Main Minecraft version of the new branch
+    #[clap(short, long)]
+    pub minecraft: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct UpdateLoaderArgs {
+    /// Branches to update the loader of. If no branches are specified, all branches will be updated.
+    pub branches: Option<Vec<String>>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ManualArgs {
+    #[clap(subcommand)]
+    pub command: ManualSubCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum ManualSubCommand {
+    /// List the manual files of branches
+    #[clap(visible_alias = "ls")]
+    List(ListManualFilesArgs),
+
+    /// Add a file from a URL or a local file to a branch
+    Add(AddManualFileArgs),
+
+    /// Remove manual files from a branch
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveManualFilesArgs),
+
+    /// Download manual files again and update their hashes and sizes if they changed
+    Refresh(RefreshManualFilesArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ListManualFilesArgs {
+    /// Branches to list the manual files of. If no branches are specified, all branches will be listed.
+    pub branches: Option<Vec<String>>,
+}
+
+#[derive(Parser, Debug)]
+pub struct AddManualFileArgs {
+    /// Branch to add the file to
+    pub branch: String,
+
+    /// Download URL or path of a local file
+    pub source: String,
+
+    /// Download URL of the file. Required if a local file is added
+    #[clap(short, long)]
+    pub url: Option<String>,
+
+    /// Path of the file in the instance, like mods/example.jar. If not specified, it is based on the contents of the file,
+    /// and jar files that aren't recognised are added as mods
+    #[clap(short, long)]
+    pub path: Option<String>,
+
+    /// Support of the file on clients. If not specified, it is based on the contents of the file
+    #[clap(long, value_enum)]
+    pub client: Option<FileSideSupport>,
+
+    /// Support of the file on servers. If not specified, it is based on the contents of the file
+    #[clap(long, value_enum)]
+    pub server: Option<FileSideSupport>,
+
+    /// Replace a manual file with the same path
+    #[clap(short, long)]
+    pub force: bool,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum FileSideSupport {
+    Required,
+    Optional,
+    Unsupported,
+}
+
+#[derive(Parser, Debug)]
+pub struct RemoveManualFilesArgs {
+    /// Branch to remove the files from
+    pub branch: String,
+
+    /// Paths or file names of the manual files to remove
+    #[arg(required = true)]
+    pub files: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct RefreshManualFilesArgs {
+    /// Branches to refresh the manual files of. If no branches are specified, all branches will be refreshed.
+    pub branches: Option<Vec<String>>,
+}
+
+#[derive(Parser, Debug)]
+pub struct CheckArgs {
+    /// Minecraft versions to check
+    #[clap(short, long, required = true)]
+    pub minecraft: Vec<String>,
+
+    /// Main mod loaders to check. Every loader is checked for every Minecraft version
+    #[clap(short, long, value_enum, default_values_t = [CheckLoader::Vanilla])]
+    pub loader: Vec<CheckLoader>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum CheckLoader {
+    /// No main mod loader, for projects like resource packs and data packs
+    Vanilla,
+    Fabric,
+    Forge,
+
+    #[clap(name = "neoforge")]
+    NeoForge,
+
+    Quilt,
+}
+
+#[derive(Parser, Debug)]
+pub struct SearchArgs {
+    /// Text to search for
+    #[arg(required = true)]
+    pub query: Vec<String>,
+
+    /// Only search for projects that support at least one of these branches.
+    /// If no branches are specified, all branches will be used.
+    #[clap(short, long)]
+    pub branches: Option<Vec<String>>,
+
+    /// Only search for projects of this type
+    #[clap(short = 't', long, value_parser = ["mod", "resourcepack", "shader", "datapack", "plugin"])]
+    pub project_type: Option<String>,
+
+    /// Maximum amount of results to show
+    #[clap(short, long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..=100))]
+    pub limit: u16,
+
+    /// Select results to add to the modpack after searching
+    #[clap(short, long)]
+    pub add: bool,
+}
+
 #[derive(Parser, Debug)]
 pub struct ExportArgs {
     /// Branches to export. If no branches are specified, all branches will be exported.
     pub branches: Option<Vec<String>>,
+
+    /// Don't check the modpack configuration for problems before exporting
+    #[clap(long)]
+    pub no_validate: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ChangelogArgs {
+    /// Branch to generate the changelog for
+    pub branch: String,
+
+    /// Git revision to compare the branch with, like a commit hash, tag or HEAD~2
+    #[clap(short, long, default_value = "HEAD")]
+    pub since: String,
+
+    /// Include the Modrinth changelogs of the new versions of updated projects
+    #[clap(short, long)]
+    pub changelogs: bool,
+
+    /// Write the changelog to this file instead of printing it
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReleaseArgs {
+    /// Part of the branch versions to increase
+    #[clap(long)]
+    pub bump: ReleaseBump,
+
+    /// Branches to release. If no branches are specified, all branches will be released.
+    pub branches: Option<Vec<String>>,
+
+    /// Don't check the modpack configuration for problems before releasing
+    #[clap(long)]
+    pub no_validate: bool,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum ReleaseBump {
+    Major,
+    Minor,
+    Patch,
 }
 
+#[derive(Parser, Debug)]
+pub struct PublishArgs {
+    /// Branches to publish. If no branches are specified, all branches will be published.
+    pub branches: Option<Vec<String>>,
+
+    /// Release channel of the new versions
+    #[clap(short = 't', long, default_value = "release")]
+    pub version_type: PublishVersionType,
+
+    /// Markdown file with the changelog of the new versions
+    #[clap(short, long, conflicts_with = "since")]
+    pub changelog: Option<PathBuf>,
+
+    /// Generate the changelog of every branch by comparing it with this Git revision
+    #[clap(short, long)]
+    pub since: Option<String>,
+
+    /// Feature the new versions on Modrinth
+    #[clap(short, long)]
+    pub featured: bool,
+
+    /// Export the branches and show what would be published, without uploading anything
+    #[clap(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Base URL of the Modrinth API to publish to, for example a local test server
+    #[clap(long, default_value = "https://api.modrinth.com/v2")]
+    pub api_base_url: String,
+
+    /// Don't check the modpack configuration for problems before publishing
+    #[clap(long)]
+    pub no_validate: bool,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum PublishVersionType {
+    Release,
+    Beta,
+    Alpha,
+}
+
+#[derive(Parser, Debug)]
+pub struct ValidateArgs;
+
+#[derive(Parser, Debug)]
+pub struct LicensesArgs {
+    /// Branches to check. If no branches are specified, all branches will be checked.
+    pub branches: Option<Vec<String>>,
+
+    /// Write an attribution file crediting all projects and files to the overrides of the branches
+    #[clap(short, long)]
+    pub credits: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct MigrateArgs;
+
 #[derive(Parser, Debug)]
 pub struct CleanArgs;
 
@@ -369,6 +772,82 @@ pub struct DocArgs {
     /// Don't print the information about branch compatibility
     #[clap(short = 'C', long)]
     pub no_compatibility_icons: bool,
+
+    /// Only include projects in this group
+    #[clap(short, long, conflicts_with = "group_sections")]
+    pub group: Option<String>,
+
+    /// Print a separate table for every group
+    #[clap(short = 's', long)]
+    pub group_sections: bool,
+
+    /// The columns of the project table, separated by commas. Version types and categories
+    /// are requested from Modrinth
+    #[clap(short, long, value_delimiter = ',', default_value = "availability")]
+    pub columns: Vec<DocColumn>,
+
+    /// The format of the documentation
+    #[clap(short, long, default_value = "markdown")]
+    pub format: DocFormat,
+
+    /// Write the documentation to this directory instead of printing it. Required for HTML.
+    /// The other formats are saved as index.md, projects.csv or projects.json
+    #[clap(short, long, required_if_eq("format", "html"))]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum DocFormat {
+    /// A Markdown page with a table of the projects in every branch
+    Markdown,
+
+    /// A static site with an index, a page for every branch and a page for every Modrinth project,
+    /// for example for GitHub Pages
+    Html,
+
+    /// A CSV file with the project table
+    Csv,
+
+    /// A JSON file with the project table
+    Json,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum DocColumn {
+    /// Whether a branch contains the project
+    Availability,
+
+    /// The file name of the project in every branch, which usually contains its version
+    File,
+
+    /// Whether the project is used on the client, the server or both
+    #[value(alias = "env")]
+    Environment,
+
+    /// Whether the project is a release, beta or alpha version in every branch
+    VersionType,
+
+    /// The Modrinth categories of the project
+    Category,
+}
+
+#[derive(Parser, Debug)]
+pub struct SchemaArgs {
+    /// The configuration file to print the schema of
+    #[clap(required_unless_present = "out")]
+    pub file: Option<SchemaFile>,
+
+    /// Save the schema to this directory instead of printing it. If no configuration file is
+    /// given, the schemas of all configuration files are saved
+    #[clap(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum SchemaFile {
+    Modpack,
+    Branch,
+    BranchFiles,
 }
 
 #[derive(Parser, Debug)]
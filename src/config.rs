@@ -1,13 +1,19 @@
 //! Structs for configuring and managing a Packrinth modpack instance.
 
+use crate::loader_meta::LoaderMetaUrls;
 use crate::modrinth::{
-    File, MrPack, MrPackDependencies, Project, Version, extract_mrpack_overrides,
+    Env, File, GameVersions, MrPack, MrPackDependencies, Project, ProjectType, Version,
+    VersionType, compare_game_versions, extract_mrpack_overrides,
+};
+use crate::schema::ConfigSchema;
+use crate::{
+    MRPACK_INDEX_FILE_NAME, PackrinthError, PackrinthResult, ProjectTable, ProjectTableCell,
+    ProjectTableRow,
 };
-use crate::{MRPACK_INDEX_FILE_NAME, PackrinthError, PackrinthResult, ProjectTable};
 use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
@@ -19,9 +25,11 @@ use zip::write::SimpleFileOptions;
 ///
 /// Can be used for checking if the user uses the right Packrinth
 /// version for their project.
+///
+/// Older modpacks can be upgraded with [`crate::migration::migrate`].
 pub const CURRENT_PACK_FORMAT: u16 = 1;
 
-fn json_to_file<T, P>(json_value: &T, file: P) -> PackrinthResult<()>
+pub(crate) fn json_to_file<T, P>(json_value: &T, file: P) -> PackrinthResult<()>
 where
     T: ?Sized + Serialize + Debug,
     P: AsRef<Path>,
@@ -43,7 +51,7 @@ where
     Ok(())
 }
 
-fn serde_json_to_string_pretty<T>(value: &T) -> Result<String, serde_json::Error>
+pub(crate) fn serde_json_to_string_pretty<T>(value: &T) -> Result<String, serde_json::Error>
 where
     T: ?Sized + Serialize,
 {
@@ -55,20 +63,129 @@ where
     Ok(String::from_utf8_lossy(&buf).to_string())
 }
 
+fn to_json_value<T>(value: &T) -> PackrinthResult<serde_json::Value>
+where
+    T: ?Sized + Serialize,
+{
+    match serde_json::to_value(value) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(PackrinthError::FailedToSerialize {
+            error_message: error.to_string(),
+        }),
+    }
+}
+
+/// Fields of [`BranchConfig`] that are combined instead of overwritten when a branch
+/// extends another branch.
+///
+/// A branch can't remove entries from these lists, and it can't unset an [`Option`] field
+/// of the branch it extends: a `null` value is left out when the branch is saved,
+/// so the inherited value would return.
+pub const MERGED_BRANCH_CONFIG_LISTS: [&str; 3] = [
+    "acceptable_minecraft_versions",
+    "acceptable_loaders",
+    "manual_files",
+];
+
+/// Merges the JSON of a branch configuration into the JSON of the branch it extends.
+fn merge_branch_config_json(merged: &mut serde_json::Value, branch: serde_json::Value) {
+    let (Some(merged), serde_json::Value::Object(branch)) = (merged.as_object_mut(), branch) else {
+        return;
+    };
+
+    for (key, value) in branch {
+        if MERGED_BRANCH_CONFIG_LISTS.contains(&key.as_str())
+            && let Some(serde_json::Value::Array(merged_list)) = merged.get_mut(&key)
+            && let serde_json::Value::Array(list) = value
+        {
+            for item in list {
+                // Manual files with the same path replace the inherited file.
+                if let Some(path) = item.get("path")
+                    && let Some(index) =
+                        merged_list.iter().position(|x| x.get("path") == Some(path))
+                {
+                    merged_list[index] = item;
+                } else if !merged_list.contains(&item) {
+                    merged_list.push(item);
+                }
+            }
+        } else {
+            merged.insert(key, value);
+        }
+    }
+}
+
+/// Removes everything from the JSON of a branch configuration that it would inherit anyway.
+fn strip_inherited_branch_config_json(parent: &serde_json::Value, branch: &mut serde_json::Value) {
+    let (Some(parent), Some(branch)) = (parent.as_object(), branch.as_object_mut()) else {
+        return;
+    };
+
+    branch.retain(|key, value| {
+        let Some(parent_value) = parent.get(key) else {
+            return true;
+        };
+        if MERGED_BRANCH_CONFIG_LISTS.contains(&key.as_str())
+            && let (serde_json::Value::Array(list), serde_json::Value::Array(parent_list)) =
+                (&mut *value, parent_value)
+        {
+            list.retain(|item| !parent_list.contains(item));
+            !list.is_empty()
+        } else {
+            // Always keep extends, otherwise the branch would stop inheriting.
+            key == "extends" || key == "$schema" || value != parent_value
+        }
+    });
+}
+
 /// Config file at the root of the modpack directory.
 ///
 /// It is important to know that every function that modifies the modpack, DOESN'T save it to
 /// the configuration file. To do that, use [`Modpack::save`].
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Modpack {
+    /// The URL of the JSON Schema of this file, so editors can give autocompletion
+    /// and validation. Packrinth itself ignores it.
+    #[serde(rename = "$schema")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
     pub pack_format: u16,
     pub name: String,
     pub summary: String,
     pub author: String,
     pub require_all: bool,
     pub auto_dependencies: bool,
+
+    /// How to choose a version of a project if multiple versions are suitable.
+    /// Projects can set their own strategy.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection: Option<SelectionStrategy>,
+
+    /// License IDs (like `LicenseRef-All-Rights-Reserved`) that may not be in the modpack.
+    /// The `licenses` subcommand fails if a project or file has one of these licenses.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub denied_licenses: Vec<String>,
+
+    /// The ID of the Modrinth project of this modpack.
+    /// The `publish` subcommand uploads exported branches as new versions of this project.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth_project_id: Option<String>,
+
     pub branches: Vec<String>,
 
+    /// A map of project groups.
+    ///
+    /// The key is the name of the group, and the value contains settings that apply
+    /// to all projects in the group.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub groups: IndexMap<String, GroupSettings>,
+
     /// A map of added projects.
     ///
     /// The key is the Modrinth project ID (fabric-api or 3jfh38sf),
@@ -83,22 +200,90 @@ pub struct Modpack {
 }
 
 /// Settings for one project that is added to a modpack.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSettings {
     // IndexMap<Branch, Project version id>
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_overrides: Option<IndexMap<String, String>>,
 
+    /// The groups this project is in. Settings that aren't set for the project itself
+    /// are taken from its groups, in the order of this list.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+
+    /// The least stable version type that may be added for this project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<VersionType>,
+
+    /// Overrides the environment Modrinth specifies for this project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Env>,
+
+    /// How to choose a version of this project if multiple versions are suitable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection: Option<SelectionStrategy>,
+
+    #[serde(flatten)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_or_exclude: Option<IncludeOrExclude>,
+}
+
+/// Settings for a group of projects.
+///
+/// These settings apply to every project in the group, unless the project sets them itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GroupSettings {
+    /// The least stable version type that may be added for projects in this group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<VersionType>,
+
+    /// Overrides the environment Modrinth specifies for projects in this group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Env>,
+
     #[serde(flatten)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_or_exclude: Option<IncludeOrExclude>,
 }
 
+/// The preferences used to choose a version of a project if multiple versions are suitable
+/// for a branch.
+///
+/// Versions are compared with the first preference. Only if that preference doesn't prefer
+/// one of the versions, the next preference is used, and so on. The default strategy prefers
+/// the newest Minecraft version and then the newest version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct SelectionStrategy(pub Vec<SelectionPreference>);
+
+/// A preference of a [`SelectionStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionPreference {
+    /// Prefer versions that support the main Minecraft version of the branch over versions
+    /// that only support one of the acceptable Minecraft versions.
+    ExactMinecraft,
+
+    /// Prefer versions for the main mod loader of the branch over versions
+    /// that only support one of the acceptable loaders.
+    MainLoader,
+
+    /// Prefer versions that support a newer Minecraft version.
+    NewestMinecraft,
+
+    /// Prefer versions that were published later.
+    NewestDate,
+}
+
 /// Inclusions or exclusions for a project.
 ///
 /// Inclusions allow projects to ONLY be added
 /// to specific branches, while exclusions remove projects from branches.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Every entry is a [`BranchSelector`]: either the name of a branch, or a rule
+/// like `loader = neoforge` that is evaluated against the configuration of every branch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum IncludeOrExclude {
     #[serde(rename = "include")]
     Include(Vec<String>),
@@ -107,6 +292,61 @@ pub enum IncludeOrExclude {
     Exclude(Vec<String>),
 }
 
+/// A single entry in the inclusions or exclusions of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BranchSelector {
+    /// Selects the branch with exactly this name.
+    Name(String),
+
+    /// Selects all branches whose configuration matches the rule.
+    Rule(BranchRule),
+}
+
+/// A rule that selects branches based on their configuration.
+///
+/// Rules are written as `<field> <operator> <value>`, for example `loader = neoforge`,
+/// `minecraft >= 1.21` or `branch ~ "*-server"`. The value may be surrounded by double quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BranchRule {
+    pub field: BranchRuleField,
+    pub operator: BranchRuleOperator,
+    pub value: String,
+}
+
+/// The part of a branch a [`BranchRule`] looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BranchRuleField {
+    /// The name of the branch.
+    Branch,
+
+    /// The main mod loader of the branch (`mod_loader` in `branch.json`).
+    Loader,
+
+    /// The main Minecraft version of the branch (`minecraft_version` in `branch.json`).
+    Minecraft,
+}
+
+/// The comparison a [`BranchRule`] makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BranchRuleOperator {
+    /// `=` or `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`, only for Minecraft versions
+    Less,
+    /// `<=`, only for Minecraft versions
+    LessOrEqual,
+    /// `>`, only for Minecraft versions
+    Greater,
+    /// `>=`, only for Minecraft versions
+    GreaterOrEqual,
+    /// `~`, matches a pattern where `*` matches any text and `?` matches one character
+    Matches,
+    /// `!~`, the opposite of [`BranchRuleOperator::Matches`]
+    NotMatches,
+}
+
 /// The branch configuration file name.
 ///
 /// This file is intended to be edited by the user. It should also be in version control.
@@ -115,8 +355,20 @@ pub const BRANCH_CONFIG_FILE_NAME: &str = "branch.json";
 /// Configuration for a branch.
 ///
 /// This configuration is supposed to be edited by the user.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[schemars(transform = require_unless_extending)]
 pub struct BranchConfig {
+    /// The URL of the JSON Schema of this file, so editors can give autocompletion
+    /// and validation. Packrinth itself ignores it.
+    #[serde(rename = "$schema")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// The name of a branch this branch inherits its configuration from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
     pub version: String,
 
     pub minecraft_version: String,
@@ -135,16 +387,42 @@ pub struct BranchConfig {
     #[serde(default)]
     pub acceptable_loaders: Vec<Loader>,
 
+    /// The directory that data packs are added to, relative to the instance directory.
+    /// Only used for versions of data packs that don't need a mod loader, which are found
+    /// if [`Loader::Datapack`] is an acceptable loader.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datapack_directory: Option<String>,
+
+    /// The slug or ID of the Modrinth project of a mod that loads the data packs in
+    /// [`BranchConfig::datapack_directory`]. It is added as dependency of every data pack.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datapack_loader: Option<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub manual_files: Vec<File>,
 }
 
+/// Makes the required properties of a branch configuration only required if it doesn't
+/// extend another branch, because it can inherit them.
+fn require_unless_extending(schema: &mut schemars::Schema) {
+    if let Some(required) = schema.remove("required") {
+        schema.insert(
+            "if".to_string(),
+            serde_json::json!({ "required": ["extends"] }),
+        );
+        schema.insert(
+            "else".to_string(),
+            serde_json::json!({ "required": required }),
+        );
+    }
+}
+
 /// Loader that a launcher has to install with the modpack.
 ///
 /// See <https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack>
 /// at `dependencies` for more information.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum MainLoader {
     #[serde(rename = "forge")]
     Forge,
@@ -157,12 +435,16 @@ pub enum MainLoader {
 }
 
 /// All Modrinth loaders, including loaders for shader packs.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum Loader {
     // For resource packs and data packs
     #[serde(rename = "minecraft")]
     Minecraft,
 
+    // Data packs that are installed as data pack instead of as mod
+    #[serde(rename = "datapack")]
+    Datapack,
+
     // Mods
     #[serde(rename = "fabric")]
     Fabric,
@@ -238,7 +520,7 @@ pub const BRANCH_FILES_INFO: &str = "This file is managed by Packrinth and not i
 /// A configuration file for all the files for a branch.
 ///
 /// This configuration file is intended to be updated by Packrinth, not by theo
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct BranchFiles {
     info: String,
 
@@ -252,7 +534,7 @@ pub struct BranchFiles {
     pub files: Vec<File>,
 }
 /// Project for [`BranchFiles`].
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct BranchFilesProject {
     pub name: String,
 
@@ -261,6 +543,14 @@ pub struct BranchFilesProject {
     pub id: Option<String>,
 }
 
+/// Which part of a semantic version to increase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+}
+
 /// The name of the modpack configuration file.
 pub const MODPACK_CONFIG_FILE_NAME: &str = "modpack.json";
 
@@ -278,6 +568,20 @@ const GAME: &str = "minecraft";
 /// will only be copied to client instances.
 pub const OVERRIDE_DIRS: [&str; 3] = ["overrides", "server-overrides", "client-overrides"];
 
+/// The directory containing override directories that are shared by all branches.
+///
+/// When exporting a branch, the override directories in this directory are merged with
+/// the override directories of the branch. Files of the branch replace shared files with the same path.
+pub const SHARED_DIRECTORY: &str = "shared";
+
+/// The names that can't be used for branches, because Packrinth uses directories with these
+/// names in the modpack directory.
+pub const RESERVED_BRANCH_NAMES: [&str; 3] = [
+    SHARED_DIRECTORY,
+    crate::TARGET_DIRECTORY,
+    crate::migration::MIGRATION_BACKUP_DIRECTORY,
+];
+
 impl Modpack {
     /// Creates a new modpack to a directory.
     ///
@@ -365,13 +669,64 @@ impl Modpack {
         Ok(modpack)
     }
 
+    /// Looks up a project on Modrinth before it is added to the modpack.
+    ///
+    /// A project can be added by its slug or by its ID. Because both refer to the same project,
+    /// it is only accepted if neither of them is added yet. Add the project with its slug to
+    /// keep the modpack configuration consistent.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ProjectNotFound`] if the project doesn't exist on Modrinth
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the Modrinth response was invalid
+    /// - [`PackrinthError::ProjectIsModpack`] if the project is a modpack
+    /// - [`PackrinthError::UnsupportedProjectType`] if Packrinth doesn't know the project type
+    /// - [`PackrinthError::ProjectAlreadyAdded`] if the project is already added to the modpack
+    pub fn resolve_new_project(&self, project: &str) -> PackrinthResult<Project> {
+        let modrinth_project = Project::from_id(project)?;
+        match &modrinth_project.project_type {
+            ProjectType::Modpack => {
+                return Err(PackrinthError::ProjectIsModpack {
+                    project: project.to_string(),
+                });
+            }
+            ProjectType::Unknown(project_type) => {
+                return Err(PackrinthError::UnsupportedProjectType {
+                    project_type: project_type.clone(),
+                });
+            }
+            _ => {}
+        }
+
+        if let Some(added_as) = self
+            .projects
+            .keys()
+            .find(|added| **added == modrinth_project.slug || **added == modrinth_project.id)
+        {
+            return Err(PackrinthError::ProjectAlreadyAdded {
+                project: project.to_string(),
+                added_as: added_as.clone(),
+            });
+        }
+
+        Ok(modrinth_project)
+    }
+
     /// Adds projects to the modpack with optional version overrides or inclusions or exclusions.
+    ///
+    /// The projects are also added to `groups`. Groups that don't exist yet are created
+    /// without any settings.
     pub fn add_projects(
         &mut self,
         projects: &[String],
         version_overrides: &Option<IndexMap<String, String>>,
         include_or_exclude: &Option<IncludeOrExclude>,
+        groups: &[String],
     ) {
+        for group in groups {
+            self.groups.entry(group.clone()).or_default();
+        }
+
         for project in projects {
             self.projects.insert(
                 String::from(project),
@@ -379,17 +734,105 @@ impl Modpack {
                     ProjectSettings {
                         version_overrides: version_overrides.clone(),
                         include_or_exclude: include_or_exclude.clone(),
+                        groups: groups.to_vec(),
+                        ..ProjectSettings::default()
                     }
                 } else {
                     ProjectSettings {
-                        version_overrides: None,
-                        include_or_exclude: None,
+                        groups: groups.to_vec(),
+                        ..ProjectSettings::default()
                     }
                 },
             );
         }
     }
 
+    /// Adds a project to groups.
+    ///
+    /// Groups that don't exist yet are created without any settings.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ProjectIsNotAdded`] if given project isn't added to the modpack
+    pub fn add_project_to_groups(
+        &mut self,
+        project: &str,
+        groups: &[String],
+    ) -> PackrinthResult<()> {
+        let Some(project_settings) = self.projects.get_mut(project) else {
+            return Err(PackrinthError::ProjectIsNotAdded {
+                project: project.to_string(),
+            });
+        };
+
+        for group in groups {
+            if !project_settings.groups.contains(group) {
+                project_settings.groups.push(group.clone());
+            }
+            self.groups.entry(group.clone()).or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Removes a project from groups.
+    ///
+    /// The groups themselves are kept, even if no projects are in them anymore.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ProjectIsNotAdded`] if given project isn't added to the modpack
+    pub fn remove_project_from_groups(
+        &mut self,
+        project: &str,
+        groups: &[String],
+    ) -> PackrinthResult<()> {
+        let Some(project_settings) = self.projects.get_mut(project) else {
+            return Err(PackrinthError::ProjectIsNotAdded {
+                project: project.to_string(),
+            });
+        };
+
+        project_settings.groups.retain(|x| !groups.contains(x));
+        Ok(())
+    }
+
+    /// Returns the settings of a project with the settings of its groups applied.
+    ///
+    /// Settings set for the project itself always win. Otherwise, the setting of the first group
+    /// (in the order of [`ProjectSettings::groups`]) that sets it is used. If the project doesn't
+    /// have a selection strategy, the selection strategy of the modpack is used.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::GroupDoesNotExist`] if the project is in a group that isn't defined
+    pub fn resolve_project_settings(
+        &self,
+        project_settings: &ProjectSettings,
+    ) -> PackrinthResult<ProjectSettings> {
+        let mut resolved_settings = project_settings.clone();
+        for group in &project_settings.groups {
+            let Some(group_settings) = self.groups.get(group) else {
+                return Err(PackrinthError::GroupDoesNotExist {
+                    group: group.clone(),
+                });
+            };
+
+            if resolved_settings.channel.is_none() {
+                resolved_settings.channel = group_settings.channel;
+            }
+            if resolved_settings.env.is_none() {
+                resolved_settings.env = group_settings.env;
+            }
+            if resolved_settings.include_or_exclude.is_none() {
+                resolved_settings
+                    .include_or_exclude
+                    .clone_from(&group_settings.include_or_exclude);
+            }
+        }
+        if resolved_settings.selection.is_none() {
+            resolved_settings.selection.clone_from(&self.selection);
+        }
+        Ok(resolved_settings)
+    }
+
     /// Adds a version override to a project added to this modpack.
     ///
     /// # Errors
@@ -651,8 +1094,10 @@ impl Modpack {
     /// If the branch already exists, it just returns the existing branch.
     ///
     /// # Errors
+    /// - [`PackrinthError::BranchNameIsReserved`] if the name is in [`RESERVED_BRANCH_NAMES`]
     /// - [`PackrinthError::FailedToCreateDir`] if the creation of the branch directory failed
     pub fn new_branch(&mut self, name: &str) -> PackrinthResult<BranchConfig> {
+        check_branch_name(name)?;
         if !self.branches.contains(&name.to_string()) {
             self.branches.push(name.to_string());
         }
@@ -669,19 +1114,85 @@ impl Modpack {
         BranchConfig::from_directory(&self.directory, name)
     }
 
+    /// Creates a new branch that extends another branch.
+    ///
+    /// This works like [`Self::new_branch`], but the configuration file of a new branch
+    /// only contains a reference to the extended branch, so it inherits everything from it.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::BranchNameIsReserved`] if the name is in [`RESERVED_BRANCH_NAMES`]
+    /// - [`PackrinthError::FailedToCreateDir`] if the creation of the branch directory failed
+    /// - [`PackrinthError::BranchDoesNotExist`] if the branch to extend doesn't exist
+    pub fn new_branch_extending(
+        &mut self,
+        name: &str,
+        extends: &str,
+    ) -> PackrinthResult<BranchConfig> {
+        check_branch_name(name)?;
+        if !self.branches.contains(&extends.to_string()) {
+            return Err(PackrinthError::BranchDoesNotExist {
+                branch: extends.to_string(),
+                error_message: "it isn't declared in the modpack config file".to_string(),
+            });
+        }
+
+        let branch_dir = self.directory.join(name);
+        if let Ok(exists) = fs::exists(&branch_dir)
+            && !exists
+        {
+            if let Err(error) = fs::create_dir(&branch_dir) {
+                return Err(PackrinthError::FailedToCreateDir {
+                    dir_to_create: branch_dir.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+            json_to_file(
+                &serde_json::json!({
+                    "$schema": ConfigSchema::BranchConfig.url(),
+                    "extends": extends,
+                }),
+                branch_dir.join(BRANCH_CONFIG_FILE_NAME),
+            )?;
+        }
+
+        self.new_branch(name)
+    }
+
     /// Removes branches from the modpack.
     ///
-    /// It also removes the branch directories. If something goes wrong during this process,
-    /// all errors get ignored.
+    /// It also removes the branch directories and removes the branches from the inclusions,
+    /// exclusions and version overrides of all projects and groups.
     ///
     /// # Errors
+    /// - [`PackrinthError::BranchIsExtended`] if another branch that isn't removed extends
+    ///   one of the branches
     /// - [`PackrinthError::FailedToRemoveDir`] if removing the branch directory failed
     pub fn remove_branches(&mut self, branch_names: &Vec<String>) -> PackrinthResult<()> {
+        for branch in &self.branches {
+            if branch_names.contains(branch) {
+                continue;
+            }
+            if let Ok(branch_json) = BranchConfig::read_json(&self.directory, branch, false)
+                && let Some(extends) = branch_json
+                    .get("extends")
+                    .and_then(serde_json::Value::as_str)
+                && branch_names
+                    .iter()
+                    .any(|branch_name| branch_name == extends)
+            {
+                return Err(PackrinthError::BranchIsExtended {
+                    branch: extends.to_string(),
+                    extended_by: branch.clone(),
+                });
+            }
+        }
+
         for branch_name in branch_names {
             let branch_path = self.directory.join(branch_name);
 
             if self.branches.contains(branch_name) {
                 self.branches.retain(|x| x != branch_name);
+                self.change_branch_references(branch_name, &BranchReferenceChange::Remove);
                 if let Ok(exists) = fs::exists(&branch_path)
                     && exists
                     && let Err(error) = fs::remove_dir_all(&branch_path)
@@ -697,80 +1208,315 @@ impl Modpack {
         Ok(())
     }
 
-    /// Saves the modpack to the configuration file.
-    ///
-    /// # Errors
-    /// - [`PackrinthError::FailedToSerialize`] if serialising this type to a JSON failed
-    /// - [`PackrinthError::FailedToWriteFile`] if writing the JSON to a file failed
-    pub fn save(&self) -> PackrinthResult<()> {
-        json_to_file(self, &self.modpack_config_path)
-    }
-
-    /// Exports a branch to a `.mrpack` file.
+    /// Renames a branch.
     ///
-    /// The path of the exported modpack will be a file in the branch directory.
-    /// The full path gets returned if exporting was successful.
+    /// The branch directory is moved, including the configuration, branch files and overrides.
+    /// All inclusions, exclusions and version overrides that refer to the branch by name, and
+    /// branches that extend the branch, are updated to use the new name.
     ///
     /// # Errors
-    /// - [`PackrinthError::FailedToSerialize`] if serializing the main manifest goes wrong
+    /// - [`PackrinthError::BranchDoesNotExist`] if the branch to rename isn't declared
+    /// - [`PackrinthError::BranchAlreadyExists`] if a branch with the new name already exists
+    /// - [`PackrinthError::BranchNameIsReserved`] if the new name is in [`RESERVED_BRANCH_NAMES`]
+    /// - [`PackrinthError::FailedToRenameDir`] if moving the branch directory failed
     ///
-    /// Other errors may occur while creating the zip file:
-    /// - [`PackrinthError::FailedToInitializeFileType`]
-    /// - [`PackrinthError::FailedToStartZipFile`]
-    /// - [`PackrinthError::FailedToWriteToZip`]
-    /// - [`PackrinthError::FailedToGetWalkDirEntry`]
-    /// - [`PackrinthError::FailedToStripPath`]
-    /// - [`PackrinthError::FailedToCopyIntoBuffer`]
-    /// - [`PackrinthError::FailedToAddZipDir`]
-    /// - [`PackrinthError::FailedToFinishZip`]
-    // Allow because it's hard to split this function up in other functions
-    // without them having lots of parameters.
-    #[allow(clippy::too_many_lines)]
-    pub fn export_branch(&self, branch: &str) -> PackrinthResult<PathBuf> {
-        let branch_config = BranchConfig::from_directory(&self.directory, branch)?;
-        let branch_files = BranchFiles::from_directory(&self.directory, branch)?;
+    /// Errors from updating the configuration files of branches that extend the branch
+    /// are propagated upwards.
+    pub fn rename_branch(&mut self, branch: &str, new_branch: &str) -> PackrinthResult<()> {
+        self.check_branch_can_be_duplicated(branch, new_branch)?;
 
-        let mrpack_file_name = format!("{}_{}.mrpack", self.name, branch_config.version);
         let branch_dir = self.directory.join(branch);
-        let target_dir = self.directory.join(crate::TARGET_DIRECTORY).join(branch);
-        if let Err(error) = fs::create_dir_all(&target_dir) {
-            return Err(PackrinthError::FailedToCreateDir {
-                dir_to_create: target_dir.display().to_string(),
+        let new_branch_dir = self.directory.join(new_branch);
+        if let Err(error) = fs::rename(&branch_dir, &new_branch_dir) {
+            return Err(PackrinthError::FailedToRenameDir {
+                from: branch_dir.display().to_string(),
+                to: new_branch_dir.display().to_string(),
                 error_message: error.to_string(),
             });
         }
-        let mrpack_path = target_dir.join(&mrpack_file_name);
-
-        let mrpack = MrPack {
-            format_version: MODRINTH_PACK_FORMAT,
-            game: GAME.to_string(),
-            version_id: branch_config.version.clone(),
-            name: self.name.clone(),
-            summary: Some(self.summary.clone()),
-            files: branch_files.files,
-            dependencies: Self::create_dependencies(branch_config)?,
-        };
 
-        let mrpack_json = match serde_json_to_string_pretty(&mrpack) {
-            Ok(mrpack_json) => mrpack_json,
-            Err(error) => {
-                return Err(PackrinthError::FailedToSerialize {
-                    error_message: error.to_string(),
-                });
+        for declared_branch in &mut self.branches {
+            if declared_branch == branch {
+                *declared_branch = new_branch.to_string();
             }
-        };
-        let options = SimpleFileOptions::default();
-        let zip_file = match fs::File::create(&mrpack_path) {
-            Ok(zip_file) => zip_file,
-            Err(error) => {
-                return Err(PackrinthError::FailedToInitializeFileType {
-                    file_to_create: mrpack_path.display().to_string(),
-                    error_message: error.to_string(),
-                });
+        }
+
+        for other_branch in &self.branches {
+            let mut branch_json = BranchConfig::read_json(&self.directory, other_branch, true)?;
+            if branch_json
+                .get("extends")
+                .and_then(serde_json::Value::as_str)
+                == Some(branch)
+            {
+                branch_json["extends"] = serde_json::Value::String(new_branch.to_string());
+                json_to_file(
+                    &branch_json,
+                    self.directory
+                        .join(other_branch)
+                        .join(BRANCH_CONFIG_FILE_NAME),
+                )?;
             }
-        };
+        }
 
-        let mut zip = ZipWriter::new(zip_file);
+        self.change_branch_references(branch, &BranchReferenceChange::Rename(new_branch));
+        Ok(())
+    }
+
+    /// Copies a branch to a new branch.
+    ///
+    /// The whole branch directory is copied, including the configuration, branch files and
+    /// overrides. The new branch is added to every inclusion, exclusion and version override
+    /// that refers to the copied branch by name, so the projects of both branches stay the same.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::BranchDoesNotExist`] if the branch to copy isn't declared
+    /// - [`PackrinthError::BranchAlreadyExists`] if a branch with the new name already exists
+    /// - [`PackrinthError::BranchNameIsReserved`] if the new name is in [`RESERVED_BRANCH_NAMES`]
+    /// - [`PackrinthError::FailedToGetWalkDirEntry`] if reading the branch directory failed
+    /// - [`PackrinthError::FailedToStripPath`] if a path in the branch directory is invalid
+    /// - [`PackrinthError::FailedToCreateDir`] if creating a directory in the new branch failed
+    /// - [`PackrinthError::FailedToCopyFile`] if copying a file to the new branch failed
+    pub fn copy_branch(&mut self, branch: &str, new_branch: &str) -> PackrinthResult<()> {
+        self.check_branch_can_be_duplicated(branch, new_branch)?;
+
+        copy_directory(
+            &self.directory.join(branch),
+            &self.directory.join(new_branch),
+            true,
+        )?;
+
+        self.branches.push(new_branch.to_string());
+        self.change_branch_references(branch, &BranchReferenceChange::Copy(new_branch));
+        Ok(())
+    }
+
+    /// Ports a branch to another Minecraft version by copying it to a new branch.
+    ///
+    /// The new branch gets the given main Minecraft version and no acceptable Minecraft versions,
+    /// because those belong to the old version. Version overrides are pinned to a Minecraft
+    /// version, so they aren't copied to the new branch. The branch files of the old branch
+    /// are removed from the new branch, because they have to be updated for the new version.
+    /// The branch that is ported isn't changed.
+    ///
+    /// If the branch extends another branch, the new branch doesn't: the extended branch
+    /// belongs to the old version too, and its acceptable Minecraft versions would be inherited
+    /// again. Instead, the new branch gets the full configuration and the inherited overrides.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToRemoveFile`] if removing the copied branch files failed
+    ///
+    /// Other errors come from [`Self::copy_branch`], [`Self::override_layers`],
+    /// [`BranchConfig::from_directory`] and [`BranchConfig::save`], which are called
+    /// in this function and propagated upwards.
+    pub fn port_branch(
+        &mut self,
+        branch: &str,
+        new_branch: &str,
+        minecraft_version: &str,
+    ) -> PackrinthResult<BranchConfig> {
+        self.copy_branch(branch, new_branch)?;
+        for project_settings in self.projects.values_mut() {
+            change_version_override_references(
+                &mut project_settings.version_overrides,
+                new_branch,
+                &BranchReferenceChange::Remove,
+            );
+        }
+
+        let branch_files_path = self.directory.join(new_branch).join(BRANCH_FILES_FILE_NAME);
+        if let Ok(exists) = fs::exists(&branch_files_path)
+            && exists
+            && let Err(error) = fs::remove_file(&branch_files_path)
+        {
+            return Err(PackrinthError::FailedToRemoveFile {
+                file_to_remove: branch_files_path.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+
+        let mut branch_config = BranchConfig::from_directory(&self.directory, new_branch)?;
+        if branch_config.extends.is_some() {
+            let new_branch_dir = self.directory.join(new_branch);
+            // The shared directory is still used, and the branch itself was already copied.
+            // Start with the most specific layer, because existing files aren't overwritten.
+            let override_layers = self.override_layers(new_branch)?;
+            for layer_dir in override_layers[1..override_layers.len() - 1].iter().rev() {
+                for override_dir in OVERRIDE_DIRS {
+                    if layer_dir.join(override_dir).exists() {
+                        copy_directory(
+                            &layer_dir.join(override_dir),
+                            &new_branch_dir.join(override_dir),
+                            false,
+                        )?;
+                    }
+                }
+            }
+            branch_config.extends = None;
+        }
+        branch_config.minecraft_version = minecraft_version.to_string();
+        branch_config.acceptable_minecraft_versions = Vec::new();
+        branch_config.save(&self.directory, new_branch)?;
+        Ok(branch_config)
+    }
+
+    /// Checks that `branch` exists and that `new_branch` can be created from it.
+    fn check_branch_can_be_duplicated(
+        &self,
+        branch: &str,
+        new_branch: &str,
+    ) -> PackrinthResult<()> {
+        if !self.branches.contains(&branch.to_string()) {
+            return Err(PackrinthError::BranchDoesNotExist {
+                branch: branch.to_string(),
+                error_message: "it isn't declared in the modpack config file".to_string(),
+            });
+        }
+
+        if self.branches.contains(&new_branch.to_string())
+            || fs::exists(self.directory.join(new_branch)).unwrap_or(true)
+        {
+            return Err(PackrinthError::BranchAlreadyExists {
+                branch: new_branch.to_string(),
+            });
+        }
+
+        check_branch_name(new_branch)
+    }
+
+    /// Updates every reference to a branch in the settings of projects and groups.
+    fn change_branch_references(&mut self, branch: &str, change: &BranchReferenceChange) {
+        for project_settings in self.projects.values_mut() {
+            change_include_or_exclude_references(
+                &mut project_settings.include_or_exclude,
+                branch,
+                change,
+            );
+            change_version_override_references(
+                &mut project_settings.version_overrides,
+                branch,
+                change,
+            );
+        }
+        for group_settings in self.groups.values_mut() {
+            change_include_or_exclude_references(
+                &mut group_settings.include_or_exclude,
+                branch,
+                change,
+            );
+        }
+    }
+
+    /// Returns the branches of this modpack that an inclusion or exclusion entry currently selects.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::InvalidBranchRule`] if the entry is an invalid rule
+    ///
+    /// Other errors come from [`BranchConfig::from_directory`], which is called in this function
+    /// and propagated upwards.
+    pub fn branches_selected_by(
+        &self,
+        selector: &str,
+        game_versions: Option<&GameVersions>,
+    ) -> PackrinthResult<Vec<String>> {
+        let selector = BranchSelector::parse(selector)?;
+        let mut selected_branches = Vec::new();
+        for branch in &self.branches {
+            let branch_config = BranchConfig::from_directory(&self.directory, branch)?;
+            if selector.selects(branch, &branch_config, game_versions) {
+                selected_branches.push(branch.clone());
+            }
+        }
+        Ok(selected_branches)
+    }
+
+    /// Saves the modpack to the configuration file.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serialising this type to a JSON failed
+    /// - [`PackrinthError::FailedToWriteFile`] if writing the JSON to a file failed
+    pub fn save(&self) -> PackrinthResult<()> {
+        json_to_file(self, &self.modpack_config_path)
+    }
+
+    /// Returns the directories that contain the override directories of a branch.
+    ///
+    /// The shared directory comes first, then all branches this branch inherits from
+    /// and finally the branch itself. Files from later layers replace files from earlier ones.
+    ///
+    /// # Errors
+    /// - Errors of [`BranchConfig::inheritance_chain`]
+    pub fn override_layers(&self, branch: &str) -> PackrinthResult<Vec<PathBuf>> {
+        let mut override_layers = vec![self.directory.join(SHARED_DIRECTORY)];
+        for inherited_branch in BranchConfig::inheritance_chain(&self.directory, branch)? {
+            override_layers.push(self.directory.join(inherited_branch));
+        }
+        Ok(override_layers)
+    }
+
+    /// Exports a branch to a `.mrpack` file.
+    ///
+    /// The path of the exported modpack will be a file in the branch directory.
+    /// The full path gets returned if exporting was successful.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serializing the main manifest goes wrong
+    ///
+    /// Other errors may occur while creating the zip file:
+    /// - [`PackrinthError::FailedToInitializeFileType`]
+    /// - [`PackrinthError::FailedToStartZipFile`]
+    /// - [`PackrinthError::FailedToWriteToZip`]
+    /// - [`PackrinthError::FailedToGetWalkDirEntry`]
+    /// - [`PackrinthError::FailedToStripPath`]
+    /// - [`PackrinthError::FailedToCopyIntoBuffer`]
+    /// - [`PackrinthError::FailedToAddZipDir`]
+    /// - [`PackrinthError::FailedToFinishZip`]
+    // Allow because it's hard to split this function up in other functions
+    // without them having lots of parameters.
+    #[allow(clippy::too_many_lines)]
+    pub fn export_branch(&self, branch: &str) -> PackrinthResult<PathBuf> {
+        let branch_config = BranchConfig::from_directory(&self.directory, branch)?;
+        let branch_files = BranchFiles::from_directory(&self.directory, branch)?;
+
+        let mrpack_path = self.exported_mrpack_path(branch, &branch_config);
+        if let Some(target_dir) = mrpack_path.parent()
+            && let Err(error) = fs::create_dir_all(target_dir)
+        {
+            return Err(PackrinthError::FailedToCreateDir {
+                dir_to_create: target_dir.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+
+        let mrpack = MrPack {
+            format_version: MODRINTH_PACK_FORMAT,
+            game: GAME.to_string(),
+            version_id: branch_config.version.clone(),
+            name: self.name.clone(),
+            summary: Some(self.summary.clone()),
+            files: branch_files.files,
+            dependencies: Self::create_dependencies(branch_config)?,
+        };
+
+        let mrpack_json = match serde_json_to_string_pretty(&mrpack) {
+            Ok(mrpack_json) => mrpack_json,
+            Err(error) => {
+                return Err(PackrinthError::FailedToSerialize {
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let options = SimpleFileOptions::default();
+        let zip_file = match fs::File::create(&mrpack_path) {
+            Ok(zip_file) => zip_file,
+            Err(error) => {
+                return Err(PackrinthError::FailedToInitializeFileType {
+                    file_to_create: mrpack_path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        };
+
+        let mut zip = ZipWriter::new(zip_file);
         if let Err(error) = zip.start_file(MRPACK_INDEX_FILE_NAME, options) {
             return Err(PackrinthError::FailedToStartZipFile {
                 file_to_start: MRPACK_INDEX_FILE_NAME.to_string(),
@@ -787,77 +1533,87 @@ impl Modpack {
         // If some items are skipped in the loop, this is set to Err, and it will be returned at the end.
         let mut result = Ok(());
 
-        // Loop every file/dir in the override dirs
-        for override_dir in OVERRIDE_DIRS {
-            let override_dir_path = branch_dir.join(override_dir);
+        let override_layers = self.override_layers(branch)?;
 
-            // Skip override dir if it doesn't exist.
-            if let Ok(exists) = fs::exists(&override_dir_path)
-                && !exists
-            {
-                continue;
-            }
+        // Map of the path in the zip to the actual path on the file system.
+        let mut zip_entries: IndexMap<String, PathBuf> = IndexMap::new();
+        for layer_dir in &override_layers {
+            // Loop every file/dir in the override dirs
+            for override_dir in OVERRIDE_DIRS {
+                let override_dir_path = layer_dir.join(override_dir);
 
-            for entry in WalkDir::new(override_dir_path) {
-                let entry = match entry {
-                    Ok(entry) => entry,
-                    Err(error) => {
-                        result = Err(PackrinthError::FailedToGetWalkDirEntry {
-                            error_message: error.to_string(),
-                        });
-                        continue;
-                    }
-                };
-                // The actual path on the file system
-                let path = entry.path();
-                // The path the file will be in the zip (/ being the root of the zip)
-                let zip_path = if let Ok(stripped_path) = path.strip_prefix(&branch_dir)
-                    && let Some(zip_path) = stripped_path.to_str()
+                // Skip override dir if it doesn't exist.
+                if let Ok(exists) = fs::exists(&override_dir_path)
+                    && !exists
                 {
-                    zip_path
-                } else {
-                    result = Err(PackrinthError::FailedToStripPath {
-                        path: path.display().to_string(),
-                    });
                     continue;
-                };
+                }
 
-                if path.is_file() {
-                    if let Err(error) = zip.start_file(zip_path, options) {
-                        result = Err(PackrinthError::FailedToStartZipFile {
-                            file_to_start: zip_path.to_string(),
-                            error_message: error.to_string(),
-                        });
-                        continue;
-                    }
-                    let mut buffer = Vec::new();
-                    let mut original_file = match fs::File::open(path) {
-                        Ok(file) => file,
+                for entry in WalkDir::new(override_dir_path) {
+                    let entry = match entry {
+                        Ok(entry) => entry,
                         Err(error) => {
-                            result = Err(PackrinthError::FailedToInitializeFileType {
-                                file_to_create: path.display().to_string(),
+                            result = Err(PackrinthError::FailedToGetWalkDirEntry {
                                 error_message: error.to_string(),
                             });
                             continue;
                         }
                     };
-                    if let Err(_error) = io::copy(&mut original_file, &mut buffer) {
-                        result = Err(PackrinthError::FailedToCopyIntoBuffer);
+                    // The path the file will be in the zip (/ being the root of the zip)
+                    let zip_path = if let Ok(stripped_path) = entry.path().strip_prefix(layer_dir)
+                        && let Some(zip_path) = stripped_path.to_str()
+                    {
+                        zip_path.to_string()
+                    } else {
+                        result = Err(PackrinthError::FailedToStripPath {
+                            path: entry.path().display().to_string(),
+                        });
                         continue;
-                    }
-                    if let Err(error) = zip.write_all(&buffer) {
-                        result = Err(PackrinthError::FailedToWriteToZip {
-                            to_write: String::from_utf8_lossy(&buffer).to_string(),
+                    };
+
+                    zip_entries.insert(zip_path, entry.path().to_path_buf());
+                }
+            }
+        }
+
+        for (zip_path, path) in &zip_entries {
+            let zip_path = zip_path.as_str();
+
+            if path.is_file() {
+                if let Err(error) = zip.start_file(zip_path, options) {
+                    result = Err(PackrinthError::FailedToStartZipFile {
+                        file_to_start: zip_path.to_string(),
+                        error_message: error.to_string(),
+                    });
+                    continue;
+                }
+                let mut buffer = Vec::new();
+                let mut original_file = match fs::File::open(path) {
+                    Ok(file) => file,
+                    Err(error) => {
+                        result = Err(PackrinthError::FailedToInitializeFileType {
+                            file_to_create: path.display().to_string(),
                             error_message: error.to_string(),
                         });
+                        continue;
                     }
-                } else if path.is_dir()
-                    && let Err(_error) = zip.add_directory(zip_path, options)
-                {
-                    result = Err(PackrinthError::FailedToAddZipDir {
-                        zip_dir_path: zip_path.to_string(),
+                };
+                if let Err(_error) = io::copy(&mut original_file, &mut buffer) {
+                    result = Err(PackrinthError::FailedToCopyIntoBuffer);
+                    continue;
+                }
+                if let Err(error) = zip.write_all(&buffer) {
+                    result = Err(PackrinthError::FailedToWriteToZip {
+                        to_write: String::from_utf8_lossy(&buffer).to_string(),
+                        error_message: error.to_string(),
                     });
                 }
+            } else if path.is_dir()
+                && let Err(_error) = zip.add_directory(zip_path, options)
+            {
+                result = Err(PackrinthError::FailedToAddZipDir {
+                    zip_dir_path: zip_path.to_string(),
+                });
             }
         }
 
@@ -871,48 +1627,78 @@ impl Modpack {
         }
     }
 
+    /// Returns the path [`Self::export_branch`] exports a branch to. The file only exists
+    /// if the branch has been exported with its current version.
+    #[must_use]
+    pub fn exported_mrpack_path(&self, branch: &str, branch_config: &BranchConfig) -> PathBuf {
+        self.directory
+            .join(crate::TARGET_DIRECTORY)
+            .join(branch)
+            .join(format!("{}_{}.mrpack", self.name, branch_config.version))
+    }
+
     /// Generates a project table showing the distribution of projects across branches.
     ///
+    /// Files from Modrinth are matched by their project, and other files by their path.
+    /// The branches are in the order of [`Modpack::branches`] and the rows are sorted by name.
+    ///
     /// # Errors
     /// The only possible errors come from [`BranchFiles::from_directory`], which
     /// is called in this function and propagated upwards.
     pub fn generate_project_table(&self) -> PackrinthResult<ProjectTable> {
-        let mut column_names = vec!["Name".to_string()];
-        // project, map: branch, whether it has the project
-        let mut project_map: HashMap<BranchFilesProject, HashMap<String, Option<()>>> =
-            HashMap::new();
+        // Rows with the Modrinth project ID or the path of the file as key.
+        let mut rows: Vec<(String, ProjectTableRow)> = Vec::new();
 
-        for branch in &self.branches {
-            column_names.push(branch.clone());
+        for (branch_index, branch) in self.branches.iter().enumerate() {
             // Even tough we are in a loop, we want to abort the action if something goes wrong
             // here, to avoid incorrect docs.
             let branch_files = BranchFiles::from_directory(&self.directory, branch)?;
 
-            for project in &branch_files.projects {
-                // Vector in hashmap that shows which branches are compatible with a project.
-                if let Some(branch_map) = project_map.get_mut(project) {
-                    if branch_map.get(branch).is_none() {
-                        branch_map.insert(branch.clone(), Some(()));
-                    }
+            for file in &branch_files.files {
+                let modrinth_id = file.modrinth_project_id();
+                let key = modrinth_id.unwrap_or(&file.path);
+                let row_index = if let Some(row_index) = rows.iter().position(|(k, _)| k == key) {
+                    row_index
                 } else {
-                    let mut branch_map = HashMap::new();
-                    branch_map.insert(branch.clone(), Some(()));
-                    project_map.insert(project.clone(), branch_map);
-                }
-            }
-        }
-
-        for project in &mut project_map {
-            for branch in &self.branches {
-                if project.1.get(branch).is_none() {
-                    project.1.insert(branch.clone(), None);
-                }
+                    let project = modrinth_id.and_then(|id| {
+                        branch_files
+                            .projects
+                            .iter()
+                            .find(|project| project.id.as_deref() == Some(id))
+                    });
+                    rows.push((
+                        key.to_string(),
+                        ProjectTableRow {
+                            name: project.map_or_else(
+                                || file.file_name().to_string(),
+                                |project| project.name.clone(),
+                            ),
+                            modrinth_id: modrinth_id.map(ToString::to_string),
+                            groups: Vec::new(),
+                            categories: Vec::new(),
+                            cells: vec![None; self.branches.len()],
+                        },
+                    ));
+                    rows.len() - 1
+                };
+                rows[row_index].1.cells[branch_index] = Some(ProjectTableCell {
+                    file_name: file.file_name().to_string(),
+                    version_id: file.modrinth_version_id().map(ToString::to_string),
+                    env: file.env,
+                    version_type: None,
+                });
             }
         }
 
+        let mut rows: Vec<ProjectTableRow> = rows.into_iter().map(|(_key, row)| row).collect();
+        rows.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.modrinth_id.cmp(&b.modrinth_id))
+        });
         Ok(ProjectTable {
-            column_names,
-            project_map,
+            branches: self.branches.clone(),
+            rows,
         })
     }
 
@@ -1000,13 +1786,8 @@ impl Modpack {
                 && (!self.projects.contains_key(&version.project_id)
                     || !self.projects.contains_key(&project.slug))
             {
-                self.projects.insert(
-                    project.slug.clone(),
-                    ProjectSettings {
-                        version_overrides: None,
-                        include_or_exclude: None,
-                    },
-                );
+                self.projects
+                    .insert(project.slug.clone(), ProjectSettings::default());
             }
 
             f(project.slug);
@@ -1059,13 +1840,18 @@ impl Modpack {
 impl Default for Modpack {
     fn default() -> Self {
         Self {
+            schema: Some(ConfigSchema::Modpack.url()),
             pack_format: CURRENT_PACK_FORMAT,
             name: "My Modrinth modpack".to_string(),
             summary: "Short summary for this modpack".to_string(),
             author: "John Doe".to_string(),
             require_all: false,
             auto_dependencies: true,
+            selection: None,
+            denied_licenses: Vec::default(),
+            modrinth_project_id: None,
             branches: Vec::default(),
+            groups: IndexMap::default(),
             projects: IndexMap::default(),
             directory: PathBuf::default(),
             modpack_config_path: PathBuf::default(),
@@ -1076,44 +1862,130 @@ impl Default for Modpack {
 impl BranchConfig {
     /// Gets a branch configuration type from a directory and name.
     ///
+    /// If the branch extends another branch, the configuration of the other branch is merged
+    /// with this one. Values set in this branch win, except for the fields in
+    /// [`MERGED_BRANCH_CONFIG_LISTS`], which are combined.
+    ///
     /// # Errors
     /// - [`PackrinthError::FailedToParseConfigJson`] if the branch configuration file was invalid
     /// - [`PackrinthError::FailedToReadToString`] if reading the configuration file failed
     /// - [`PackrinthError::DirectoryExpected`] if the given directory is not a directory
-    /// - [`PackrinthError::BranchDoesNotExist`] if the branch doesn't exist
+    /// - [`PackrinthError::BranchDoesNotExist`] if the branch (or a branch it extends) doesn't exist
+    /// - [`PackrinthError::BranchInheritanceCycle`] if the branch extends itself, directly or indirectly
     pub fn from_directory(directory: &Path, name: &str) -> PackrinthResult<Self> {
+        Self::read_merged(directory, name, true)
+    }
+
+    /// Gets a branch configuration like [`Self::from_directory`], but without writing anything.
+    ///
+    /// If the configuration file of the branch or a branch it extends doesn't exist, an error
+    /// is returned instead of creating a default configuration file.
+    ///
+    /// # Errors
+    /// The same errors as [`Self::from_directory`] can occur.
+    pub fn from_existing_directory(directory: &Path, name: &str) -> PackrinthResult<Self> {
+        Self::read_merged(directory, name, false)
+    }
+
+    fn read_merged(directory: &Path, name: &str, create_missing: bool) -> PackrinthResult<Self> {
+        let mut merged_json = serde_json::Value::Object(serde_json::Map::new());
+        for (_branch, branch_json) in Self::read_inheritance_chain(directory, name, create_missing)?
+        {
+            merge_branch_config_json(&mut merged_json, branch_json);
+        }
+
+        match serde_json::from_value(merged_json) {
+            Ok(branch_config) => Ok(branch_config),
+            Err(error) => Err(PackrinthError::FailedToParseConfigJson {
+                config_path: directory
+                    .join(name)
+                    .join(BRANCH_CONFIG_FILE_NAME)
+                    .display()
+                    .to_string(),
+                error_message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Returns the names of all branches a branch inherits from, starting with the branch
+    /// that doesn't extend any other branch and ending with the branch itself.
+    ///
+    /// # Errors
+    /// The same errors as [`Self::from_directory`] can occur.
+    pub fn inheritance_chain(directory: &Path, name: &str) -> PackrinthResult<Vec<String>> {
+        Ok(Self::read_inheritance_chain(directory, name, true)?
+            .into_iter()
+            .map(|(branch, _branch_json)| branch)
+            .collect())
+    }
+
+    fn read_inheritance_chain(
+        directory: &Path,
+        name: &str,
+        create_missing: bool,
+    ) -> PackrinthResult<Vec<(String, serde_json::Value)>> {
+        let mut chain = vec![(
+            name.to_string(),
+            Self::read_json(directory, name, create_missing)?,
+        )];
+        while let Some(parent) = chain[chain.len() - 1]
+            .1
+            .get("extends")
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string)
+        {
+            if chain.iter().any(|(branch, _)| *branch == parent) {
+                return Err(PackrinthError::BranchInheritanceCycle {
+                    branch: name.to_string(),
+                });
+            }
+            let parent_json = Self::read_json(directory, &parent, create_missing)?;
+            chain.push((parent, parent_json));
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Reads the configuration file of one branch, without merging anything.
+    /// If `create_missing` is `true`, a missing file is created with the default configuration.
+    fn read_json(
+        directory: &Path,
+        name: &str,
+        create_missing: bool,
+    ) -> PackrinthResult<serde_json::Value> {
         let branch_dir = directory.join(name);
         match fs::metadata(&branch_dir) {
             Ok(metadata) => {
                 if metadata.is_dir() {
                     let branch_config_path = branch_dir.join(BRANCH_CONFIG_FILE_NAME);
-                    let branch_config = match fs::read_to_string(&branch_config_path) {
-                        Ok(contents) => {
-                            let branch_config: Self = match serde_json::from_str(&contents) {
-                                Ok(contents) => contents,
-                                Err(error) => {
-                                    return Err(PackrinthError::FailedToParseConfigJson {
-                                        config_path: branch_config_path.display().to_string(),
-                                        error_message: error.to_string(),
-                                    });
-                                }
-                            };
-                            branch_config
-                        }
+                    match fs::read_to_string(&branch_config_path) {
+                        Ok(contents) => match serde_json::from_str(&contents) {
+                            Ok(serde_json::Value::Object(branch_json)) => {
+                                Ok(serde_json::Value::Object(branch_json))
+                            }
+                            Ok(_) => Err(PackrinthError::FailedToParseConfigJson {
+                                config_path: branch_config_path.display().to_string(),
+                                error_message: "expected a JSON object".to_string(),
+                            }),
+                            Err(error) => Err(PackrinthError::FailedToParseConfigJson {
+                                config_path: branch_config_path.display().to_string(),
+                                error_message: error.to_string(),
+                            }),
+                        },
                         Err(error) => {
-                            if error.kind() == io::ErrorKind::NotFound {
+                            if create_missing && error.kind() == io::ErrorKind::NotFound {
                                 let default_branch_config = Self::default();
                                 default_branch_config.save(directory, name)?;
-                                default_branch_config
+                                to_json_value(&default_branch_config)
                             } else {
-                                return Err(PackrinthError::FailedToReadToString {
+                                Err(PackrinthError::FailedToReadToString {
                                     path_to_read: branch_config_path.display().to_string(),
                                     error_message: error.to_string(),
-                                });
+                                })
                             }
                         }
-                    };
-                    Ok(branch_config)
+                    }
                 } else {
                     Err(PackrinthError::DirectoryExpected {
                         path_that_should_have_been_dir: branch_dir.display().to_string(),
@@ -1129,35 +2001,223 @@ impl BranchConfig {
 
     /// Saves the branch configuration to the directory and name of the branch.
     ///
+    /// If the branch extends another branch, only the values that differ from the other branch
+    /// are written, so the branch keeps inheriting everything else.
+    ///
     /// # Errors
     /// - [`PackrinthError::FailedToSerialize`] if serialising this type to a JSON failed
     /// - [`PackrinthError::FailedToWriteFile`] if writing the JSON to a file failed
+    ///
+    /// If the branch extends another branch, errors from [`Self::from_directory`]
+    /// can also occur.
     pub fn save(&self, directory: &Path, name: &str) -> PackrinthResult<()> {
         let branch_config_path = directory.join(name).join(BRANCH_CONFIG_FILE_NAME);
-        json_to_file(self, branch_config_path)
+        if let Some(parent) = &self.extends {
+            let parent_json = to_json_value(&Self::from_directory(directory, parent)?)?;
+            let mut branch_json = to_json_value(self)?;
+            strip_inherited_branch_config_json(&parent_json, &mut branch_json);
+            json_to_file(&branch_json, branch_config_path)
+        } else {
+            json_to_file(self, branch_config_path)
+        }
     }
 
-    /// Prints a representation of the branch.
+    /// Bumps the version of this branch and returns the new version.
     ///
-    /// Example:
-    /// ```text
-    /// Branch 1.20.1:
-    ///   - Branch version: 1.0.0+1.20.1-alpha.1
-    ///   - Main Minecraft version: 1.20.1
-    ///   - Acceptable Minecraft versions:
-    ///   - Main mod loader: Fabric
-    ///   - Main mod loader version: 0.17.2
-    ///   - Acceptable loaders: Iris
-    ///   - No manual files are added
-    /// ```
+    /// The pre-release part (like `-fabric`) and the build metadata (like `+mc1.21.8`)
+    /// of the version are kept.
     ///
     /// # Errors
-    /// - [`PackrinthError::MainModLoaderProvidedButNoVersion`] if a main mod loader was specified
-    ///   in the configuration file, but no version for the mod loader was set
-    pub fn print_display(&self, name: &str) -> PackrinthResult<()> {
-        println!("Branch {name}:");
-        println!("  - Branch version: {}", self.version);
-        println!("  - Main Minecraft version: {}", self.minecraft_version);
+    /// - [`PackrinthError::BranchVersionIsNotSemver`] if the version isn't a semantic version
+    pub fn bump_version(&mut self, name: &str, bump: VersionBump) -> PackrinthResult<String> {
+        let mut version = match semver::Version::parse(&self.version) {
+            Ok(version) => version,
+            Err(error) => {
+                return Err(PackrinthError::BranchVersionIsNotSemver {
+                    branch: name.to_string(),
+                    version: self.version.clone(),
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        match bump {
+            VersionBump::Major => {
+                version.major += 1;
+                version.minor = 0;
+                version.patch = 0;
+            }
+            VersionBump::Minor => {
+                version.minor += 1;
+                version.patch = 0;
+            }
+            VersionBump::Patch => version.patch += 1,
+        }
+
+        self.version = version.to_string();
+        Ok(self.version.clone())
+    }
+
+    /// Returns the main Minecraft version followed by the acceptable Minecraft versions.
+    #[must_use]
+    pub fn game_versions(&self) -> Vec<String> {
+        let mut game_versions = vec![self.minecraft_version.clone()];
+        game_versions.extend(self.acceptable_minecraft_versions.clone());
+        game_versions
+    }
+
+    /// Returns the Modrinth values of all loaders projects may use in this branch.
+    ///
+    /// Next to the acceptable loaders and the main mod loader, the `minecraft` and `vanilla`
+    /// loaders are always added, so resource packs and shaders without a loader are found.
+    #[must_use]
+    pub fn modrinth_loaders(&self) -> Vec<&str> {
+        let mut loaders = Loader::modrinth_value_vec(&self.acceptable_loaders);
+        if let Some(mod_loader) = &self.mod_loader {
+            loaders.push(mod_loader.modrinth_value());
+        }
+
+        // Default loaders that will always be added
+        loaders.push(Loader::Minecraft.modrinth_value());
+        loaders.push(Loader::VanillaShader.modrinth_value());
+        loaders
+    }
+
+    /// Adds a manual file to this branch.
+    ///
+    /// If `force` is `true`, a manual file with the same path is replaced.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ManualFileAlreadyExists`] if a manual file with the same path
+    ///   already exists and `force` is `false`
+    pub fn add_manual_file(&mut self, file: File, force: bool) -> PackrinthResult<()> {
+        match self
+            .manual_files
+            .iter()
+            .position(|manual_file| manual_file.path == file.path)
+        {
+            Some(index) if force => self.manual_files[index] = file,
+            Some(_) => {
+                return Err(PackrinthError::ManualFileAlreadyExists { path: file.path });
+            }
+            None => self.manual_files.push(file),
+        }
+        Ok(())
+    }
+
+    /// Removes a manual file from this branch and returns it.
+    ///
+    /// The file can be given with its full path, or only with its file name
+    /// if no other manual file has that name.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ManualFileDoesNotExist`] if no manual file (or more than one manual
+    ///   file with the file name) was found
+    pub fn remove_manual_file(&mut self, path: &str) -> PackrinthResult<File> {
+        let index = if let Some(index) = self
+            .manual_files
+            .iter()
+            .position(|manual_file| manual_file.path == path)
+        {
+            index
+        } else {
+            let mut matches = self
+                .manual_files
+                .iter()
+                .enumerate()
+                .filter(|(_, manual_file)| manual_file.path.rsplit('/').next() == Some(path));
+            match (matches.next(), matches.next()) {
+                (Some((index, _)), None) => index,
+                _ => {
+                    return Err(PackrinthError::ManualFileDoesNotExist {
+                        path: path.to_string(),
+                    });
+                }
+            }
+        };
+        Ok(self.manual_files.remove(index))
+    }
+
+    /// Replaces [`Self::datapack_loader`] by the ID of its Modrinth project, which
+    /// [`File::from_project`] adds as dependency of every data pack. Call this once before
+    /// updating the projects of a branch, so the project is only requested once.
+    ///
+    /// The configuration shouldn't be saved afterward, so it keeps the slug the user wrote.
+    ///
+    /// # Errors
+    /// - Errors of [`Project::from_id`]
+    pub fn resolve_datapack_loader(&mut self) -> PackrinthResult<()> {
+        if let Some(datapack_loader) = &self.datapack_loader {
+            self.datapack_loader = Some(Project::from_id(datapack_loader)?.id);
+        }
+        Ok(())
+    }
+
+    /// Checks if the main Minecraft version and the acceptable Minecraft versions
+    /// of this branch are known Minecraft versions.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::UnknownMinecraftVersion`] if one of the versions isn't in `game_versions`
+    pub fn validate_minecraft_versions(&self, game_versions: &GameVersions) -> PackrinthResult<()> {
+        for version in
+            std::iter::once(&self.minecraft_version).chain(&self.acceptable_minecraft_versions)
+        {
+            if game_versions.get(version).is_none() {
+                return Err(PackrinthError::UnknownMinecraftVersion {
+                    version: version.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the loader version to the latest stable version of the main mod loader
+    /// for the main Minecraft version of this branch.
+    ///
+    /// If the branch doesn't have a main mod loader, nothing changes and [`None`] is returned.
+    /// Otherwise, the new loader version is returned.
+    ///
+    /// # Errors
+    /// Errors come from [`LoaderMetaUrls::latest_loader_version`], which is called in this
+    /// function and propagated upwards.
+    pub fn update_loader_version(
+        &mut self,
+        loader_meta_urls: &LoaderMetaUrls,
+    ) -> PackrinthResult<Option<String>> {
+        let Some(mod_loader) = self.mod_loader else {
+            return Ok(None);
+        };
+
+        let loader_version =
+            loader_meta_urls.latest_loader_version(mod_loader, &self.minecraft_version)?;
+        self.loader_version = Some(loader_version.clone());
+        Ok(Some(loader_version))
+    }
+
+    /// Prints a representation of the branch.
+    ///
+    /// Example:
+    /// ```text
+    /// Branch 1.20.1:
+    ///   - Branch version: 1.0.0+1.20.1-alpha.1
+    ///   - Main Minecraft version: 1.20.1
+    ///   - Acceptable Minecraft versions:
+    ///   - Main mod loader: Fabric
+    ///   - Main mod loader version: 0.17.2
+    ///   - Acceptable loaders: Iris
+    ///   - No manual files are added
+    /// ```
+    ///
+    /// # Errors
+    /// - [`PackrinthError::MainModLoaderProvidedButNoVersion`] if a main mod loader was specified
+    ///   in the configuration file, but no version for the mod loader was set
+    pub fn print_display(&self, name: &str) -> PackrinthResult<()> {
+        println!("Branch {name}:");
+        if let Some(extends) = &self.extends {
+            println!("  - Extends: {extends}");
+        }
+        println!("  - Branch version: {}", self.version);
+        println!("  - Main Minecraft version: {}", self.minecraft_version);
         println!(
             "  - Acceptable Minecraft versions: {}",
             self.acceptable_minecraft_versions.join(", ")
@@ -1186,12 +2246,16 @@ impl BranchConfig {
 impl Default for BranchConfig {
     fn default() -> Self {
         Self {
+            schema: Some(ConfigSchema::BranchConfig.url()),
+            extends: None,
             version: "1.0.0-fabric".to_string(),
             minecraft_version: "1.21.8".to_string(),
             acceptable_minecraft_versions: vec!["1.21.6".to_string(), "1.21.7".to_string()],
             mod_loader: Some(MainLoader::Fabric),
             loader_version: Some("0.17.2".to_string()),
             acceptable_loaders: vec![Loader::Minecraft, Loader::VanillaShader],
+            datapack_directory: None,
+            datapack_loader: None,
             manual_files: vec![],
         }
     }
@@ -1264,6 +2328,19 @@ impl BranchFiles {
         let branch_files_path = directory.join(name).join(BRANCH_FILES_FILE_NAME);
         json_to_file(self, branch_files_path)
     }
+
+    /// Serializes the branch files to the same JSON as [`BranchFiles::save`] writes.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serialising this type to a JSON failed
+    pub fn to_json(&self) -> PackrinthResult<String> {
+        match serde_json_to_string_pretty(self) {
+            Ok(json) => Ok(json),
+            Err(error) => Err(PackrinthError::FailedToSerialize {
+                error_message: error.to_string(),
+            }),
+        }
+    }
 }
 
 impl Default for BranchFiles {
@@ -1298,6 +2375,19 @@ impl MainLoader {
             MainLoader::Quilt => "quilt",
         }
     }
+
+    /// Returns the main loader with the given Modrinth value, like `neoforge`.
+    #[must_use]
+    pub fn from_modrinth_value(value: &str) -> Option<Self> {
+        [
+            MainLoader::Forge,
+            MainLoader::NeoForge,
+            MainLoader::Fabric,
+            MainLoader::Quilt,
+        ]
+        .into_iter()
+        .find(|main_loader| main_loader.modrinth_value() == value)
+    }
 }
 
 impl Loader {
@@ -1326,6 +2416,7 @@ impl Loader {
     pub const fn pretty_value(&self) -> &str {
         match self {
             Loader::Minecraft => "Minecraft",
+            Loader::Datapack => "Data pack",
             Loader::Fabric => "Fabric",
             Loader::Forge => "Forge",
             Loader::NeoForge => "NeoForge",
@@ -1360,6 +2451,7 @@ impl Loader {
     pub const fn modrinth_value(&self) -> &str {
         match self {
             Loader::Minecraft => "minecraft",
+            Loader::Datapack => "datapack",
             Loader::Fabric => "fabric",
             Loader::Forge => "forge",
             Loader::NeoForge => "neoforge",
@@ -1389,3 +2481,1034 @@ impl Loader {
         }
     }
 }
+
+impl IncludeOrExclude {
+    /// Returns whether a project with these inclusions or exclusions should be added to a branch.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::InvalidBranchRule`] if one of the entries is an invalid rule
+    pub fn allows_branch(
+        &self,
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        game_versions: Option<&GameVersions>,
+    ) -> PackrinthResult<bool> {
+        match self {
+            IncludeOrExclude::Include(inclusions) => {
+                for inclusion in inclusions {
+                    if BranchSelector::parse(inclusion)?.selects(
+                        branch_name,
+                        branch_config,
+                        game_versions,
+                    ) {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            IncludeOrExclude::Exclude(exclusions) => {
+                for exclusion in exclusions {
+                    if BranchSelector::parse(exclusion)?.selects(
+                        branch_name,
+                        branch_config,
+                        game_versions,
+                    ) {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl Default for SelectionStrategy {
+    fn default() -> Self {
+        Self(vec![
+            SelectionPreference::NewestMinecraft,
+            SelectionPreference::NewestDate,
+        ])
+    }
+}
+
+impl fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let preferences: Vec<&str> = self
+            .0
+            .iter()
+            .map(|preference| match preference {
+                SelectionPreference::ExactMinecraft => "exact Minecraft version",
+                SelectionPreference::MainLoader => "main loader",
+                SelectionPreference::NewestMinecraft => "newest Minecraft version",
+                SelectionPreference::NewestDate => "newest publish date",
+            })
+            .collect();
+        write!(f, "{}", preferences.join(", then "))
+    }
+}
+
+impl BranchSelector {
+    /// Parses an entry of the inclusions or exclusions of a project.
+    ///
+    /// Entries containing one of the operator characters (`=`, `!`, `<`, `>` or `~`) are parsed
+    /// as a [`BranchRule`]. All other entries are branch names.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::InvalidBranchRule`] if the entry looks like a rule, but isn't a valid one
+    pub fn parse(selector: &str) -> PackrinthResult<Self> {
+        if selector.contains(['=', '!', '<', '>', '~']) {
+            Ok(BranchSelector::Rule(BranchRule::parse(selector)?))
+        } else {
+            Ok(BranchSelector::Name(selector.to_string()))
+        }
+    }
+
+    /// Returns whether this selector selects the given branch.
+    ///
+    /// The Minecraft versions from Modrinth are used to compare Minecraft versions,
+    /// see [`compare_game_versions`].
+    #[must_use]
+    pub fn selects(
+        &self,
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        game_versions: Option<&GameVersions>,
+    ) -> bool {
+        match self {
+            BranchSelector::Name(name) => name == branch_name,
+            BranchSelector::Rule(rule) => rule.selects(branch_name, branch_config, game_versions),
+        }
+    }
+}
+
+impl BranchRule {
+    /// Parses a rule like `minecraft >= 1.21`.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::InvalidBranchRule`] if the field or operator is unknown, the value is
+    ///   missing or the operator can't be used for the field
+    pub fn parse(rule: &str) -> PackrinthResult<Self> {
+        let invalid_rule = |error_message: &str| PackrinthError::InvalidBranchRule {
+            rule: rule.to_string(),
+            error_message: error_message.to_string(),
+        };
+
+        let trimmed_rule = rule.trim();
+        let field_end = trimmed_rule
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(trimmed_rule.len());
+        let field = match &trimmed_rule[..field_end] {
+            "branch" => BranchRuleField::Branch,
+            "loader" => BranchRuleField::Loader,
+            "minecraft" => BranchRuleField::Minecraft,
+            "" => return Err(invalid_rule("no field specified")),
+            _ => {
+                return Err(invalid_rule(
+                    "unknown field, use branch, loader or minecraft",
+                ));
+            }
+        };
+
+        let rest = trimmed_rule[field_end..].trim_start();
+        // Two-character operators have to be checked first, because they start with a
+        // one-character operator.
+        let operator = [
+            ("==", BranchRuleOperator::Equal),
+            ("!=", BranchRuleOperator::NotEqual),
+            ("<=", BranchRuleOperator::LessOrEqual),
+            (">=", BranchRuleOperator::GreaterOrEqual),
+            ("!~", BranchRuleOperator::NotMatches),
+            ("=", BranchRuleOperator::Equal),
+            ("<", BranchRuleOperator::Less),
+            (">", BranchRuleOperator::Greater),
+            ("~", BranchRuleOperator::Matches),
+        ]
+        .into_iter()
+        .find(|(operator, _)| rest.starts_with(operator));
+        let Some((operator_text, operator)) = operator else {
+            return Err(invalid_rule("unknown operator"));
+        };
+
+        let value = rest[operator_text.len()..].trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        if value.is_empty() {
+            return Err(invalid_rule("no value specified"));
+        }
+
+        if field != BranchRuleField::Minecraft
+            && matches!(
+                operator,
+                BranchRuleOperator::Less
+                    | BranchRuleOperator::LessOrEqual
+                    | BranchRuleOperator::Greater
+                    | BranchRuleOperator::GreaterOrEqual
+            )
+        {
+            return Err(invalid_rule(
+                "ordering operators can only be used with the minecraft field",
+            ));
+        }
+
+        Ok(Self {
+            field,
+            operator,
+            value: value.to_string(),
+        })
+    }
+
+    /// Returns whether this rule selects the given branch.
+    ///
+    /// The Minecraft versions from Modrinth are used to compare Minecraft versions,
+    /// see [`compare_game_versions`].
+    #[must_use]
+    pub fn selects(
+        &self,
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        game_versions: Option<&GameVersions>,
+    ) -> bool {
+        let actual = match self.field {
+            BranchRuleField::Branch => branch_name,
+            BranchRuleField::Minecraft => branch_config.minecraft_version.as_str(),
+            BranchRuleField::Loader => match &branch_config.mod_loader {
+                Some(mod_loader) => mod_loader.modrinth_value(),
+                // A branch without a main loader never equals or matches a loader.
+                None => {
+                    return matches!(
+                        self.operator,
+                        BranchRuleOperator::NotEqual | BranchRuleOperator::NotMatches
+                    );
+                }
+            },
+        };
+
+        match self.operator {
+            BranchRuleOperator::Equal => actual == self.value,
+            BranchRuleOperator::NotEqual => actual != self.value,
+            BranchRuleOperator::Matches => pattern_matches(&self.value, actual),
+            BranchRuleOperator::NotMatches => !pattern_matches(&self.value, actual),
+            BranchRuleOperator::Less => {
+                compare_game_versions(actual, &self.value, game_versions).is_lt()
+            }
+            BranchRuleOperator::LessOrEqual => {
+                compare_game_versions(actual, &self.value, game_versions).is_le()
+            }
+            BranchRuleOperator::Greater => {
+                compare_game_versions(actual, &self.value, game_versions).is_gt()
+            }
+            BranchRuleOperator::GreaterOrEqual => {
+                compare_game_versions(actual, &self.value, game_versions).is_ge()
+            }
+        }
+    }
+}
+
+impl fmt::Display for BranchRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = match self.field {
+            BranchRuleField::Branch => "branch",
+            BranchRuleField::Loader => "loader",
+            BranchRuleField::Minecraft => "minecraft",
+        };
+        let operator = match self.operator {
+            BranchRuleOperator::Equal => "=",
+            BranchRuleOperator::NotEqual => "!=",
+            BranchRuleOperator::Less => "<",
+            BranchRuleOperator::LessOrEqual => "<=",
+            BranchRuleOperator::Greater => ">",
+            BranchRuleOperator::GreaterOrEqual => ">=",
+            BranchRuleOperator::Matches => "~",
+            BranchRuleOperator::NotMatches => "!~",
+        };
+        if self.value.contains(char::is_whitespace) {
+            write!(f, "{field} {operator} \"{}\"", self.value)
+        } else {
+            write!(f, "{field} {operator} {}", self.value)
+        }
+    }
+}
+
+/// A change to a branch that references to that branch have to follow.
+enum BranchReferenceChange<'a> {
+    /// The branch got a new name.
+    Rename(&'a str),
+
+    /// The branch was copied to a new branch, which should be referenced as well.
+    Copy(&'a str),
+
+    /// The branch was removed.
+    Remove,
+}
+
+/// Fails if a branch name is in [`RESERVED_BRANCH_NAMES`].
+fn check_branch_name(name: &str) -> PackrinthResult<()> {
+    if RESERVED_BRANCH_NAMES.contains(&name) {
+        return Err(PackrinthError::BranchNameIsReserved {
+            branch: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Copies all files in `from` to `to`, creating directories where needed.
+/// If `overwrite` is `false`, files that already exist in `to` are kept.
+///
+/// # Errors
+/// - [`PackrinthError::FailedToGetWalkDirEntry`] if reading the directory failed
+/// - [`PackrinthError::FailedToStripPath`] if a path in the directory is invalid
+/// - [`PackrinthError::FailedToCreateDir`] if creating a directory failed
+/// - [`PackrinthError::FailedToCopyFile`] if copying a file failed
+fn copy_directory(from: &Path, to: &Path, overwrite: bool) -> PackrinthResult<()> {
+    for entry in WalkDir::new(from) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                return Err(PackrinthError::FailedToGetWalkDirEntry {
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let Ok(relative_path) = entry.path().strip_prefix(from) else {
+            return Err(PackrinthError::FailedToStripPath {
+                path: entry.path().display().to_string(),
+            });
+        };
+        let target_path = to.join(relative_path);
+
+        if entry.file_type().is_dir() {
+            if let Err(error) = fs::create_dir_all(&target_path) {
+                return Err(PackrinthError::FailedToCreateDir {
+                    dir_to_create: target_path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        } else if (overwrite || !target_path.exists())
+            && let Err(error) = fs::copy(entry.path(), &target_path)
+        {
+            return Err(PackrinthError::FailedToCopyFile {
+                from: entry.path().display().to_string(),
+                to: target_path.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the selector with `branch` replaced by `new_branch` if the selector refers
+/// to `branch` by its name, either directly or with a `branch =` rule. `branch !=` rules
+/// are only replaced if `replace_not_equal` is `true`.
+/// Selectors that don't refer to the branch by name (or are invalid) return [`None`].
+fn selector_with_branch_replaced(
+    selector: &str,
+    branch: &str,
+    new_branch: &str,
+    replace_not_equal: bool,
+) -> Option<String> {
+    match BranchSelector::parse(selector) {
+        Ok(BranchSelector::Name(name)) if name == branch => Some(new_branch.to_string()),
+        Ok(BranchSelector::Rule(rule))
+            if rule.field == BranchRuleField::Branch
+                && (rule.operator == BranchRuleOperator::Equal
+                    || (replace_not_equal && rule.operator == BranchRuleOperator::NotEqual))
+                && rule.value == branch =>
+        {
+            Some(
+                BranchRule {
+                    value: new_branch.to_string(),
+                    ..rule
+                }
+                .to_string(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Updates inclusions or exclusions after a branch changed.
+///
+/// Removing a branch only removes entries that are the branch name itself, because rules
+/// referring to a branch that doesn't exist simply don't select anything.
+fn change_include_or_exclude_references(
+    include_or_exclude: &mut Option<IncludeOrExclude>,
+    branch: &str,
+    change: &BranchReferenceChange,
+) {
+    let Some(IncludeOrExclude::Include(selectors) | IncludeOrExclude::Exclude(selectors)) =
+        include_or_exclude
+    else {
+        return;
+    };
+
+    match change {
+        BranchReferenceChange::Rename(new_branch) => {
+            for selector in selectors.iter_mut() {
+                if let Some(renamed) =
+                    selector_with_branch_replaced(selector, branch, new_branch, true)
+                {
+                    *selector = renamed;
+                }
+            }
+        }
+        BranchReferenceChange::Copy(new_branch) => {
+            // Every branch differs from at least one of the two branches, so copying
+            // a branch != rule would make the list select all branches.
+            let copies: Vec<String> = selectors
+                .iter()
+                .filter_map(|selector| {
+                    selector_with_branch_replaced(selector, branch, new_branch, false)
+                })
+                .collect();
+            for copy in copies {
+                if !selectors.contains(&copy) {
+                    selectors.push(copy);
+                }
+            }
+        }
+        BranchReferenceChange::Remove => selectors.retain(|selector| selector != branch),
+    }
+
+    // An empty exclusion list excludes nothing, so it can be removed entirely.
+    // An empty inclusion list is kept, because it means the project shouldn't be added anywhere.
+    if let Some(IncludeOrExclude::Exclude(exclusions)) = include_or_exclude
+        && exclusions.is_empty()
+    {
+        *include_or_exclude = None;
+    }
+}
+
+/// Updates version overrides after a branch changed.
+fn change_version_override_references(
+    version_overrides: &mut Option<IndexMap<String, String>>,
+    branch: &str,
+    change: &BranchReferenceChange,
+) {
+    let Some(overrides) = version_overrides else {
+        return;
+    };
+
+    match change {
+        BranchReferenceChange::Rename(new_branch) => {
+            if let Some(index) = overrides.get_index_of(branch) {
+                let version = overrides[index].clone();
+                // Keep the override at the same position, so the diff stays small.
+                overrides.shift_remove_index(index);
+                overrides.shift_insert(index, (*new_branch).to_string(), version);
+            }
+        }
+        BranchReferenceChange::Copy(new_branch) => {
+            if let Some(version) = overrides.get(branch).cloned() {
+                overrides.insert((*new_branch).to_string(), version);
+            }
+        }
+        BranchReferenceChange::Remove => {
+            overrides.shift_remove(branch);
+        }
+    }
+
+    if overrides.is_empty() {
+        *version_overrides = None;
+    }
+}
+
+/// Matches text against a pattern where `*` matches any text and `?` matches one character.
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut pattern_index, mut text_index) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it was tried at.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while text_index < text.len() {
+        if pattern_index < pattern.len()
+            && (pattern[pattern_index] == '?' || pattern[pattern_index] == text[text_index])
+        {
+            pattern_index += 1;
+            text_index += 1;
+        } else if pattern_index < pattern.len() && pattern[pattern_index] == '*' {
+            backtrack = Some((pattern_index, text_index));
+            pattern_index += 1;
+        } else if let Some((star_index, star_text_index)) = backtrack {
+            // Let the last `*` match one more character.
+            pattern_index = star_index + 1;
+            text_index = star_text_index + 1;
+            backtrack = Some((star_index, star_text_index + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[pattern_index..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modrinth::{FileHashes, GameVersion, GameVersionType, SideSupport};
+    use pretty_assertions::assert_eq;
+
+    fn branch_config(minecraft_version: &str, mod_loader: Option<MainLoader>) -> BranchConfig {
+        BranchConfig {
+            minecraft_version: minecraft_version.to_string(),
+            mod_loader,
+            ..BranchConfig::default()
+        }
+    }
+
+    #[test]
+    fn parse_branch_selector() {
+        assert_eq!(
+            Ok(BranchSelector::Name("1.21.1".to_string())),
+            BranchSelector::parse("1.21.1")
+        );
+        assert_eq!(
+            Ok(BranchSelector::Rule(BranchRule {
+                field: BranchRuleField::Branch,
+                operator: BranchRuleOperator::Matches,
+                value: "*-server".to_string(),
+            })),
+            BranchSelector::parse("branch ~ \"*-server\"")
+        );
+        assert_eq!(
+            Ok(BranchSelector::Rule(BranchRule {
+                field: BranchRuleField::Minecraft,
+                operator: BranchRuleOperator::GreaterOrEqual,
+                value: "1.21".to_string(),
+            })),
+            BranchSelector::parse("minecraft>=1.21")
+        );
+        assert!(BranchSelector::parse("loader >= neoforge").is_err());
+        assert!(BranchSelector::parse("version = 1.0.0").is_err());
+        assert!(BranchSelector::parse("loader =").is_err());
+    }
+
+    #[test]
+    fn branch_rule_selects() {
+        let neoforge = branch_config("1.21.1", Some(MainLoader::NeoForge));
+        let fabric = branch_config("1.20.1", Some(MainLoader::Fabric));
+        let vanilla = branch_config("1.21.10", None);
+
+        let loader_rule = BranchRule::parse("loader = neoforge").unwrap();
+        assert!(loader_rule.selects("neo", &neoforge, None));
+        assert!(!loader_rule.selects("fabric", &fabric, None));
+        assert!(!loader_rule.selects("vanilla", &vanilla, None));
+
+        let minecraft_rule = BranchRule::parse("minecraft >= 1.21").unwrap();
+        assert!(minecraft_rule.selects("neo", &neoforge, None));
+        assert!(!minecraft_rule.selects("fabric", &fabric, None));
+        assert!(minecraft_rule.selects("vanilla", &vanilla, None));
+
+        // Snapshots can only be placed between releases with the versions from Modrinth.
+        let snapshot = branch_config("24w14a", None);
+        let game_versions = GameVersions::new(
+            [
+                ("24w14a", GameVersionType::Snapshot),
+                ("1.21", GameVersionType::Release),
+            ]
+            .into_iter()
+            .map(|(version, version_type)| GameVersion {
+                version: version.to_string(),
+                version_type,
+                date: String::new(),
+                major: false,
+            })
+            .collect(),
+        );
+        assert!(!minecraft_rule.selects("snapshot", &snapshot, None));
+        assert!(minecraft_rule.selects("snapshot", &snapshot, Some(&game_versions)));
+
+        let branch_rule = BranchRule::parse("branch ~ \"*-server\"").unwrap();
+        assert!(branch_rule.selects("1.21.1-server", &neoforge, None));
+        assert!(!branch_rule.selects("1.21.1-client", &neoforge, None));
+    }
+
+    #[test]
+    fn include_or_exclude_allows_branch() {
+        let config = branch_config("1.21.1", Some(MainLoader::NeoForge));
+
+        let include =
+            IncludeOrExclude::Include(vec!["1.20.1".to_string(), "loader = neoforge".to_string()]);
+        assert_eq!(Ok(true), include.allows_branch("1.21.1", &config, None));
+
+        let exclude = IncludeOrExclude::Exclude(vec!["minecraft < 1.21".to_string()]);
+        assert_eq!(Ok(true), exclude.allows_branch("1.21.1", &config, None));
+        assert_eq!(
+            Ok(false),
+            exclude.allows_branch("1.20.1", &branch_config("1.20.1", None), None)
+        );
+    }
+
+    #[test]
+    fn resolve_project_settings_with_groups() {
+        let mut modpack = Modpack {
+            selection: Some(SelectionStrategy(vec![SelectionPreference::ExactMinecraft])),
+            ..Modpack::default()
+        };
+        modpack.groups.insert(
+            "performance".to_string(),
+            GroupSettings {
+                channel: Some(VersionType::Release),
+                env: None,
+                include_or_exclude: Some(IncludeOrExclude::Include(vec![
+                    "loader = fabric".to_string(),
+                ])),
+            },
+        );
+        modpack.groups.insert(
+            "client".to_string(),
+            GroupSettings {
+                channel: Some(VersionType::Alpha),
+                env: Some(Env {
+                    client: SideSupport::Required,
+                    server: SideSupport::Unsupported,
+                }),
+                include_or_exclude: None,
+            },
+        );
+
+        let project_settings = ProjectSettings {
+            groups: vec!["performance".to_string(), "client".to_string()],
+            include_or_exclude: Some(IncludeOrExclude::Exclude(vec!["1.20.1".to_string()])),
+            ..ProjectSettings::default()
+        };
+        assert_eq!(
+            Ok(ProjectSettings {
+                version_overrides: None,
+                groups: vec!["performance".to_string(), "client".to_string()],
+                channel: Some(VersionType::Release),
+                env: Some(Env {
+                    client: SideSupport::Required,
+                    server: SideSupport::Unsupported,
+                }),
+                selection: Some(SelectionStrategy(vec![SelectionPreference::ExactMinecraft])),
+                include_or_exclude: Some(IncludeOrExclude::Exclude(vec!["1.20.1".to_string()])),
+            }),
+            modpack.resolve_project_settings(&project_settings)
+        );
+
+        let unknown_group = ProjectSettings {
+            groups: vec!["worldgen".to_string()],
+            ..ProjectSettings::default()
+        };
+        assert_eq!(
+            Err(PackrinthError::GroupDoesNotExist {
+                group: "worldgen".to_string()
+            }),
+            modpack.resolve_project_settings(&unknown_group)
+        );
+    }
+
+    #[test]
+    fn branch_config_inheritance() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+
+        let mut base = modpack.new_branch("base").unwrap();
+        base.acceptable_minecraft_versions = vec!["1.21.6".to_string()];
+        base.save(directory, "base").unwrap();
+
+        let mut child = modpack.new_branch_extending("child", "base").unwrap();
+        assert_eq!(
+            format!(
+                "{{\n\t\"$schema\": \"{}\",\n\t\"extends\": \"base\"\n}}",
+                ConfigSchema::BranchConfig.url()
+            ),
+            fs::read_to_string(directory.join("child").join(BRANCH_CONFIG_FILE_NAME)).unwrap()
+        );
+        assert_eq!(base.minecraft_version, child.minecraft_version);
+
+        child.version = "2.0.0".to_string();
+        child
+            .acceptable_minecraft_versions
+            .push("1.21.7".to_string());
+        child.save(directory, "child").unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "$schema": ConfigSchema::BranchConfig.url(),
+                "extends": "base",
+                "version": "2.0.0",
+                "acceptable_minecraft_versions": ["1.21.7"],
+            }),
+            serde_json::from_str::<serde_json::Value>(
+                &fs::read_to_string(directory.join("child").join(BRANCH_CONFIG_FILE_NAME)).unwrap()
+            )
+            .unwrap()
+        );
+
+        let child = BranchConfig::from_directory(directory, "child").unwrap();
+        assert_eq!(
+            vec!["1.21.6".to_string(), "1.21.7".to_string()],
+            child.acceptable_minecraft_versions
+        );
+        assert_eq!(
+            vec!["base".to_string(), "child".to_string()],
+            BranchConfig::inheritance_chain(directory, "child").unwrap()
+        );
+
+        base.extends = Some("child".to_string());
+        json_to_file(&base, directory.join("base").join(BRANCH_CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(
+            Err(PackrinthError::BranchInheritanceCycle {
+                branch: "child".to_string()
+            }),
+            BranchConfig::from_directory(directory, "child")
+        );
+    }
+
+    #[test]
+    fn copy_branch_does_not_copy_not_equal_rules() {
+        let mut include_or_exclude = Some(IncludeOrExclude::Exclude(vec![
+            "1.21.8".to_string(),
+            "branch = 1.21.8".to_string(),
+            "branch != 1.21.8".to_string(),
+        ]));
+        change_include_or_exclude_references(
+            &mut include_or_exclude,
+            "1.21.8",
+            &BranchReferenceChange::Copy("1.21.9"),
+        );
+        assert_eq!(
+            Some(IncludeOrExclude::Exclude(vec![
+                "1.21.8".to_string(),
+                "branch = 1.21.8".to_string(),
+                "branch != 1.21.8".to_string(),
+                "1.21.9".to_string(),
+                "branch = 1.21.9".to_string(),
+            ])),
+            include_or_exclude
+        );
+    }
+
+    #[test]
+    fn reserved_branch_names_are_refused() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        let reserved = PackrinthError::BranchNameIsReserved {
+            branch: SHARED_DIRECTORY.to_string(),
+        };
+        assert_eq!(Err(reserved.clone()), modpack.new_branch(SHARED_DIRECTORY));
+        assert!(!directory.join(SHARED_DIRECTORY).exists());
+
+        modpack
+            .new_branch("1.21.8")
+            .unwrap()
+            .save(directory, "1.21.8")
+            .unwrap();
+        assert_eq!(
+            Err(reserved.clone()),
+            modpack.new_branch_extending(SHARED_DIRECTORY, "1.21.8")
+        );
+        assert_eq!(
+            Err(reserved.clone()),
+            modpack.copy_branch("1.21.8", SHARED_DIRECTORY)
+        );
+        assert_eq!(
+            Err(reserved),
+            modpack.rename_branch("1.21.8", SHARED_DIRECTORY)
+        );
+        assert_eq!(vec!["1.21.8".to_string()], modpack.branches);
+    }
+
+    #[test]
+    fn rename_copy_and_remove_branch_references() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        modpack.new_branch("1.21.5").unwrap();
+        modpack
+            .new_branch_extending("1.21.5-server", "1.21.5")
+            .unwrap();
+        modpack.new_branch("1.21.8").unwrap();
+        modpack.projects.insert(
+            "sodium".to_string(),
+            ProjectSettings {
+                version_overrides: Some(IndexMap::from([
+                    ("1.21.5".to_string(), "abc".to_string()),
+                    ("1.21.8".to_string(), "def".to_string()),
+                ])),
+                include_or_exclude: Some(IncludeOrExclude::Include(vec![
+                    "1.21.5".to_string(),
+                    "branch != 1.21.5".to_string(),
+                    "loader = fabric".to_string(),
+                ])),
+                ..ProjectSettings::default()
+            },
+        );
+
+        modpack.rename_branch("1.21.5", "1.21.6").unwrap();
+        assert_eq!(vec!["1.21.6", "1.21.5-server", "1.21.8"], modpack.branches);
+        assert!(fs::exists(directory.join("1.21.6").join(BRANCH_CONFIG_FILE_NAME)).unwrap());
+        assert!(!fs::exists(directory.join("1.21.5")).unwrap());
+        assert_eq!(
+            vec!["1.21.6".to_string(), "1.21.5-server".to_string()],
+            BranchConfig::inheritance_chain(directory, "1.21.5-server").unwrap()
+        );
+        let sodium = &modpack.projects["sodium"];
+        assert_eq!(
+            Some(IndexMap::from([
+                ("1.21.6".to_string(), "abc".to_string()),
+                ("1.21.8".to_string(), "def".to_string()),
+            ])),
+            sodium.version_overrides
+        );
+        assert_eq!(
+            Some(IncludeOrExclude::Include(vec![
+                "1.21.6".to_string(),
+                "branch != 1.21.6".to_string(),
+                "loader = fabric".to_string(),
+            ])),
+            sodium.include_or_exclude
+        );
+
+        modpack.copy_branch("1.21.8", "1.21.9").unwrap();
+        assert!(fs::exists(directory.join("1.21.9").join(BRANCH_CONFIG_FILE_NAME)).unwrap());
+        assert_eq!(
+            Some(&"def".to_string()),
+            modpack.projects["sodium"]
+                .version_overrides
+                .as_ref()
+                .unwrap()
+                .get("1.21.9")
+        );
+        assert_eq!(
+            Err(PackrinthError::BranchAlreadyExists {
+                branch: "1.21.9".to_string()
+            }),
+            modpack.copy_branch("1.21.8", "1.21.9")
+        );
+
+        assert_eq!(
+            Err(PackrinthError::BranchIsExtended {
+                branch: "1.21.6".to_string(),
+                extended_by: "1.21.5-server".to_string(),
+            }),
+            modpack.remove_branches(&vec!["1.21.6".to_string()])
+        );
+        modpack
+            .remove_branches(&vec!["1.21.6".to_string(), "1.21.5-server".to_string()])
+            .unwrap();
+        let sodium = &modpack.projects["sodium"];
+        assert_eq!(
+            Some(IndexMap::from([
+                ("1.21.8".to_string(), "def".to_string()),
+                ("1.21.9".to_string(), "def".to_string()),
+            ])),
+            sodium.version_overrides
+        );
+        assert_eq!(
+            Some(IncludeOrExclude::Include(vec![
+                "branch != 1.21.6".to_string(),
+                "loader = fabric".to_string(),
+            ])),
+            sodium.include_or_exclude
+        );
+    }
+
+    #[test]
+    fn port_extending_branch() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        let mut base = modpack.new_branch("1.21.4").unwrap();
+        base.minecraft_version = "1.21.4".to_string();
+        base.acceptable_minecraft_versions = vec!["1.21.3".to_string()];
+        base.save(directory, "1.21.4").unwrap();
+        modpack
+            .new_branch_extending("1.21.4-server", "1.21.4")
+            .unwrap();
+        for (branch, file, contents) in [
+            ("1.21.4", "options.txt", "base"),
+            ("1.21.4", "servers.dat", "base"),
+            ("1.21.4-server", "options.txt", "server"),
+        ] {
+            let overrides_dir = directory.join(branch).join("overrides");
+            fs::create_dir_all(&overrides_dir).unwrap();
+            fs::write(overrides_dir.join(file), contents).unwrap();
+        }
+
+        let ported = modpack
+            .port_branch("1.21.4-server", "1.21.5-server", "1.21.5")
+            .unwrap();
+        assert_eq!(None, ported.extends);
+        assert!(ported.acceptable_minecraft_versions.is_empty());
+        assert_eq!(
+            ported,
+            BranchConfig::from_directory(directory, "1.21.5-server").unwrap()
+        );
+        let overrides_dir = directory.join("1.21.5-server").join("overrides");
+        assert_eq!(
+            "server",
+            fs::read_to_string(overrides_dir.join("options.txt")).unwrap()
+        );
+        assert_eq!(
+            "base",
+            fs::read_to_string(overrides_dir.join("servers.dat")).unwrap()
+        );
+    }
+
+    #[test]
+    fn port_branch() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        let mut branch_config = modpack.new_branch("1.21.4").unwrap();
+        branch_config.minecraft_version = "1.21.4".to_string();
+        branch_config.acceptable_minecraft_versions = vec!["1.21.3".to_string()];
+        branch_config.save(directory, "1.21.4").unwrap();
+        BranchFiles::default().save(directory, "1.21.4").unwrap();
+        modpack.projects.insert(
+            "yosbr".to_string(),
+            ProjectSettings {
+                version_overrides: Some(IndexMap::from([(
+                    "1.21.4".to_string(),
+                    "KMOzdYko".to_string(),
+                )])),
+                include_or_exclude: Some(IncludeOrExclude::Include(vec!["1.21.4".to_string()])),
+                ..ProjectSettings::default()
+            },
+        );
+
+        let ported = modpack.port_branch("1.21.4", "1.21.5", "1.21.5").unwrap();
+        assert_eq!("1.21.5", ported.minecraft_version);
+        assert!(ported.acceptable_minecraft_versions.is_empty());
+        assert_eq!(
+            ported,
+            BranchConfig::from_directory(directory, "1.21.5").unwrap()
+        );
+        assert_eq!(
+            "1.21.4",
+            BranchConfig::from_directory(directory, "1.21.4")
+                .unwrap()
+                .minecraft_version
+        );
+        assert!(!fs::exists(directory.join("1.21.5").join(BRANCH_FILES_FILE_NAME)).unwrap());
+        assert!(fs::exists(directory.join("1.21.4").join(BRANCH_FILES_FILE_NAME)).unwrap());
+
+        let yosbr = &modpack.projects["yosbr"];
+        assert_eq!(
+            Some(IndexMap::from([(
+                "1.21.4".to_string(),
+                "KMOzdYko".to_string()
+            )])),
+            yosbr.version_overrides
+        );
+        assert_eq!(
+            Some(IncludeOrExclude::Include(vec![
+                "1.21.4".to_string(),
+                "1.21.5".to_string()
+            ])),
+            yosbr.include_or_exclude
+        );
+    }
+
+    #[test]
+    fn bump_branch_version() {
+        let mut branch_config = BranchConfig {
+            version: "1.4.2-fabric+mc1.21.8".to_string(),
+            ..BranchConfig::default()
+        };
+        assert_eq!(
+            Ok("1.4.3-fabric+mc1.21.8".to_string()),
+            branch_config.bump_version("1.21.8", VersionBump::Patch)
+        );
+        assert_eq!(
+            Ok("1.5.0-fabric+mc1.21.8".to_string()),
+            branch_config.bump_version("1.21.8", VersionBump::Minor)
+        );
+        assert_eq!(
+            Ok("2.0.0-fabric+mc1.21.8".to_string()),
+            branch_config.bump_version("1.21.8", VersionBump::Major)
+        );
+
+        branch_config.version = "1.0".to_string();
+        assert!(matches!(
+            branch_config.bump_version("1.21.8", VersionBump::Patch),
+            Err(PackrinthError::BranchVersionIsNotSemver { .. })
+        ));
+    }
+
+    #[test]
+    fn add_and_remove_manual_files() {
+        let manual_file = |path: &str, file_size: u64| File {
+            project_name: String::new(),
+            path: path.to_string(),
+            hashes: FileHashes {
+                sha1: String::new(),
+                sha512: String::new(),
+            },
+            env: None,
+            downloads: vec![],
+            file_size,
+        };
+        let mut branch_config = BranchConfig::default();
+        branch_config
+            .add_manual_file(manual_file("mods/a.jar", 1), false)
+            .unwrap();
+        branch_config
+            .add_manual_file(manual_file("config/a.jar", 2), false)
+            .unwrap();
+        assert_eq!(
+            Err(PackrinthError::ManualFileAlreadyExists {
+                path: "mods/a.jar".to_string()
+            }),
+            branch_config.add_manual_file(manual_file("mods/a.jar", 3), false)
+        );
+        branch_config
+            .add_manual_file(manual_file("mods/a.jar", 3), true)
+            .unwrap();
+        assert_eq!(3, branch_config.manual_files[0].file_size);
+
+        // The file name is ambiguous, so the full path is needed.
+        assert_eq!(
+            Err(PackrinthError::ManualFileDoesNotExist {
+                path: "a.jar".to_string()
+            }),
+            branch_config.remove_manual_file("a.jar")
+        );
+        assert_eq!(
+            Ok(manual_file("config/a.jar", 2)),
+            branch_config.remove_manual_file("config/a.jar")
+        );
+        assert_eq!(
+            Ok(manual_file("mods/a.jar", 3)),
+            branch_config.remove_manual_file("a.jar")
+        );
+        assert!(branch_config.manual_files.is_empty());
+    }
+
+    #[test]
+    fn validate_minecraft_versions() {
+        let game_versions = GameVersions::new(
+            ["1.21.8", "1.21.7", "1.21.6"]
+                .into_iter()
+                .map(|version| GameVersion {
+                    version: version.to_string(),
+                    version_type: GameVersionType::Release,
+                    date: String::new(),
+                    major: false,
+                })
+                .collect(),
+        );
+
+        let mut branch_config = BranchConfig::default();
+        assert_eq!(
+            Ok(()),
+            branch_config.validate_minecraft_versions(&game_versions)
+        );
+
+        branch_config
+            .acceptable_minecraft_versions
+            .push("1.21.9".to_string());
+        assert_eq!(
+            Err(PackrinthError::UnknownMinecraftVersion {
+                version: "1.21.9".to_string()
+            }),
+            branch_config.validate_minecraft_versions(&game_versions)
+        );
+    }
+}
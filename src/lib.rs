@@ -33,12 +33,26 @@
 use std::fmt::Write as _;
 use std::io::Write as _;
 
+pub mod changelog;
 pub mod config;
 pub mod crates_io;
+pub mod licenses;
+pub mod loader_meta;
+pub mod manual;
+pub mod migration;
 pub mod modrinth;
+pub mod schema;
+pub mod site;
+pub mod validation;
+
+#[cfg(test)]
+mod test_utils;
 
 use crate::config::{BranchConfig, BranchFiles, BranchFilesProject, Modpack, ProjectSettings};
-use crate::modrinth::{Env, File, FileResult, SideSupport, VersionDependency};
+use crate::modrinth::{
+    Env, File, FileResult, GameVersions, SideSupport, VersionDependency, VersionType,
+};
+use indexmap::IndexMap;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::RetryTransientMiddleware;
 use reqwest_retry::policies::ExponentialBackoff;
@@ -46,7 +60,7 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::fs::OpenOptions;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use std::time::Duration;
 
@@ -56,6 +70,7 @@ use std::time::Duration;
 pub const TARGET_DIRECTORY: &str = "target";
 
 static CLIENT: OnceLock<ClientWithMiddleware> = OnceLock::new();
+static POST_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 const USER_AGENT: &str = concat!(
     "Thijzert123",
     "/",
@@ -64,31 +79,154 @@ const USER_AGENT: &str = concat!(
     env!("CARGO_PKG_VERSION")
 );
 
-fn request_text<T: ToString + ?Sized>(full_url: &T) -> PackrinthResult<String> {
-    let client = CLIENT.get_or_init(|| {
+fn build_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .expect("Failed to build request client")
+}
+
+fn client() -> &'static ClientWithMiddleware {
+    CLIENT.get_or_init(|| {
         let retry_policy = ExponentialBackoff::builder()
             .build_with_total_retry_duration(Duration::from_secs(60 * 2));
-        ClientBuilder::new(
-            reqwest::Client::builder()
-                .user_agent(USER_AGENT)
-                .build()
-                .expect("Failed to build request client"),
-        )
-        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-        .build()
-    });
+        ClientBuilder::new(build_client())
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build()
+    })
+}
+
+/// Returns a client that doesn't retry requests. POST requests aren't retried, because
+/// a request that failed halfway may already have been handled, like an uploaded version.
+fn post_client() -> &'static reqwest::Client {
+    POST_CLIENT.get_or_init(build_client)
+}
+
+fn request_text<T: ToString + ?Sized>(full_url: &T) -> PackrinthResult<String> {
+    let request_failed = |error_message: String| PackrinthError::RequestFailed {
+        url: full_url.to_string(),
+        error_message,
+    };
 
     let runtime = tokio::runtime::Runtime::new().expect("Failed to create runtime");
-    let response = runtime
-        .block_on(client.get(full_url.to_string()).send())
-        .expect("Failed to get response");
+    let response = match runtime.block_on(client().get(full_url.to_string()).send()) {
+        Ok(response) => response,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
     match runtime.block_on(response.text()) {
         Ok(text) => Ok(text),
-        Err(error) => Err(PackrinthError::RequestFailed {
-            url: full_url.to_string(),
+        Err(error) => Err(request_failed(error.to_string())),
+    }
+}
+
+/// Requests text like [`request_text`], but returns [`None`] if the server responds
+/// with 404 Not Found.
+fn request_optional_text<T: ToString + ?Sized>(full_url: &T) -> PackrinthResult<Option<String>> {
+    let request_failed = |error_message: String| PackrinthError::RequestFailed {
+        url: full_url.to_string(),
+        error_message,
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let response = match runtime.block_on(client().get(full_url.to_string()).send()) {
+        Ok(response) => response,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    match runtime.block_on(response.text()) {
+        Ok(text) => Ok(Some(text)),
+        Err(error) => Err(request_failed(error.to_string())),
+    }
+}
+
+/// Downloads a file. Unlike text requests, the download fails if the server
+/// doesn't respond with a success status.
+fn request_bytes<T: ToString + ?Sized>(full_url: &T) -> PackrinthResult<Vec<u8>> {
+    let request_failed = |error_message: String| PackrinthError::RequestFailed {
+        url: full_url.to_string(),
+        error_message,
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let response = match runtime.block_on(client().get(full_url.to_string()).send()) {
+        Ok(response) => response,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
+    let response = match response.error_for_status() {
+        Ok(response) => response,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
+    match runtime.block_on(response.bytes()) {
+        Ok(bytes) => Ok(bytes.to_vec()),
+        Err(error) => Err(request_failed(error.to_string())),
+    }
+}
+
+/// Sends a POST request with a multipart form, authorized with a token, and returns the response text.
+/// Unlike GET requests, the request fails if the server doesn't respond with a success status.
+fn request_post<T: ToString + ?Sized>(
+    full_url: &T,
+    token: &str,
+    form: reqwest::multipart::Form,
+) -> PackrinthResult<String> {
+    let request_failed = |error_message: String| PackrinthError::RequestFailed {
+        url: full_url.to_string(),
+        error_message,
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let request = post_client()
+        .post(full_url.to_string())
+        .header(reqwest::header::AUTHORIZATION, token)
+        .multipart(form);
+    let response = match runtime.block_on(request.send()) {
+        Ok(response) => response,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
+    let status = response.status();
+    let text = match runtime.block_on(response.text()) {
+        Ok(text) => text,
+        Err(error) => return Err(request_failed(error.to_string())),
+    };
+    if status.is_success() {
+        Ok(text)
+    } else {
+        Err(request_failed(format!("{status}: {text}")))
+    }
+}
+
+/// Creates the parent directory of a path, if it doesn't exist yet.
+///
+/// # Errors
+/// - [`PackrinthError::FailedToCreateDir`] if creating the directory failed
+pub fn create_parent_dir(path: &Path) -> PackrinthResult<()> {
+    if let Some(parent) = path.parent()
+        && let Err(error) = fs::create_dir_all(parent)
+    {
+        return Err(PackrinthError::FailedToCreateDir {
+            dir_to_create: parent.display().to_string(),
             error_message: error.to_string(),
-        }),
+        });
     }
+    Ok(())
+}
+
+/// Writes a file, creating its parent directory if needed.
+///
+/// # Errors
+/// - [`PackrinthError::FailedToCreateDir`] if creating the parent directory failed
+/// - [`PackrinthError::FailedToWriteFile`] if writing the file failed
+pub fn write_file<C: AsRef<[u8]>>(path: &Path, contents: C) -> PackrinthResult<()> {
+    create_parent_dir(path)?;
+    if let Err(error) = fs::write(path, contents) {
+        return Err(PackrinthError::FailedToWriteFile {
+            path_to_write_to: path.display().to_string(),
+            error_message: error.to_string(),
+        });
+    }
+    Ok(())
 }
 
 /// The file name of the configuration file inside a `.mrpack` pack.
@@ -116,14 +254,19 @@ pub struct ProjectUpdater<'a> {
     pub require_all: bool,
     pub no_beta: bool,
     pub no_alpha: bool,
+
+    /// The Minecraft versions from Modrinth, used to compare Minecraft versions.
+    /// If [`None`], they are compared by their names.
+    pub game_versions: Option<&'a GameVersions>,
 }
 
 /// The result when updating a project.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ProjectUpdateResult {
     /// The project was successfully updated. All dependencies will be returned,
-    /// but the [`Vec`] may be empty.
-    Added(Vec<VersionDependency>),
+    /// but the [`Vec`] may be empty. If known, an explanation of why the version
+    /// was chosen is returned as well.
+    Added(Vec<VersionDependency>, Option<String>),
 
     /// The project was skipped, because it has inclusions or exclusions specified.
     Skipped,
@@ -139,14 +282,16 @@ pub enum ProjectUpdateResult {
 impl ProjectUpdater<'_> {
     /// Updates a project using the Modrinth API.
     pub fn update_project(&mut self) -> ProjectUpdateResult {
-        match File::from_project(
+        let (file_result, reason) = File::from_project_with_reason(
             self.branch_name,
             self.branch_config,
             self.slug_project_id,
             self.project_settings,
-            self.no_beta,
             self.no_alpha,
-        ) {
+            self.no_beta,
+            self.game_versions,
+        );
+        match file_result {
             FileResult::Ok {
                 mut file,
                 dependencies,
@@ -157,6 +302,9 @@ impl ProjectUpdater<'_> {
                     id: Some(project_id),
                 });
 
+                if let Some(env) = self.project_settings.env {
+                    file.env = Some(env);
+                }
                 if self.require_all {
                     file.env = Some(Env {
                         client: SideSupport::Required,
@@ -165,7 +313,7 @@ impl ProjectUpdater<'_> {
                 }
 
                 self.branch_files.files.push(file);
-                ProjectUpdateResult::Added(dependencies)
+                ProjectUpdateResult::Added(dependencies, reason)
             }
             FileResult::Skipped => ProjectUpdateResult::Skipped,
             FileResult::NotFound => ProjectUpdateResult::NotFound,
@@ -174,22 +322,187 @@ impl ProjectUpdater<'_> {
     }
 }
 
+/// How well a project is available for a branch on Modrinth.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectAvailability {
+    /// A release version of the project is available.
+    Release,
+
+    /// No release version is available, but a beta version is.
+    Beta,
+
+    /// No release or beta version is available, but an alpha version is.
+    Alpha,
+
+    /// No version of the project is available.
+    NotFound,
+
+    /// The project isn't added to the branch, because of its inclusions or exclusions.
+    Skipped,
+
+    /// Some other error occurred while looking up the project.
+    Failed(PackrinthError),
+}
+
+impl ProjectAvailability {
+    /// Checks the availability of a project for a branch using the Modrinth API.
+    ///
+    /// The channel of the project settings is ignored, so that a report can show
+    /// that a less stable version exists.
+    #[must_use]
+    pub fn check(
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        slug_project_id: &str,
+        project_settings: &ProjectSettings,
+        game_versions: Option<&GameVersions>,
+    ) -> Self {
+        match File::available_version_types(
+            branch_name,
+            branch_config,
+            slug_project_id,
+            project_settings,
+            game_versions,
+        ) {
+            Ok(Some(version_types)) => Self::from_version_types(&version_types),
+            Ok(None) => Self::Skipped,
+            Err(error) => Self::Failed(error),
+        }
+    }
+
+    /// Returns the availability of the most stable of the version types.
+    fn from_version_types(version_types: &[VersionType]) -> Self {
+        if version_types.contains(&VersionType::Release) {
+            Self::Release
+        } else if version_types.contains(&VersionType::Beta) {
+            Self::Beta
+        } else if version_types.contains(&VersionType::Alpha) {
+            Self::Alpha
+        } else {
+            Self::NotFound
+        }
+    }
+}
+
+/// A column of a [`ProjectTable`].
+///
+/// [`ProjectTableColumn::Category`] is shown once for every project. All other columns
+/// are shown for every branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProjectTableColumn {
+    /// Whether the branch contains the project, shown with checkmark icons.
+    Availability,
+
+    /// The name of the file of the project in the branch, which usually contains its version.
+    FileName,
+
+    /// Whether the project is used on the client, the server or both.
+    Environment,
+
+    /// Whether the file of the project is a release, beta or alpha version.
+    VersionType,
+
+    /// The Modrinth categories of the project.
+    Category,
+}
+
+impl ProjectTableColumn {
+    /// Returns the title of the column.
+    #[must_use]
+    pub const fn title(&self) -> &str {
+        match self {
+            ProjectTableColumn::Availability => "Included",
+            ProjectTableColumn::FileName => "File",
+            ProjectTableColumn::Environment => "Environment",
+            ProjectTableColumn::VersionType => "Version type",
+            ProjectTableColumn::Category => "Category",
+        }
+    }
+
+    /// Returns the key of the column in JSON.
+    const fn json_key(self) -> &'static str {
+        match self {
+            ProjectTableColumn::Availability => "included",
+            ProjectTableColumn::FileName => "file_name",
+            ProjectTableColumn::Environment => "environment",
+            ProjectTableColumn::VersionType => "version_type",
+            ProjectTableColumn::Category => "categories",
+        }
+    }
+}
+
+/// What a branch contains of a project in a [`ProjectTable`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectTableCell {
+    /// The name of the file, without the directory it is in.
+    pub file_name: String,
+
+    /// The Modrinth ID of the version of the file, which is used to look up its version type.
+    /// If [`None`], the file isn't downloaded from Modrinth.
+    pub version_id: Option<String>,
+
+    /// The environment of the file. If [`None`], the file is required on both sides.
+    pub env: Option<Env>,
+
+    /// The version type of the file. This is only known after
+    /// [`ProjectTable::fetch_modrinth_details`] and only for files from Modrinth.
+    pub version_type: Option<VersionType>,
+}
+
+impl ProjectTableCell {
+    fn environment(&self) -> String {
+        self.env
+            .as_ref()
+            .map_or_else(|| "Client and server".to_string(), modrinth::Env::label)
+    }
+
+    fn version_type(&self) -> &str {
+        self.version_type
+            .as_ref()
+            .map_or("Unknown", VersionType::pretty_value)
+    }
+}
+
+/// A project or manual file in a [`ProjectTable`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectTableRow {
+    pub name: String,
+
+    /// The Modrinth ID of the project. If [`None`], the row is a manual file.
+    pub modrinth_id: Option<String>,
+
+    /// The groups of the project. These are only known after [`ProjectTable::assign_groups`].
+    pub groups: Vec<String>,
+
+    /// The Modrinth categories of the project. These are only known after
+    /// [`ProjectTable::fetch_modrinth_details`].
+    pub categories: Vec<String>,
+
+    /// The cells of the branches in [`ProjectTable::branches`], in the same order.
+    /// [`None`] means that the project isn't in the branch.
+    pub cells: Vec<Option<ProjectTableCell>>,
+}
+
+impl ProjectTableRow {
+    fn markdown_name(&self) -> String {
+        match &self.modrinth_id {
+            Some(id) => format!("[{}](https://modrinth.com/project/{id})", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
 /// A table that can be used to show which branches contain which projects.
 ///
 /// This can be useful if you provide your modpack for multiple Minecraft versions,
 /// and you want to show which mods are compatible with all the modpack branches.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ProjectTable {
-    /// The column names are sorted from left to right. The first value should be something like
-    /// `Project` or `Mod name`. All the other values should be the names of the branches.
-    pub column_names: Vec<String>,
-
-    /// The project map that contains information of which projects are available for which branches.
-    /// This [`HashMap`] contains the project as key, and another nested [`HashMap`] as value.
-    /// The nested map contains a branch name as key, and an empty [`Option`] as value.
-    /// [`Some`] with an empty `()` value means that the project is available for the branch,
-    /// while [`None`] means that the project isn't available for the branch.
-    pub project_map: HashMap<BranchFilesProject, HashMap<String, Option<()>>>,
+    /// The branches in the order of the modpack configuration.
+    pub branches: Vec<String>,
+
+    /// The projects and manual files, sorted by name.
+    pub rows: Vec<ProjectTableRow>,
 }
 
 impl Display for ProjectTable {
@@ -198,78 +511,233 @@ impl Display for ProjectTable {
     /// meaning that a Markdown renderer can show the text correctly, but it may not be the prettiest
     /// out-of-the-box (without a renderer).
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // Write column names
-        writeln!(f, "|{}|", self.column_names.join("|"))?;
+        write!(
+            f,
+            "{}",
+            self.to_markdown(&[ProjectTableColumn::Availability])
+        )
+    }
+}
 
-        // Write alignment text (:-- is left, :-: is center)
-        write!(f, "|:--|")?;
-        // Use 1..len because column names include the 'Name' for the project column
-        for _ in 1..self.column_names.len() {
-            write!(f, ":-:|")?;
-        }
-        writeln!(f)?;
-
-        let mut sorted_project_map: Vec<_> = self.project_map.iter().collect();
-        // Sort by key (human name of project)
-        sorted_project_map.sort_by(|a, b| a.0.name.cmp(&b.0.name));
-
-        let mut iter = sorted_project_map.iter().peekable();
-        while let Some(project) = iter.next() {
-            if let Some(id) = &project.0.id {
-                // If project has an id (not a manual file), write a Markdown link.
-                let mut project_url = "https://modrinth.com/project/".to_string();
-                project_url.push_str(id);
-                write!(f, "|[{}]({})|", project.0.name, project_url)?;
+impl ProjectTable {
+    /// Removes all projects that aren't in `group` from the table.
+    ///
+    /// If `group` is [`None`], only the projects that aren't in any group are kept.
+    /// The groups have to be set with [`Self::assign_groups`] first.
+    pub fn retain_group(&mut self, group: Option<&str>) {
+        self.rows.retain(|row| match group {
+            Some(group) => row.groups.iter().any(|x| x == group),
+            None => row.groups.is_empty(),
+        });
+    }
+
+    /// Sets the groups of the projects in the table from the project settings of a modpack.
+    ///
+    /// The groups aren't stored in the branch files, so that changing the groups of a project
+    /// doesn't change the files of every branch. Projects are matched by their Modrinth ID.
+    /// Projects that were added to the modpack by their slug are looked up on Modrinth
+    /// with a single request, if there are any.
+    ///
+    /// # Errors
+    /// - Errors of [`modrinth::Project::from_ids`]
+    pub fn assign_groups(
+        &mut self,
+        projects: &IndexMap<String, ProjectSettings>,
+    ) -> PackrinthResult<()> {
+        // Groups with the Modrinth project ID as key.
+        let mut project_groups: HashMap<String, &Vec<String>> = HashMap::new();
+        let mut slugs = Vec::new();
+        for (slug_project_id, project_settings) in projects {
+            if project_settings.groups.is_empty() {
+                continue;
+            }
+            if self
+                .rows
+                .iter()
+                .any(|row| row.modrinth_id.as_ref() == Some(slug_project_id))
+            {
+                project_groups.insert(slug_project_id.clone(), &project_settings.groups);
             } else {
-                write!(f, "|{}|", project.0.name)?;
+                slugs.push(slug_project_id.clone());
             }
+        }
 
-            let mut sorted_branch_map: Vec<_> = project.1.iter().collect();
-            // Sort by key (human name of project)
-            sorted_branch_map.sort_by(|a, b| a.0.cmp(b.0));
+        if !slugs.is_empty() {
+            for project in modrinth::Project::from_ids(&slugs)? {
+                if let Some(project_settings) = projects.get(&project.slug) {
+                    project_groups.insert(project.id, &project_settings.groups);
+                }
+            }
+        }
 
-            for branch in sorted_branch_map {
-                let icon = match branch.1 {
-                    Some(()) => "✅",
-                    None => "❌",
-                };
-                write!(f, "{icon}|")?;
+        for row in &mut self.rows {
+            row.groups = row
+                .modrinth_id
+                .as_ref()
+                .and_then(|modrinth_id| project_groups.get(modrinth_id))
+                .map(|groups| (*groups).clone())
+                .unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Requests the details from Modrinth that are needed for the given columns
+    /// but aren't in the branch files: the version types of the files and the categories
+    /// of the projects. All projects and all versions are requested at once.
+    ///
+    /// Details that couldn't be requested stay unknown, and the errors of the failed
+    /// requests are returned.
+    #[must_use]
+    pub fn fetch_modrinth_details(
+        &mut self,
+        columns: &[ProjectTableColumn],
+    ) -> Vec<PackrinthError> {
+        let mut errors = Vec::new();
+        if columns.contains(&ProjectTableColumn::Category) {
+            let project_ids: Vec<String> = self
+                .rows
+                .iter()
+                .filter_map(|row| row.modrinth_id.clone())
+                .collect();
+            if !project_ids.is_empty() {
+                match modrinth::FullProject::from_ids(&project_ids) {
+                    Ok(projects) => {
+                        let categories: HashMap<String, Vec<String>> = projects
+                            .into_iter()
+                            .map(|project| (project.project.id, project.categories))
+                            .collect();
+                        for row in &mut self.rows {
+                            if let Some(categories) = row
+                                .modrinth_id
+                                .as_ref()
+                                .and_then(|modrinth_id| categories.get(modrinth_id))
+                            {
+                                row.categories.clone_from(categories);
+                            }
+                        }
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+        }
+
+        if columns.contains(&ProjectTableColumn::VersionType) {
+            let mut version_ids: Vec<String> = self
+                .rows
+                .iter()
+                .flat_map(|row| row.cells.iter().flatten())
+                .filter_map(|cell| cell.version_id.clone())
+                .collect();
+            version_ids.sort();
+            version_ids.dedup();
+            if !version_ids.is_empty() {
+                match modrinth::Version::from_ids(&version_ids) {
+                    Ok(versions) => {
+                        let version_types: HashMap<String, VersionType> = versions
+                            .into_iter()
+                            .map(|version| (version.id, version.version_type))
+                            .collect();
+                        for cell in self
+                            .rows
+                            .iter_mut()
+                            .flat_map(|row| row.cells.iter_mut().flatten())
+                        {
+                            cell.version_type = cell
+                                .version_id
+                                .as_ref()
+                                .and_then(|version_id| version_types.get(version_id))
+                                .copied();
+                        }
+                    }
+                    Err(error) => errors.push(error),
+                }
             }
+        }
+        errors
+    }
 
-            // Print newline except for the last time of this loop.
-            if iter.peek().is_some() {
-                writeln!(f)?;
+    /// Returns the column titles of a table with the given columns. Branch columns get the
+    /// name of the branch as title, followed by the column title if there are multiple.
+    fn column_titles(&self, columns: &[ProjectTableColumn]) -> Vec<String> {
+        let mut titles = vec!["Name".to_string()];
+        if columns.contains(&ProjectTableColumn::Category) {
+            titles.push(ProjectTableColumn::Category.title().to_string());
+        }
+        let branch_columns = Self::branch_columns(columns);
+        for branch in &self.branches {
+            for column in &branch_columns {
+                if branch_columns.len() == 1 {
+                    titles.push(branch.clone());
+                } else {
+                    titles.push(format!("{branch} {}", column.title()));
+                }
             }
         }
+        titles
+    }
 
-        Ok(())
+    fn branch_columns(columns: &[ProjectTableColumn]) -> Vec<ProjectTableColumn> {
+        columns
+            .iter()
+            .copied()
+            .filter(|column| *column != ProjectTableColumn::Category)
+            .collect()
     }
-}
 
-impl ProjectTable {
-    /// Returns a documentation table [`String`] similar to what `display` produces,
-    /// but without the branch compatibility information.
+    /// Returns the values of a row for the given columns. `not_included` is used for branches
+    /// that don't contain the project, except in the [`ProjectTableColumn::Availability`] column.
+    fn row_values(
+        row: &ProjectTableRow,
+        columns: &[ProjectTableColumn],
+        availability: (&str, &str),
+        not_included: &str,
+    ) -> Vec<String> {
+        let mut values = Vec::new();
+        if columns.contains(&ProjectTableColumn::Category) {
+            values.push(row.categories.join(", "));
+        }
+        for cell in &row.cells {
+            for column in Self::branch_columns(columns) {
+                let value = match (column, cell) {
+                    (ProjectTableColumn::Availability, Some(_)) => availability.0.to_string(),
+                    (ProjectTableColumn::Availability, None) => availability.1.to_string(),
+                    (_, None) => not_included.to_string(),
+                    (ProjectTableColumn::FileName, Some(cell)) => cell.file_name.clone(),
+                    (ProjectTableColumn::Environment, Some(cell)) => cell.environment(),
+                    (ProjectTableColumn::VersionType, Some(cell)) => {
+                        cell.version_type().to_string()
+                    }
+                    (ProjectTableColumn::Category, Some(_)) => String::new(),
+                };
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    /// Renders the table as Markdown with the given columns. The name of every project
+    /// is always the first column.
     #[must_use]
-    pub fn display_no_compatibility_icons(&self) -> String {
+    pub fn to_markdown(&self, columns: &[ProjectTableColumn]) -> String {
         // All write macros have an unwrap call, because a write call to a String never fails.
         let mut buffer = String::new();
 
-        writeln!(buffer, "|{}|", self.column_names[0]).unwrap();
-        writeln!(buffer, "|:--|").unwrap();
+        let titles = self.column_titles(columns);
+        writeln!(buffer, "|{}|", titles.join("|")).unwrap();
 
-        let mut sorted_project_map: Vec<_> = self.project_map.iter().collect();
-        // Sort by key (human name of project)
-        sorted_project_map.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        // Write alignment text (:-- is left, :-: is center)
+        write!(buffer, "|:--|").unwrap();
+        // Use 1..len because the first column is the name of the project
+        for _ in 1..titles.len() {
+            write!(buffer, ":-:|").unwrap();
+        }
+        writeln!(buffer).unwrap();
 
-        let mut iter = sorted_project_map.iter().peekable();
-        while let Some(project) = iter.next() {
-            if let Some(id) = &project.0.id {
-                // If project has an id (not a manual file), write a Markdown link.
-                let mut project_url = "https://modrinth.com/project/".to_string();
-                project_url.push_str(id);
-                write!(buffer, "|[{}]({})|", project.0.name, project_url).unwrap();
-            } else {
-                write!(buffer, "|{}|", project.0.name).unwrap();
+        let mut iter = self.rows.iter().peekable();
+        while let Some(row) = iter.next() {
+            write!(buffer, "|{}|", row.markdown_name()).unwrap();
+            for value in Self::row_values(row, columns, ("✅", "❌"), "-") {
+                write!(buffer, "{value}|").unwrap();
             }
 
             // Print newline except for the last time of this loop.
@@ -280,9 +748,103 @@ impl ProjectTable {
 
         buffer
     }
-}
 
-// TODO extend modrinth api structs to have all possible values, not just the ones required by packrinth
+    /// Returns a documentation table [`String`] similar to what `display` produces,
+    /// but without the branch compatibility information.
+    #[deprecated(since = "0.9.0", note = "use `to_markdown(&[])` instead")]
+    #[must_use]
+    pub fn display_no_compatibility_icons(&self) -> String {
+        self.to_markdown(&[])
+    }
+
+    /// Renders the table as CSV with the given columns. The name of every project
+    /// is always the first column, followed by its Modrinth ID.
+    #[must_use]
+    pub fn to_csv(&self, columns: &[ProjectTableColumn]) -> String {
+        let csv_line = |values: Vec<String>| {
+            values
+                .iter()
+                .map(|value| {
+                    if value.contains([',', '"', '\n']) {
+                        format!("\"{}\"", value.replace('"', "\"\""))
+                    } else {
+                        value.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut buffer = String::new();
+        let mut titles = self.column_titles(columns);
+        titles.insert(1, "Modrinth ID".to_string());
+        writeln!(buffer, "{}", csv_line(titles)).unwrap();
+        for row in &self.rows {
+            let mut values = vec![
+                row.name.clone(),
+                row.modrinth_id.clone().unwrap_or_default(),
+            ];
+            values.extend(Self::row_values(row, columns, ("yes", "no"), ""));
+            writeln!(buffer, "{}", csv_line(values)).unwrap();
+        }
+        buffer
+    }
+
+    /// Renders the table as JSON with the given columns. Every project has its name,
+    /// Modrinth ID and groups, and an object with a value for every branch
+    /// that is `null` if the project isn't in the branch.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serializing the table failed
+    pub fn to_json(&self, columns: &[ProjectTableColumn]) -> PackrinthResult<String> {
+        let mut rows = Vec::new();
+        for row in &self.rows {
+            let mut json_row = serde_json::Map::new();
+            json_row.insert("name".to_string(), row.name.clone().into());
+            json_row.insert("modrinth_id".to_string(), row.modrinth_id.clone().into());
+            json_row.insert("groups".to_string(), row.groups.clone().into());
+            if columns.contains(&ProjectTableColumn::Category) {
+                json_row.insert(
+                    ProjectTableColumn::Category.json_key().to_string(),
+                    row.categories.clone().into(),
+                );
+            }
+
+            let mut json_branches = serde_json::Map::new();
+            for (branch, cell) in self.branches.iter().zip(&row.cells) {
+                let Some(cell) = cell else {
+                    json_branches.insert(branch.clone(), serde_json::Value::Null);
+                    continue;
+                };
+                let mut json_cell = serde_json::Map::new();
+                for column in Self::branch_columns(columns) {
+                    let value: serde_json::Value = match column {
+                        ProjectTableColumn::Availability => true.into(),
+                        ProjectTableColumn::FileName => cell.file_name.clone().into(),
+                        ProjectTableColumn::Environment => cell.environment().into(),
+                        ProjectTableColumn::VersionType => cell
+                            .version_type
+                            .as_ref()
+                            .map(VersionType::modrinth_value)
+                            .into(),
+                        ProjectTableColumn::Category => continue,
+                    };
+                    json_cell.insert(column.json_key().to_string(), value);
+                }
+                json_branches.insert(branch.clone(), json_cell.into());
+            }
+            json_row.insert("branches".to_string(), json_branches.into());
+            rows.push(serde_json::Value::Object(json_row));
+        }
+
+        match config::serde_json_to_string_pretty(&rows) {
+            Ok(json) => Ok(json),
+            Err(error) => Err(PackrinthError::FailedToSerialize {
+                error_message: error.to_string(),
+            }),
+        }
+    }
+}
 
 /// Utils for working with a Git-managed modpack instance.
 pub struct GitUtils;
@@ -291,7 +853,8 @@ impl GitUtils {
     /// Initializes a Git repository tailored for use with a Packrinth modpack.
     ///
     /// This means that a `.gitignore` file will be made containing the `target` directory,
-    /// the place where all exported `.mrpack` files will be located.
+    /// the place where all exported `.mrpack` files will be located, and the directory
+    /// with the backups of subcommand `migrate`.
     ///
     /// # Errors
     /// - [`PackrinthError::FailedToInitGitRepoWhileInitModpack`] if initializing the Git repository failed
@@ -318,17 +881,19 @@ impl GitUtils {
                 .create(true)
                 .open(&gitignore_path)
         {
-            if let Err(error) = writeln!(&gitignore_file, "# Exported files") {
-                return Err(PackrinthError::FailedToWriteFile {
-                    path_to_write_to: gitignore_path.display().to_string(),
-                    error_message: error.to_string(),
-                });
-            }
-            if let Err(error) = writeln!(&gitignore_file, "{TARGET_DIRECTORY}") {
-                return Err(PackrinthError::FailedToWriteFile {
-                    path_to_write_to: gitignore_path.display().to_string(),
-                    error_message: error.to_string(),
-                });
+            let lines = [
+                "# Exported files",
+                TARGET_DIRECTORY,
+                "# Backups of the files changed by subcommand migrate",
+                migration::MIGRATION_BACKUP_DIRECTORY,
+            ];
+            for line in lines {
+                if let Err(error) = writeln!(&gitignore_file, "{line}") {
+                    return Err(PackrinthError::FailedToWriteFile {
+                        path_to_write_to: gitignore_path.display().to_string(),
+                        error_message: error.to_string(),
+                    });
+                }
             }
             if let Err(error) = gitignore_file.sync_all() {
                 return Err(PackrinthError::FailedToWriteFile {
@@ -341,19 +906,515 @@ impl GitUtils {
         Ok(())
     }
 
-    /// Checks if the modpack is dirty.
+    /// Returns the files in the modpack directory with uncommitted changes, relative to the
+    /// modpack directory. Files that aren't tracked by Git are ignored.
+    ///
+    /// Changes outside the modpack directory don't make the modpack dirty.
+    /// If the modpack isn't in a Git repository, no files are returned.
     ///
-    /// It does this by checking whether the directory of the modpack
-    /// has uncommitted changes. If any errors occur (for example, if no Git repository exists),
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if opening the Git repository failed
+    /// - [`PackrinthError::FailedToGetGitStatus`] if getting the status of the repository failed
+    pub fn modpack_dirty_files(modpack: &Modpack) -> PackrinthResult<Vec<PathBuf>> {
+        Self::changed_files(&modpack.directory, gix::status::UntrackedFiles::None)
+    }
+
+    /// Checks if the modpack is dirty, which means that [`Self::modpack_dirty_files`]
+    /// returns any files. If any errors occur (for example, if getting the Git status failed),
     /// `false` will be returned.
+    #[deprecated(
+        since = "0.9.0",
+        note = "use `modpack_dirty_files`, which returns the dirty files and reports errors"
+    )]
     #[must_use]
     pub fn modpack_is_dirty(modpack: &Modpack) -> bool {
-        let git_repo = match gix::open(&modpack.directory) {
+        Self::modpack_dirty_files(modpack).is_ok_and(|files| !files.is_empty())
+    }
+
+    /// Returns the files in the modpack directory with uncommitted changes, including files
+    /// that aren't tracked by Git yet, relative to the modpack directory.
+    ///
+    /// If the modpack isn't in a Git repository, no files are returned.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if opening the Git repository failed
+    /// - [`PackrinthError::FailedToGetGitStatus`] if getting the status of the repository failed
+    pub fn modpack_changed_files(modpack: &Modpack) -> PackrinthResult<Vec<PathBuf>> {
+        Self::changed_files(&modpack.directory, gix::status::UntrackedFiles::Files)
+    }
+
+    fn changed_files(
+        directory: &Path,
+        untracked_files: gix::status::UntrackedFiles,
+    ) -> PackrinthResult<Vec<PathBuf>> {
+        if !Self::is_repository(directory) {
+            return Ok(Vec::new());
+        }
+        let failed_to_get_status = |error_message: String| PackrinthError::FailedToGetGitStatus {
+            path: directory.display().to_string(),
+            error_message,
+        };
+
+        let git_repo = match gix::discover(directory) {
+            Ok(git_repo) => git_repo,
+            Err(error) => {
+                return Err(PackrinthError::FailedToOpenGitRepo {
+                    path: directory.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let relative_directory = match (
+            directory.canonicalize(),
+            git_repo.workdir().map(Path::canonicalize),
+        ) {
+            (Ok(directory), Some(Ok(workdir))) => match directory.strip_prefix(workdir) {
+                Ok(relative_directory) => relative_directory.to_path_buf(),
+                Err(error) => return Err(failed_to_get_status(error.to_string())),
+            },
+            (Err(error), _) | (_, Some(Err(error))) => {
+                return Err(failed_to_get_status(error.to_string()));
+            }
+            (_, None) => {
+                return Err(failed_to_get_status(
+                    "the repository has no work tree".to_string(),
+                ));
+            }
+        };
+        let status = match git_repo.status(gix::progress::Discard) {
+            Ok(status) => status,
+            Err(error) => return Err(failed_to_get_status(error.to_string())),
+        };
+        let items = match status
+            .untracked_files(untracked_files)
+            .index_worktree_rewrites(None)
+            .tree_index_track_renames(gix::status::tree_index::TrackRenames::Disabled)
+            .into_iter(Vec::new())
+        {
+            Ok(items) => items,
+            Err(error) => return Err(failed_to_get_status(error.to_string())),
+        };
+
+        let mut files = Vec::new();
+        for item in items {
+            let item = match item {
+                Ok(item) => item,
+                Err(error) => return Err(failed_to_get_status(error.to_string())),
+            };
+            let location = PathBuf::from(item.location().to_string());
+            if let Ok(file) = location.strip_prefix(&relative_directory)
+                && !files.iter().any(|other_file| other_file == file)
+            {
+                files.push(file.to_path_buf());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns whether a file is ignored by Git, for example because of a `.gitignore` file.
+    ///
+    /// Like Git, files that are already tracked aren't ignored, even if they match an ignore
+    /// pattern. If the file isn't in a Git repository, `false` is returned.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if opening the Git repository failed
+    /// - [`PackrinthError::FailedToGetGitStatus`] if reading the ignore files failed
+    pub fn is_ignored(path: &Path) -> PackrinthResult<bool> {
+        if !path.parent().is_some_and(Self::is_repository) {
+            return Ok(false);
+        }
+        let failed_to_get_status = |error_message: String| PackrinthError::FailedToGetGitStatus {
+            path: path.display().to_string(),
+            error_message,
+        };
+
+        let (git_repo, relative_path) = Self::open_repo_for_path(path)?;
+        // A new repository doesn't have an index yet.
+        let index = match git_repo.index_or_empty() {
+            Ok(index) => index,
+            Err(error) => return Err(failed_to_get_status(error.to_string())),
+        };
+        let git_path = relative_path.to_string_lossy().replace('\\', "/");
+        if index
+            .entry_by_path(gix::bstr::BStr::new(&git_path))
+            .is_some()
+        {
+            return Ok(false);
+        }
+        let mut excludes = match git_repo.excludes(
+            &index,
+            None,
+            gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+        ) {
+            Ok(excludes) => excludes,
+            Err(error) => return Err(failed_to_get_status(error.to_string())),
+        };
+        match excludes.at_path(&relative_path, Some(gix::index::entry::Mode::FILE)) {
+            Ok(platform) => Ok(platform.is_excluded()),
+            Err(error) => Err(failed_to_get_status(error.to_string())),
+        }
+    }
+
+    /// Reads a file as it was at a Git revision, like `HEAD`, `HEAD~3` or `v1.0.0`.
+    ///
+    /// The file may be anywhere in the repository that contains it.
+    /// If the file didn't exist at the revision, [`None`] is returned.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if the file isn't in a Git repository with a work tree
+    /// - [`PackrinthError::GitRevisionNotFound`] if the revision doesn't point to a commit
+    /// - [`PackrinthError::FailedToReadFromGit`] if reading the file from Git failed
+    pub fn read_file_at_revision(path: &Path, revision: &str) -> PackrinthResult<Option<String>> {
+        let failed_to_read = |error_message: String| PackrinthError::FailedToReadFromGit {
+            path: path.display().to_string(),
+            revision: revision.to_string(),
+            error_message,
+        };
+
+        let (git_repo, relative_path) = Self::open_repo_for_path(path)?;
+
+        let revision_not_found = |error_message: String| PackrinthError::GitRevisionNotFound {
+            revision: revision.to_string(),
+            error_message,
+        };
+        let id = match git_repo.rev_parse_single(revision) {
+            Ok(id) => id,
+            Err(error) => return Err(revision_not_found(error.to_string())),
+        };
+        let commit = match id.object() {
+            Ok(object) => match object.peel_to_commit() {
+                Ok(commit) => commit,
+                Err(error) => return Err(revision_not_found(error.to_string())),
+            },
+            Err(error) => return Err(failed_to_read(error.to_string())),
+        };
+        let tree = match commit.tree() {
+            Ok(tree) => tree,
+            Err(error) => return Err(failed_to_read(error.to_string())),
+        };
+        let entry = match tree.lookup_entry_by_path(&relative_path) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return Ok(None),
+            Err(error) => return Err(failed_to_read(error.to_string())),
+        };
+        match entry.object() {
+            Ok(object) => match String::from_utf8(object.data.clone()) {
+                Ok(contents) => Ok(Some(contents)),
+                Err(error) => Err(failed_to_read(error.to_string())),
+            },
+            Err(error) => Err(failed_to_read(error.to_string())),
+        }
+    }
+
+    /// Returns whether a directory is inside a Git repository.
+    #[must_use]
+    pub fn is_repository(directory: &Path) -> bool {
+        gix::discover(directory).is_ok()
+    }
+
+    /// Commits files to the current branch of the Git repository that contains them.
+    ///
+    /// Only the given files are committed, with their contents in the working tree.
+    /// Files that don't exist anymore are removed. Afterward, the committed files are
+    /// updated in the index.
+    /// The ID of the new commit is returned.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if a file isn't in a Git repository with a work tree
+    /// - [`PackrinthError::FailedToReadToString`] if reading a file failed
+    /// - [`PackrinthError::FailedToCommit`] if creating the commit failed
+    pub fn commit_files(files: &[PathBuf], message: &str) -> PackrinthResult<String> {
+        let failed_to_commit =
+            |error_message: String| PackrinthError::FailedToCommit { error_message };
+        let Some(first_file) = files.first() else {
+            return Err(failed_to_commit("there are no files to commit".to_string()));
+        };
+
+        let mut file_contents = Vec::new();
+        for file in files {
+            if let Ok(false) = fs::exists(file) {
+                file_contents.push((file.clone(), None));
+                continue;
+            }
+            match fs::read(file) {
+                Ok(contents) => file_contents.push((file.clone(), Some(contents))),
+                Err(error) => {
+                    return Err(PackrinthError::FailedToReadToString {
+                        path_to_read: file.display().to_string(),
+                        error_message: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        let (git_repo, _relative_path) = Self::open_repo_for_path(first_file)?;
+        // A new repository has no commits yet, so the first commit has no parents.
+        let parents: Vec<gix::ObjectId> = git_repo
+            .head_id()
+            .ok()
+            .map(gix::Id::detach)
+            .into_iter()
+            .collect();
+        let tree_id = Self::head_tree_with_files(&git_repo, &file_contents)?;
+        let commit_id = match git_repo.commit("HEAD", message, tree_id, parents) {
+            Ok(commit_id) => commit_id.detach(),
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        };
+
+        Self::update_index(&git_repo, &file_contents)?;
+        Ok(commit_id.to_string())
+    }
+
+    /// Updates the committed files in the index. Without this, Git would show the committed
+    /// changes as not staged. Other files keep their entries, so changes that were staged
+    /// before stay staged.
+    fn update_index(
+        git_repo: &gix::Repository,
+        files: &[(PathBuf, Option<Vec<u8>>)],
+    ) -> PackrinthResult<()> {
+        let failed_to_commit =
+            |error_message: String| PackrinthError::FailedToCommit { error_message };
+
+        let mut index = match git_repo.index_or_empty() {
+            Ok(index) => gix::index::File::clone(&index),
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        };
+        for (file, contents) in files {
+            let (_git_repo, relative_path) = Self::open_repo_for_path(file)?;
+            let git_path = relative_path.to_string_lossy().replace('\\', "/");
+            let git_path = gix::bstr::BStr::new(&git_path);
+            let Some(contents) = contents else {
+                index.remove_entries(|_index, path, _entry| path == git_path);
+                continue;
+            };
+
+            let id = match gix::objs::compute_hash(
+                git_repo.object_hash(),
+                gix::objs::Kind::Blob,
+                contents,
+            ) {
+                Ok(id) => id,
+                Err(error) => return Err(failed_to_commit(error.to_string())),
+            };
+            let stat = match gix::index::fs::Metadata::from_path_no_follow(file) {
+                Ok(metadata) => gix::index::entry::Stat::from_fs(&metadata).unwrap_or_default(),
+                Err(error) => return Err(failed_to_commit(error.to_string())),
+            };
+            if let Some(entry) =
+                index.entry_mut_by_path_and_stage(git_path, gix::index::entry::Stage::Unconflicted)
+            {
+                entry.id = id;
+                entry.stat = stat;
+            } else {
+                index.dangerously_push_entry(
+                    stat,
+                    id,
+                    gix::index::entry::Flags::empty(),
+                    gix::index::entry::Mode::FILE,
+                    git_path,
+                );
+                // Looking up entries by path only works if they are sorted.
+                index.sort_entries();
+            }
+        }
+        // The cached trees don't match the changed entries anymore.
+        index.remove_tree();
+
+        match index.write(gix::index::write::Options::default()) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(failed_to_commit(error.to_string())),
+        }
+    }
+
+    /// Commits files with the given contents to a Git branch that starts at the `HEAD` commit.
+    ///
+    /// The working tree, the index and the current branch aren't changed. If the Git branch
+    /// already exists, it is replaced. The ID of the new commit is returned.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if a file isn't in a Git repository with a work tree
+    /// - [`PackrinthError::FailedToCommit`] if creating the commit or the branch failed
+    pub fn commit_to_branch(
+        git_branch: &str,
+        files: &[(PathBuf, Vec<u8>)],
+        message: &str,
+    ) -> PackrinthResult<String> {
+        let failed_to_commit =
+            |error_message: String| PackrinthError::FailedToCommit { error_message };
+        let Some((first_file, _contents)) = files.first() else {
+            return Err(failed_to_commit("there are no files to commit".to_string()));
+        };
+
+        let (git_repo, _relative_path) = Self::open_repo_for_path(first_file)?;
+        let head_id = match git_repo.head_id() {
+            Ok(head_id) => head_id.detach(),
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        };
+        let file_contents: Vec<(PathBuf, Option<Vec<u8>>)> = files
+            .iter()
+            .map(|(file, contents)| (file.clone(), Some(contents.clone())))
+            .collect();
+        let tree_id = Self::head_tree_with_files(&git_repo, &file_contents)?;
+
+        let reference = format!("refs/heads/{git_branch}");
+        match git_repo.try_find_reference(reference.as_str()) {
+            Ok(Some(existing_reference)) => {
+                if let Err(error) = existing_reference.delete() {
+                    return Err(failed_to_commit(error.to_string()));
+                }
+            }
+            Ok(None) => {}
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        }
+        match git_repo.commit(reference.as_str(), message, tree_id, [head_id]) {
+            Ok(commit_id) => Ok(commit_id.to_string()),
+            Err(error) => Err(failed_to_commit(error.to_string())),
+        }
+    }
+
+    /// Writes the tree of the `HEAD` commit with the given files changed, and returns its ID.
+    /// Files without contents are removed.
+    fn head_tree_with_files(
+        git_repo: &gix::Repository,
+        files: &[(PathBuf, Option<Vec<u8>>)],
+    ) -> PackrinthResult<gix::ObjectId> {
+        let failed_to_commit =
+            |error_message: String| PackrinthError::FailedToCommit { error_message };
+
+        let head_tree_id = match git_repo.head_tree_id_or_empty() {
+            Ok(head_tree_id) => head_tree_id,
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        };
+        let mut tree_editor = match git_repo.edit_tree(head_tree_id) {
+            Ok(tree_editor) => tree_editor,
+            Err(error) => return Err(failed_to_commit(error.to_string())),
+        };
+
+        for (file, contents) in files {
+            let (_git_repo, relative_path) = Self::open_repo_for_path(file)?;
+            let git_path = relative_path.to_string_lossy().replace('\\', "/");
+            let Some(contents) = contents else {
+                if let Err(error) = tree_editor.remove(git_path) {
+                    return Err(failed_to_commit(error.to_string()));
+                }
+                continue;
+            };
+
+            let blob_id = match git_repo.write_blob(contents) {
+                Ok(blob_id) => blob_id,
+                Err(error) => return Err(failed_to_commit(error.to_string())),
+            };
+            if let Err(error) =
+                tree_editor.upsert(git_path, gix::objs::tree::EntryKind::Blob, blob_id)
+            {
+                return Err(failed_to_commit(error.to_string()));
+            }
+        }
+
+        match tree_editor.write() {
+            Ok(tree_id) => Ok(tree_id.detach()),
+            Err(error) => Err(failed_to_commit(error.to_string())),
+        }
+    }
+
+    /// Creates an annotated tag that points to the `HEAD` commit of the Git repository
+    /// that contains the directory.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToOpenGitRepo`] if the directory isn't in a Git repository
+    /// - [`PackrinthError::FailedToCreateTag`] if the tag already exists or creating it failed
+    pub fn tag_head(directory: &Path, tag: &str, message: &str) -> PackrinthResult<()> {
+        let failed_to_create_tag = |error_message: String| PackrinthError::FailedToCreateTag {
+            tag: tag.to_string(),
+            error_message,
+        };
+        let git_repo = match gix::discover(directory) {
             Ok(git_repo) => git_repo,
-            Err(_error) => return false,
+            Err(error) => {
+                return Err(PackrinthError::FailedToOpenGitRepo {
+                    path: directory.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let head_id = match git_repo.head_id() {
+            Ok(head_id) => head_id.detach(),
+            Err(error) => return Err(failed_to_create_tag(error.to_string())),
+        };
+        let tagger = match git_repo.committer() {
+            Some(Ok(tagger)) => tagger,
+            Some(Err(error)) => return Err(failed_to_create_tag(error.to_string())),
+            None => {
+                return Err(failed_to_create_tag(
+                    "no Git user name and email are configured".to_string(),
+                ));
+            }
         };
 
-        git_repo.is_dirty().unwrap_or(false)
+        if let Err(error) = git_repo.tag(
+            tag,
+            head_id,
+            gix::objs::Kind::Commit,
+            Some(tagger),
+            message,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+        ) {
+            return Err(failed_to_create_tag(error.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Opens the Git repository that contains a file and returns the path of the file
+    /// relative to the work tree of the repository.
+    fn open_repo_for_path(path: &Path) -> PackrinthResult<(gix::Repository, PathBuf)> {
+        let failed_to_open = |error_message: String| PackrinthError::FailedToOpenGitRepo {
+            path: path.display().to_string(),
+            error_message,
+        };
+
+        // The file and even its parent directories may have been removed,
+        // so start at the closest directory that still exists.
+        let mut parent = path;
+        let mut missing_components = Vec::new();
+        loop {
+            let (Some(next_parent), Some(file_name)) = (parent.parent(), parent.file_name()) else {
+                return Err(failed_to_open(
+                    "the path has no parent directory".to_string(),
+                ));
+            };
+            missing_components.push(file_name);
+            parent = next_parent;
+            if parent.is_dir() {
+                break;
+            }
+        }
+        let parent = match parent.canonicalize() {
+            Ok(parent) => parent,
+            Err(error) => return Err(failed_to_open(error.to_string())),
+        };
+        let git_repo = match gix::discover(&parent) {
+            Ok(git_repo) => git_repo,
+            Err(error) => return Err(failed_to_open(error.to_string())),
+        };
+        let Some(workdir) = git_repo.workdir() else {
+            return Err(failed_to_open(
+                "the repository has no work tree".to_string(),
+            ));
+        };
+        let relative_path = match workdir.canonicalize() {
+            Ok(workdir) => match parent.strip_prefix(workdir) {
+                Ok(relative_parent) => missing_components
+                    .iter()
+                    .rev()
+                    .fold(relative_parent.to_path_buf(), |path, component| {
+                        path.join(component)
+                    }),
+                Err(error) => return Err(failed_to_open(error.to_string())),
+            },
+            Err(error) => return Err(failed_to_open(error.to_string())),
+        };
+        Ok((git_repo, relative_path))
     }
 }
 
@@ -443,6 +1504,40 @@ pub enum PackrinthError {
         error_message: String,
     },
     AttemptedToAddOtherModpack,
+    ProjectNotFound {
+        project: String,
+    },
+    ProjectIsModpack {
+        project: String,
+    },
+    ProjectAlreadyAdded {
+        project: String,
+        added_as: String,
+    },
+    UnsupportedProjectType {
+        project_type: String,
+    },
+    NoDatapackDirectory {
+        project: String,
+    },
+    UnknownManualFileDirectory {
+        file_name: String,
+    },
+    InvalidManualFilePath {
+        path: String,
+    },
+    ManualFileAlreadyExists {
+        path: String,
+    },
+    ManualFileDoesNotExist {
+        path: String,
+    },
+    ManualFileHasNoDownloads {
+        path: String,
+    },
+    NoDownloadUrlSpecified {
+        file: String,
+    },
     NoModrinthFilesFoundForProject {
         project: String,
     },
@@ -453,13 +1548,19 @@ pub enum PackrinthError {
     FailedToGetCurrentDirectory {
         error_message: String,
     },
-    InvalidPackFormat {
+    PackFormatTooNew {
+        used_pack_format: u16,
+    },
+    PackFormatOutdated {
         used_pack_format: u16,
     },
     NoBranchSpecified,
     NoInclusionsSpecified,
     NoExclusionsSpecified,
-    RepoIsDirty,
+    RepoIsDirty {
+        files: Vec<String>,
+        can_allow_dirty: bool,
+    },
     FailedToInitGitRepoWhileInitModpack {
         error_message: String,
     },
@@ -484,6 +1585,9 @@ pub enum PackrinthError {
     BranchAlreadyExists {
         branch: String,
     },
+    BranchNameIsReserved {
+        branch: String,
+    },
     FailedToRemoveDir {
         dir_to_remove: String,
         error_message: String,
@@ -496,6 +1600,84 @@ pub enum PackrinthError {
         version_to_parse: String,
         error_message: String,
     },
+    InvalidBranchRule {
+        rule: String,
+        error_message: String,
+    },
+    GroupDoesNotExist {
+        group: String,
+    },
+    BranchInheritanceCycle {
+        branch: String,
+    },
+    FailedToRenameDir {
+        from: String,
+        to: String,
+        error_message: String,
+    },
+    FailedToCopyFile {
+        from: String,
+        to: String,
+        error_message: String,
+    },
+    BranchIsExtended {
+        branch: String,
+        extended_by: String,
+    },
+    FailedToRemoveFile {
+        file_to_remove: String,
+        error_message: String,
+    },
+    FailedToParseLoaderMetaResponseJson {
+        url: String,
+        error_message: String,
+    },
+    NoLoaderVersionFound {
+        loader: String,
+        minecraft_version: String,
+    },
+    UnknownMinecraftVersion {
+        version: String,
+    },
+    ModpackIsInvalid {
+        errors: usize,
+    },
+    DeniedLicensesFound {
+        count: usize,
+    },
+    FailedToOpenGitRepo {
+        path: String,
+        error_message: String,
+    },
+    GitRevisionNotFound {
+        revision: String,
+        error_message: String,
+    },
+    FailedToReadFromGit {
+        path: String,
+        revision: String,
+        error_message: String,
+    },
+    FailedToCommit {
+        error_message: String,
+    },
+    FailedToCreateTag {
+        tag: String,
+        error_message: String,
+    },
+    BranchVersionIsNotSemver {
+        branch: String,
+        version: String,
+        error_message: String,
+    },
+    NoModrinthProjectId,
+    NoModrinthToken {
+        env_var: String,
+    },
+    FailedToGetGitStatus {
+        path: String,
+        error_message: String,
+    },
 }
 
 impl PackrinthError {
@@ -532,14 +1714,26 @@ impl PackrinthError {
             PackrinthError::FailedToFinishZip => ("failed to finish zip".to_string(), file_an_issue),
             PackrinthError::BranchDoesNotExist { branch, error_message } => (format!("branch {branch} doesn't exist: {error_message}"), "add a branch with subcommand: branch add".to_string()),
             PackrinthError::AttemptedToAddOtherModpack => ("one of the projects is another modpack".to_string(), "remove the modpack project with subcommand: project remove <MODPACK_PROJECT>".to_string()),
+            PackrinthError::ProjectNotFound { project } => (format!("project {project} doesn't exist on Modrinth"), "check if the project id is spelled correctly or find the project with subcommand: search".to_string()),
+            PackrinthError::ProjectIsModpack { project } => (format!("project {project} is a modpack"), "only mods, resource packs, shaders, data packs and plugins can be added".to_string()),
+            PackrinthError::ProjectAlreadyAdded { project, added_as } => (format!("project {project} is already added as {added_as}"), "remove it first with subcommand: project remove".to_string()),
+            PackrinthError::UnsupportedProjectType { project_type } => (format!("one of the projects has project type {project_type}, which isn't supported"), "remove the project with subcommand: project remove <PROJECT>, or add its file as a manual file".to_string()),
+            PackrinthError::NoDatapackDirectory { project } => (format!("{project} is a data pack, but the branch doesn't have a data pack directory"), "add datapack_directory to branch.json, or remove datapack from acceptable_loaders to use the mod version".to_string()),
+            PackrinthError::UnknownManualFileDirectory { file_name } => (format!("can't tell which directory {file_name} belongs in"), "pass the path of the file in the instance with the --path flag".to_string()),
+            PackrinthError::InvalidManualFilePath { path } => (format!("manual file path {path} isn't a path inside the instance directory"), "pass a relative path without '..' with the --path flag".to_string()),
+            PackrinthError::ManualFileAlreadyExists { path } => (format!("a manual file with path {path} already exists"), "pass the --force flag to replace it".to_string()),
+            PackrinthError::ManualFileDoesNotExist { path } => (format!("there is no manual file with path {path}"), "list the manual files of the branch with subcommand: manual list".to_string()),
+            PackrinthError::ManualFileHasNoDownloads { path } => (format!("manual file {path} doesn't have a download URL"), "add a download URL to the manual file in branch.json".to_string()),
+            PackrinthError::NoDownloadUrlSpecified { file } => (format!("no download URL specified for local file {file}"), "pass the URL the file can be downloaded from with the --url flag".to_string()),
             PackrinthError::NoModrinthFilesFoundForProject { project } => (format!("no files found for project {project}"), "check if the project id is spelled correctly or try to remove or add project inclusions, exclusions or overrides".to_string()),
             PackrinthError::RequestFailed { url, error_message } => (format!("request to {url} failed: {error_message}"), format!("check your internet connection or {file_an_issue}")),
             PackrinthError::FailedToGetCurrentDirectory { error_message } => (format!("couldn't get the current directory: {error_message}"), "the current directory may not exist or you have insufficient permissions to access the current directory".to_string()),
-            PackrinthError::InvalidPackFormat { used_pack_format } => (format!("pack format {used_pack_format} is not supported by this Packrinth version"), format!("please use a configuration with pack format {}", config::CURRENT_PACK_FORMAT)),
+            PackrinthError::PackFormatTooNew { used_pack_format } => (format!("pack format {used_pack_format} is newer than pack format {}, which this Packrinth version uses", config::CURRENT_PACK_FORMAT), "update Packrinth to use this modpack".to_string()),
+            PackrinthError::PackFormatOutdated { used_pack_format } => (format!("pack format {used_pack_format} is older than pack format {}, which this Packrinth version uses", config::CURRENT_PACK_FORMAT), "upgrade the modpack with subcommand: migrate".to_string()),
             PackrinthError::NoBranchSpecified => ("no branch specified".to_string(), "specify a branch or remove all with the --all flag".to_string()),
             PackrinthError::NoInclusionsSpecified => ("no inclusions specified".to_string(), "specify inclusions or remove all with the --all flag".to_string()),
             PackrinthError::NoExclusionsSpecified => ("no exclusions specified".to_string(), "specify exclusions or remove all with the --all flag".to_string()),
-            PackrinthError::RepoIsDirty => ("git repository has uncommitted changes".to_string(), "pass the --allow-dirty flag to force continuing".to_string()),
+            PackrinthError::RepoIsDirty { files, can_allow_dirty } => (format!("git repository has uncommitted changes in the modpack: {}", files.join(", ")), if *can_allow_dirty { "pass the --allow-dirty flag to force continuing" } else { "commit or stash your changes first" }.to_string()),
             PackrinthError::FailedToInitGitRepoWhileInitModpack { error_message } => (format!("failed to initialize Git repository: {error_message}"), "the modpack itself was initialized successfully, so you can try to initialize a Git repository yourself".to_string()),
             PackrinthError::ModpackAlreadyExists { directory } => (format!("a modpack instance already exists in {directory}"), "to force initializing a new repository, pass the --force flag".to_string()),
             PackrinthError::MainModLoaderProvidedButNoVersion => ("a main mod loader was specified for a branch, but no version was provided".to_string(), "add the loader_version to branch.json".to_string()),
@@ -547,9 +1741,280 @@ impl PackrinthError {
             PackrinthError::FailedToCreateZipArchive { zip_path, error_message } => (format!("failed to create zip archive for zip at {zip_path}: {error_message}"), "check if you have sufficient permissions and if the zip file exists".to_string()),
             PackrinthError::InvalidMrPack { mrpack_path, error_message } => (format!("Modrinth pack at {mrpack_path} is invalid: {error_message}"), "make sure you adhere to the specifications (https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack)".to_string()),
             PackrinthError::FailedToExtractMrPack { mrpack_path, output_directory, error_message } => (format!("failed to extract Modrinth pack at {mrpack_path} to {output_directory}: {error_message}"), "check if you have sufficient permissions".to_string()),
+            PackrinthError::BranchNameIsReserved { branch } => (format!("{branch} can't be used as a branch name, because Packrinth uses a directory with that name"), format!("choose a name other than {}", config::RESERVED_BRANCH_NAMES.join(", "))),
             PackrinthError::BranchAlreadyExists { branch } => (format!("branch {branch} already exists"), "you can still continue by passing the --force flag".to_string()),
             PackrinthError::FailedToRemoveDir { dir_to_remove, error_message } => (format!("failed to remove directory {dir_to_remove}: {error_message}"), "check if you have sufficient permissions and if the directory exists".to_string()),
             PackrinthError::FailedToParseSemverVersion { version_to_parse, error_message } => (format!("failed to parse semver version {version_to_parse}: {error_message}"), file_an_issue),
+            PackrinthError::InvalidBranchRule { rule, error_message } => (format!("branch rule {rule} is invalid: {error_message}"), "write rules like: loader = neoforge, minecraft >= 1.21 or branch ~ \"*-server\"".to_string()),
+            PackrinthError::GroupDoesNotExist { group } => (format!("group {group} doesn't exist"), "add a project to the group with subcommand: project group add".to_string()),
+            PackrinthError::BranchInheritanceCycle { branch } => (format!("branch {branch} extends itself"), "change the extends value in the branch.json files so that no branch extends itself, directly or via other branches".to_string()),
+            PackrinthError::FailedToRenameDir { from, to, error_message } => (format!("failed to move directory {from} to {to}: {error_message}"), "check if you have sufficient permissions and if the directory exists".to_string()),
+            PackrinthError::FailedToCopyFile { from, to, error_message } => (format!("failed to copy file {from} to {to}: {error_message}"), "check if you have sufficient permissions and if the file exists".to_string()),
+            PackrinthError::BranchIsExtended { branch, extended_by } => (format!("branch {branch} is extended by branch {extended_by}"), format!("remove branch {extended_by} as well, or remove its extends value")),
+            PackrinthError::FailedToRemoveFile { file_to_remove, error_message } => (format!("failed to remove file {file_to_remove}: {error_message}"), "check if you have sufficient permissions and if the file exists".to_string()),
+            PackrinthError::FailedToParseLoaderMetaResponseJson { url, error_message } => (format!("loader metadata response from {url} is invalid: {error_message}"), "check if the loader metadata service is available".to_string()),
+            PackrinthError::NoLoaderVersionFound { loader, minecraft_version } => (format!("no stable {loader} version found for Minecraft {minecraft_version}"), "set the loader version manually in the branch configuration".to_string()),
+            PackrinthError::UnknownMinecraftVersion { version } => (format!("{version} is not a known Minecraft version"), "check the Minecraft versions in the branch configuration, for example 1.21.8 or 25w31a".to_string()),
+            PackrinthError::ModpackIsInvalid { errors } => (format!("the modpack configuration has {errors} error(s)"), "fix the errors above; update and export can skip this check with the --no-validate flag".to_string()),
+            PackrinthError::DeniedLicensesFound { count } => (format!("{count} project(s) or file(s) have a denied license"), "remove them from the branches or remove their licenses from denied_licenses in the modpack configuration".to_string()),
+            PackrinthError::FailedToOpenGitRepo { path, error_message } => (format!("failed to find a Git repository for {path}: {error_message}"), "make sure the modpack is in a Git repository with at least one commit".to_string()),
+            PackrinthError::GitRevisionNotFound { revision, error_message } => (format!("Git revision {revision} doesn't point to a commit: {error_message}"), "use a commit hash, branch, tag or something like HEAD~2".to_string()),
+            PackrinthError::FailedToReadFromGit { path, revision, error_message } => (format!("failed to read {path} at Git revision {revision}: {error_message}"), "check if the Git repository is valid".to_string()),
+            PackrinthError::FailedToCommit { error_message } => (format!("failed to create Git commit: {error_message}"), "make sure the repository has at least one commit and a Git user name and email are configured".to_string()),
+            PackrinthError::FailedToCreateTag { tag, error_message } => (format!("failed to create Git tag {tag}: {error_message}"), "if the tag already exists, remove it or bump the version again".to_string()),
+            PackrinthError::BranchVersionIsNotSemver { branch, version, error_message } => (format!("version {version} of branch {branch} isn't a semantic version: {error_message}"), "use a version like 1.2.3, 1.2.3-fabric or 1.2.3+mc1.21.8 in the branch configuration".to_string()),
+            PackrinthError::NoModrinthProjectId => ("the modpack has no Modrinth project to publish to".to_string(), "set modrinth_project_id in the modpack configuration to the ID of your Modrinth project".to_string()),
+            PackrinthError::NoModrinthToken { env_var } => (format!("no Modrinth token found in environment variable {env_var}"), format!("set {env_var} to a Modrinth personal access token with the 'Create versions' scope")),
+            PackrinthError::FailedToGetGitStatus { path, error_message } => (format!("failed to get the Git status of {path}: {error_message}"), "check if the Git repository isn't corrupted".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::file;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn project_table_follows_branch_order() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        // Not in alphabetical order, to check that the columns follow the modpack configuration.
+        for branch in ["1.21.8", "1.20.1"] {
+            modpack
+                .new_branch(branch)
+                .unwrap()
+                .save(directory, branch)
+                .unwrap();
+        }
+
+        let mut branch_files = BranchFiles::default();
+        branch_files.projects = vec![BranchFilesProject {
+            name: "Sodium".to_string(),
+            id: Some("AANobbMI".to_string()),
+        }];
+        branch_files.files = vec![
+            File {
+                env: Some(Env {
+                    client: SideSupport::Required,
+                    server: SideSupport::Unsupported,
+                }),
+                ..file(
+                    "mods/sodium-0.6.13.jar",
+                    "a",
+                    "https://cdn.modrinth.com/data/AANobbMI/versions/a/sodium-0.6.13.jar",
+                )
+            },
+            file("mods/manual.jar", "b", "https://github.com/manual.jar"),
+        ];
+        branch_files.save(directory, "1.21.8").unwrap();
+        branch_files.files = vec![File {
+            env: Some(Env {
+                client: SideSupport::Required,
+                server: SideSupport::Optional,
+            }),
+            ..file(
+                "mods/sodium-0.5.3.jar",
+                "c",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/c/sodium-0.5.3.jar",
+            )
+        }];
+        branch_files.save(directory, "1.20.1").unwrap();
+
+        let mut table = modpack.generate_project_table().unwrap();
+        assert_eq!(vec!["1.21.8", "1.20.1"], table.branches);
+        table.rows[0].cells[0].as_mut().unwrap().version_type = Some(VersionType::Beta);
+
+        assert_eq!(
+            "|Name|1.21.8|1.20.1|
+|:--|:-:|:-:|
+|[Sodium](https://modrinth.com/project/AANobbMI)|✅|✅|
+|manual.jar|✅|❌|",
+            table.to_string()
+        );
+        assert_eq!(
+            "|Name|
+|:--|
+|[Sodium](https://modrinth.com/project/AANobbMI)|
+|manual.jar|",
+            table.to_markdown(&[])
+        );
+
+        let columns = [
+            ProjectTableColumn::FileName,
+            ProjectTableColumn::Environment,
+            ProjectTableColumn::VersionType,
+        ];
+        assert_eq!(
+            "Name,Modrinth ID,1.21.8 File,1.21.8 Environment,1.21.8 Version type,1.20.1 File,1.20.1 Environment,1.20.1 Version type
+Sodium,AANobbMI,sodium-0.6.13.jar,Client,Beta,sodium-0.5.3.jar,Client and server (optional),Unknown
+manual.jar,,manual.jar,Client and server,Unknown,,,
+",
+            table.to_csv(&columns)
+        );
+
+        let performance = ProjectSettings {
+            groups: vec!["performance".to_string()],
+            ..ProjectSettings::default()
+        };
+        table
+            .assign_groups(&IndexMap::from([("AANobbMI".to_string(), performance)]))
+            .unwrap();
+        table.retain_group(Some("performance"));
+        assert_eq!(
+            serde_json::json!([{
+                "name": "Sodium",
+                "modrinth_id": "AANobbMI",
+                "groups": ["performance"],
+                "branches": {
+                    "1.21.8": {
+                        "file_name": "sodium-0.6.13.jar",
+                        "environment": "Client",
+                        "version_type": "beta"
+                    },
+                    "1.20.1": {
+                        "file_name": "sodium-0.5.3.jar",
+                        "environment": "Client and server (optional)",
+                        "version_type": null
+                    }
+                }
+            }]),
+            serde_json::from_str::<serde_json::Value>(&table.to_json(&columns).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn git_changes_are_scoped_to_modpack() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let repo_dir = temp_dir.path();
+        GitUtils::initialize_modpack_repo(repo_dir).unwrap();
+        let mut git_config = OpenOptions::new()
+            .append(true)
+            .open(repo_dir.join(".git").join("config"))
+            .unwrap();
+        writeln!(
+            git_config,
+            "[user]\n\tname = Packrinth\n\temail = packrinth@example.com"
+        )
+        .unwrap();
+
+        let modpack = Modpack {
+            directory: repo_dir.join("pack"),
+            ..Modpack::default()
+        };
+        fs::create_dir_all(modpack.directory.join("1.21.8")).unwrap();
+        fs::write(repo_dir.join("README.md"), "readme").unwrap();
+        fs::write(modpack.directory.join("modpack.json"), "{}").unwrap();
+        fs::write(modpack.directory.join("1.21.8").join("old.txt"), "old").unwrap();
+        let files: Vec<PathBuf> = [
+            repo_dir.join("README.md"),
+            modpack.directory.join("modpack.json"),
+            modpack.directory.join("1.21.8").join("old.txt"),
+        ]
+        .to_vec();
+        GitUtils::commit_files(&files, "Initial commit").unwrap();
+        assert!(
+            GitUtils::modpack_changed_files(&modpack)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Changes outside the modpack directory don't matter.
+        fs::write(repo_dir.join("README.md"), "changed readme").unwrap();
+        assert!(GitUtils::modpack_dirty_files(&modpack).unwrap().is_empty());
+
+        fs::write(modpack.directory.join("modpack.json"), "{\"name\": \"\"}").unwrap();
+        fs::remove_dir_all(modpack.directory.join("1.21.8")).unwrap();
+        fs::create_dir(modpack.directory.join("1.21.9")).unwrap();
+        fs::write(modpack.directory.join("1.21.9").join("new.txt"), "new").unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("1.21.8/old.txt"),
+                PathBuf::from("modpack.json")
+            ],
+            GitUtils::modpack_dirty_files(&modpack).unwrap()
+        );
+        let changed_files = GitUtils::modpack_changed_files(&modpack).unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("1.21.8/old.txt"),
+                PathBuf::from("1.21.9/new.txt"),
+                PathBuf::from("modpack.json")
+            ],
+            changed_files
+        );
+
+        let paths: Vec<PathBuf> = changed_files
+            .iter()
+            .map(|file| modpack.directory.join(file))
+            .collect();
+        GitUtils::commit_files(&paths, "Update modpack").unwrap();
+        assert!(
+            GitUtils::modpack_changed_files(&modpack)
+                .unwrap()
+                .is_empty()
+        );
+        // Files that weren't committed keep their entries in the index.
+        let index = gix::open(repo_dir).unwrap().open_index().unwrap();
+        let readme_entry = index.entry_by_path("README.md".into()).unwrap();
+        assert_ne!(gix::index::entry::Stat::default(), readme_entry.stat);
+        assert_eq!(
+            Some("new".to_string()),
+            GitUtils::read_file_at_revision(&modpack.directory.join("1.21.9/new.txt"), "HEAD")
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            GitUtils::read_file_at_revision(&modpack.directory.join("1.21.8/old.txt"), "HEAD")
+                .unwrap()
+        );
+
+        // Committing to another branch leaves the working tree and the current branch alone,
+        // and replaces the branch if it already exists.
+        let new_file = modpack.directory.join("1.21.9/new.txt");
+        for contents in ["first", "second"] {
+            GitUtils::commit_to_branch(
+                "packrinth/update-new",
+                &[(new_file.clone(), contents.as_bytes().to_vec())],
+                "Update new.txt",
+            )
+            .unwrap();
         }
+        assert_eq!(
+            Some("second".to_string()),
+            GitUtils::read_file_at_revision(&new_file, "packrinth/update-new").unwrap()
+        );
+        assert_eq!(
+            Some("new".to_string()),
+            GitUtils::read_file_at_revision(&new_file, "packrinth/update-new~1").unwrap()
+        );
+        assert_eq!("new", fs::read_to_string(&new_file).unwrap());
+        assert!(
+            GitUtils::modpack_changed_files(&modpack)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn project_availability_uses_most_stable_version_type() {
+        assert_eq!(
+            ProjectAvailability::Release,
+            ProjectAvailability::from_version_types(&[VersionType::Alpha, VersionType::Release])
+        );
+        assert_eq!(
+            ProjectAvailability::Beta,
+            ProjectAvailability::from_version_types(&[VersionType::Alpha, VersionType::Beta])
+        );
+        assert_eq!(
+            ProjectAvailability::Alpha,
+            ProjectAvailability::from_version_types(&[VersionType::Alpha])
+        );
+        assert_eq!(
+            ProjectAvailability::NotFound,
+            ProjectAvailability::from_version_types(&[])
+        );
     }
 }
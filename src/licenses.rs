@@ -0,0 +1,409 @@
+//! Auditing the licenses of everything in a branch before the modpack is redistributed.
+
+use crate::config::{BranchConfig, BranchFiles, Modpack, OVERRIDE_DIRS};
+use crate::modrinth::{File, FullProject, License};
+use crate::{PackrinthError, PackrinthResult};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+/// The name of the attribution file that is written to the `overrides` directory of a branch.
+pub const CREDITS_FILE_NAME: &str = "CREDITS.md";
+
+/// The license ID Modrinth uses for projects that are All Rights Reserved.
+pub const ALL_RIGHTS_RESERVED: &str = "LicenseRef-All-Rights-Reserved";
+
+/// The license ID Modrinth uses for projects without a known license.
+///
+/// Files that aren't from Modrinth also get this license in a deny-list.
+pub const UNKNOWN_LICENSE: &str = "LicenseRef-Unknown";
+
+/// How freely content with a license may be redistributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LicenseKind {
+    /// A license from the SPDX license list.
+    Spdx,
+
+    /// A custom license that has to be read before redistributing.
+    Custom,
+
+    AllRightsReserved,
+
+    /// The license isn't known, like for files that aren't from Modrinth.
+    Unknown,
+}
+
+impl LicenseKind {
+    /// Classifies a Modrinth license by its ID.
+    #[must_use]
+    pub fn from_license(license: &License) -> Self {
+        match license.id.as_str() {
+            ALL_RIGHTS_RESERVED => Self::AllRightsReserved,
+            UNKNOWN_LICENSE => Self::Unknown,
+            id if id.starts_with("LicenseRef-") => Self::Custom,
+            _ => Self::Spdx,
+        }
+    }
+
+    /// Returns whether content with this kind of license may not be redistributed
+    /// without checking the license or asking permission.
+    #[must_use]
+    pub const fn is_restrictive(self) -> bool {
+        !matches!(self, Self::Spdx)
+    }
+}
+
+/// Where content in a branch comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentSource {
+    Modrinth,
+
+    /// A manual file, or another file in the branch files that isn't from Modrinth.
+    Manual,
+
+    /// A jar file in one of the override directories.
+    Override,
+}
+
+/// The license of a project or file in a branch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LicenseEntry {
+    /// The title of the Modrinth project, or the path of the file if it isn't from Modrinth.
+    pub name: String,
+
+    /// The slug of the Modrinth project, if the file is from Modrinth.
+    pub slug: Option<String>,
+
+    /// The license of the Modrinth project. Files that aren't from Modrinth don't have a license.
+    pub license: Option<License>,
+    pub kind: LicenseKind,
+    pub source: ContentSource,
+}
+
+impl LicenseEntry {
+    /// Returns the license ID, or [`UNKNOWN_LICENSE`] if there is no license.
+    #[must_use]
+    pub fn license_id(&self) -> &str {
+        self.license
+            .as_ref()
+            .map_or(UNKNOWN_LICENSE, |license| license.id.as_str())
+    }
+
+    /// Returns a human-readable description of the license.
+    #[must_use]
+    pub fn license_label(&self) -> String {
+        self.license
+            .as_ref()
+            .map_or_else(|| "unknown license".to_string(), License::label)
+    }
+}
+
+/// The licenses of everything in a branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchLicenses {
+    pub branch: String,
+    pub entries: Vec<LicenseEntry>,
+}
+
+impl BranchLicenses {
+    /// Collects the licenses of the projects and files in the branch files of a branch,
+    /// its manual files and the jar files in its override directories.
+    ///
+    /// Modrinth projects are looked up in `projects` by their ID first, so projects that are
+    /// in multiple branches are only requested once. The other projects are requested
+    /// with one request and added to `projects`. Projects that Modrinth doesn't know
+    /// get an unknown license.
+    ///
+    /// # Errors
+    /// - Errors of [`BranchConfig::from_directory`] and [`BranchFiles::from_directory`]
+    /// - Errors of [`FullProject::from_ids`]
+    /// - [`PackrinthError::FailedToGetWalkDirEntry`] if reading an override directory failed
+    pub fn collect(
+        modpack: &Modpack,
+        branch: &str,
+        projects: &mut HashMap<String, FullProject>,
+    ) -> PackrinthResult<Self> {
+        let branch_config = BranchConfig::from_directory(&modpack.directory, branch)?;
+        let branch_files = BranchFiles::from_directory(&modpack.directory, branch)?;
+
+        let mut missing_project_ids: Vec<String> = branch_files
+            .files
+            .iter()
+            .filter_map(File::modrinth_project_id)
+            .filter(|project_id| !projects.contains_key(*project_id))
+            .map(ToString::to_string)
+            .collect();
+        missing_project_ids.sort();
+        missing_project_ids.dedup();
+        if !missing_project_ids.is_empty() {
+            for project in FullProject::from_ids(&missing_project_ids)? {
+                projects.insert(project.project.id.clone(), project);
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut manual_paths = Vec::new();
+        for file in &branch_files.files {
+            if let Some(project_id) = file.modrinth_project_id() {
+                let Some(full_project) = projects.get(project_id) else {
+                    // Modrinth doesn't know the project anymore, so its license is unknown.
+                    entries.push(LicenseEntry {
+                        slug: Some(project_id.to_string()),
+                        ..Self::unknown_entry(file.path.clone(), ContentSource::Modrinth)
+                    });
+                    continue;
+                };
+                if entries.iter().any(|entry: &LicenseEntry| {
+                    entry.slug.as_ref() == Some(&full_project.project.slug)
+                }) {
+                    continue;
+                }
+                entries.push(LicenseEntry {
+                    name: full_project.project.title.clone(),
+                    slug: Some(full_project.project.slug.clone()),
+                    license: Some(full_project.license.clone()),
+                    kind: LicenseKind::from_license(&full_project.license),
+                    source: ContentSource::Modrinth,
+                });
+            } else {
+                manual_paths.push(file.path.clone());
+            }
+        }
+
+        // Manual files are only in the branch files after updating the branch.
+        for manual_file in &branch_config.manual_files {
+            if !manual_paths.contains(&manual_file.path) {
+                manual_paths.push(manual_file.path.clone());
+            }
+        }
+        for path in manual_paths {
+            entries.push(Self::unknown_entry(path, ContentSource::Manual));
+        }
+
+        for path in Self::override_jars(modpack, branch)? {
+            entries.push(Self::unknown_entry(path, ContentSource::Override));
+        }
+
+        Ok(Self {
+            branch: branch.to_string(),
+            entries,
+        })
+    }
+
+    fn unknown_entry(path: String, source: ContentSource) -> LicenseEntry {
+        LicenseEntry {
+            name: path,
+            slug: None,
+            license: None,
+            kind: LicenseKind::Unknown,
+            source,
+        }
+    }
+
+    /// Returns the paths of all jar files in the override directories of a branch,
+    /// relative to their override directory.
+    fn override_jars(modpack: &Modpack, branch: &str) -> PackrinthResult<Vec<String>> {
+        let mut jars = Vec::new();
+        for layer_dir in modpack.override_layers(branch)? {
+            for override_dir in OVERRIDE_DIRS {
+                let override_dir_path = layer_dir.join(override_dir);
+                if !override_dir_path.is_dir() {
+                    continue;
+                }
+
+                for entry in WalkDir::new(&override_dir_path) {
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(error) => {
+                            return Err(PackrinthError::FailedToGetWalkDirEntry {
+                                error_message: error.to_string(),
+                            });
+                        }
+                    };
+                    let is_jar = entry
+                        .path()
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("jar"));
+                    if entry.file_type().is_file()
+                        && is_jar
+                        && let Ok(path) = entry.path().strip_prefix(&override_dir_path)
+                    {
+                        let path = path.to_string_lossy().replace('\\', "/");
+                        if !jars.contains(&path) {
+                            jars.push(path);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(jars)
+    }
+
+    /// Returns the entries with a license from `denied_licenses`.
+    ///
+    /// License IDs are compared case-insensitively. Entries without a license
+    /// are denied if [`UNKNOWN_LICENSE`] is in the list.
+    #[must_use]
+    pub fn denied(&self, denied_licenses: &[String]) -> Vec<&LicenseEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                denied_licenses
+                    .iter()
+                    .any(|denied| denied.eq_ignore_ascii_case(entry.license_id()))
+            })
+            .collect()
+    }
+
+    /// Creates a Markdown attribution file that credits all projects and files in the branch.
+    #[must_use]
+    pub fn credits(&self, modpack_name: &str) -> String {
+        let mut credits = String::new();
+        writeln!(credits, "# Credits").unwrap();
+        writeln!(credits).unwrap();
+        writeln!(
+            credits,
+            "{modpack_name} contains the following projects and files. They are redistributed under their own licenses."
+        )
+        .unwrap();
+
+        let modrinth_entries: Vec<&LicenseEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.source == ContentSource::Modrinth)
+            .collect();
+        if !modrinth_entries.is_empty() {
+            writeln!(credits).unwrap();
+            writeln!(credits, "## Modrinth projects").unwrap();
+            writeln!(credits).unwrap();
+            for entry in modrinth_entries {
+                let slug = entry.slug.as_deref().unwrap_or_default();
+                writeln!(
+                    credits,
+                    "- [{}](https://modrinth.com/project/{slug}): {}",
+                    entry.name,
+                    entry.license_label()
+                )
+                .unwrap();
+            }
+        }
+
+        let other_entries: Vec<&LicenseEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.source != ContentSource::Modrinth)
+            .collect();
+        if !other_entries.is_empty() {
+            writeln!(credits).unwrap();
+            writeln!(credits, "## Other files").unwrap();
+            writeln!(credits).unwrap();
+            for entry in other_entries {
+                writeln!(credits, "- `{}`: {}", entry.name, entry.license_label()).unwrap();
+            }
+        }
+
+        credits
+    }
+
+    /// Writes the attribution file from [`Self::credits`] to the `overrides` directory
+    /// of the branch and returns its path.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToCreateDir`] if creating the `overrides` directory failed
+    /// - [`PackrinthError::FailedToWriteFile`] if writing the attribution file failed
+    pub fn write_credits(&self, modpack: &Modpack) -> PackrinthResult<PathBuf> {
+        let path = modpack
+            .directory
+            .join(&self.branch)
+            .join(OVERRIDE_DIRS[0])
+            .join(CREDITS_FILE_NAME);
+        crate::write_file(&path, self.credits(&modpack.name))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{file, sodium};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn collect_branch_licenses() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        modpack.name = "Test pack".to_string();
+        modpack.denied_licenses = vec![UNKNOWN_LICENSE.to_string()];
+        let mut branch_config = modpack.new_branch("1.21.8").unwrap();
+        branch_config.manual_files = vec![file(
+            "mods/manual.jar",
+            "",
+            "https://github.com/example/manual.jar",
+        )];
+        branch_config.save(directory, "1.21.8").unwrap();
+
+        let mut branch_files = BranchFiles::from_directory(directory, "1.21.8").unwrap();
+        branch_files.files = vec![
+            file(
+                "mods/sodium.jar",
+                "",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/u1OmRkrw/sodium.jar",
+            ),
+            file(
+                "mods/manual.jar",
+                "",
+                "https://github.com/example/manual.jar",
+            ),
+        ];
+        branch_files.save(directory, "1.21.8").unwrap();
+        let override_mods = directory.join("1.21.8").join("overrides").join("mods");
+        fs::create_dir_all(&override_mods).unwrap();
+        fs::write(override_mods.join("bundled.jar"), "").unwrap();
+
+        let mut projects = HashMap::from([("AANobbMI".to_string(), sodium())]);
+
+        let branch_licenses = BranchLicenses::collect(&modpack, "1.21.8", &mut projects).unwrap();
+        assert_eq!(
+            vec![
+                ("Sodium", LicenseKind::Custom, ContentSource::Modrinth),
+                (
+                    "mods/manual.jar",
+                    LicenseKind::Unknown,
+                    ContentSource::Manual
+                ),
+                (
+                    "mods/bundled.jar",
+                    LicenseKind::Unknown,
+                    ContentSource::Override
+                ),
+            ],
+            branch_licenses
+                .entries
+                .iter()
+                .map(|entry| (entry.name.as_str(), entry.kind, entry.source))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, branch_licenses.denied(&modpack.denied_licenses).len());
+
+        let credits_path = branch_licenses.write_credits(&modpack).unwrap();
+        assert_eq!(
+            "# Credits
+
+Test pack contains the following projects and files. They are redistributed under their own licenses.
+
+## Modrinth projects
+
+- [Sodium](https://modrinth.com/project/sodium): LicenseRef-Polyform-Shield-License-1.0.0
+
+## Other files
+
+- `mods/manual.jar`: unknown license
+- `mods/bundled.jar`: unknown license
+",
+            fs::read_to_string(credits_path).unwrap()
+        );
+    }
+}
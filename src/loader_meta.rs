@@ -0,0 +1,256 @@
+//! Structs for resolving mod loader versions with the metadata services of the mod loaders.
+
+use crate::config::MainLoader;
+use crate::{PackrinthError, PackrinthResult, request_text};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+
+/// Environment variable that overrides the base URL of the Fabric metadata service.
+pub const FABRIC_META_URL_VAR: &str = "PACKRINTH_FABRIC_META_URL";
+/// Environment variable that overrides the base URL of the Quilt metadata service.
+pub const QUILT_META_URL_VAR: &str = "PACKRINTH_QUILT_META_URL";
+/// Environment variable that overrides the base URL of the Forge file server.
+pub const FORGE_META_URL_VAR: &str = "PACKRINTH_FORGE_META_URL";
+/// Environment variable that overrides the base URL of the `NeoForge` Maven API.
+pub const NEOFORGE_META_URL_VAR: &str = "PACKRINTH_NEOFORGE_META_URL";
+
+/// Base URLs of the services that provide the versions of mod loaders.
+///
+/// The URLs don't end with a `/`. They can be changed to use a local stand-in for a service.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LoaderMetaUrls {
+    pub fabric: String,
+    pub quilt: String,
+    pub forge: String,
+    pub neoforge: String,
+}
+
+impl Default for LoaderMetaUrls {
+    fn default() -> Self {
+        Self {
+            fabric: "https://meta.fabricmc.net".to_string(),
+            quilt: "https://meta.quiltmc.org".to_string(),
+            forge: "https://files.minecraftforge.net/net/minecraftforge/forge".to_string(),
+            neoforge: "https://maven.neoforged.net/api/maven".to_string(),
+        }
+    }
+}
+
+/// Part of a loader entry returned by the Fabric and Quilt metadata services.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct LoaderMetaEntry {
+    loader: LoaderMetaVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct LoaderMetaVersion {
+    version: String,
+
+    // Only Fabric returns whether a version is stable.
+    #[serde(default)]
+    stable: Option<bool>,
+}
+
+/// Promoted versions on the Forge file server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ForgePromotions {
+    promos: HashMap<String, String>,
+}
+
+/// Versions of an artifact in the `NeoForge` Maven API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct NeoForgeVersions {
+    versions: Vec<String>,
+}
+
+impl LoaderMetaUrls {
+    /// Returns the default URLs, with every URL for which an environment variable
+    /// ([`FABRIC_META_URL_VAR`], [`QUILT_META_URL_VAR`], [`FORGE_META_URL_VAR`]
+    /// or [`NEOFORGE_META_URL_VAR`]) is set replaced by the value of the variable.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mut urls = Self::default();
+        for (variable, url) in [
+            (FABRIC_META_URL_VAR, &mut urls.fabric),
+            (QUILT_META_URL_VAR, &mut urls.quilt),
+            (FORGE_META_URL_VAR, &mut urls.forge),
+            (NEOFORGE_META_URL_VAR, &mut urls.neoforge),
+        ] {
+            if let Ok(value) = env::var(variable) {
+                *url = value.trim_end_matches('/').to_string();
+            }
+        }
+        urls
+    }
+
+    /// Looks up the latest stable version of a mod loader for a Minecraft version.
+    ///
+    /// For Forge, the recommended version is used. The latest Forge version isn't used
+    /// if there is no recommended version, because it isn't marked as stable.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToParseLoaderMetaResponseJson`] if the response was invalid
+    /// - [`PackrinthError::NoLoaderVersionFound`] if the loader has no stable version for
+    ///   the Minecraft version
+    pub fn latest_loader_version(
+        &self,
+        mod_loader: MainLoader,
+        minecraft_version: &str,
+    ) -> PackrinthResult<String> {
+        let url = match mod_loader {
+            MainLoader::Fabric => format!("{}/v2/versions/loader/{minecraft_version}", self.fabric),
+            MainLoader::Quilt => format!("{}/v3/versions/loader/{minecraft_version}", self.quilt),
+            MainLoader::Forge => format!("{}/promotions_slim.json", self.forge),
+            MainLoader::NeoForge => {
+                format!("{}/versions/releases/net/neoforged/neoforge", self.neoforge)
+            }
+        };
+        let response = request_text(&url)?;
+
+        let loader_version = match mod_loader {
+            MainLoader::Fabric | MainLoader::Quilt => Self::latest_meta_version(&response, &url)?,
+            MainLoader::Forge => Self::latest_forge_version(&response, &url, minecraft_version)?,
+            MainLoader::NeoForge => {
+                Self::latest_neoforge_version(&response, &url, minecraft_version)?
+            }
+        };
+
+        loader_version.ok_or_else(|| PackrinthError::NoLoaderVersionFound {
+            loader: mod_loader.pretty_value().to_string(),
+            minecraft_version: minecraft_version.to_string(),
+        })
+    }
+
+    /// The Fabric and Quilt services return the loader versions from new to old.
+    /// Fabric marks stable versions, while Quilt only uses pre-release suffixes like `-beta.1`.
+    fn latest_meta_version(response: &str, url: &str) -> PackrinthResult<Option<String>> {
+        let entries: Vec<LoaderMetaEntry> = parse_response(response, url)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| entry.loader)
+            .find(|loader| loader.stable.unwrap_or(!loader.version.contains('-')))
+            .map(|loader| loader.version))
+    }
+
+    fn latest_forge_version(
+        response: &str,
+        url: &str,
+        minecraft_version: &str,
+    ) -> PackrinthResult<Option<String>> {
+        let mut promotions: ForgePromotions = parse_response(response, url)?;
+        Ok(promotions
+            .promos
+            .remove(&format!("{minecraft_version}-recommended")))
+    }
+
+    /// `NeoForge` versions start with the minor and patch version of Minecraft,
+    /// so `21.1.x` is for Minecraft `1.21.1` and `21.0.x` is for Minecraft `1.21`.
+    fn latest_neoforge_version(
+        response: &str,
+        url: &str,
+        minecraft_version: &str,
+    ) -> PackrinthResult<Option<String>> {
+        let neoforge_versions: NeoForgeVersions = parse_response(response, url)?;
+
+        let mut minecraft_parts = minecraft_version.split('.').skip(1);
+        let (Some(minor), patch) = (minecraft_parts.next(), minecraft_parts.next()) else {
+            return Ok(None);
+        };
+        let prefix = format!("{minor}.{}.", patch.unwrap_or("0"));
+
+        Ok(neoforge_versions
+            .versions
+            .into_iter()
+            .filter(|version| version.starts_with(&prefix) && !version.contains('-'))
+            .max_by_key(|version| {
+                version
+                    .split('.')
+                    .map(|part| part.parse::<u64>().unwrap_or(0))
+                    .collect::<Vec<u64>>()
+            }))
+    }
+}
+
+fn parse_response<T: for<'a> Deserialize<'a>>(response: &str, url: &str) -> PackrinthResult<T> {
+    match serde_json::from_str(response) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(PackrinthError::FailedToParseLoaderMetaResponseJson {
+            url: url.to_string(),
+            error_message: error.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn latest_meta_version() {
+        let fabric_response = r#"[
+            {"loader": {"separator": ".", "build": 3, "maven": "", "version": "0.17.3", "stable": false}},
+            {"loader": {"separator": ".", "build": 2, "maven": "", "version": "0.17.2", "stable": true}}
+        ]"#;
+        assert_eq!(
+            Ok(Some("0.17.2".to_string())),
+            LoaderMetaUrls::latest_meta_version(fabric_response, "fabric")
+        );
+
+        let quilt_response = r#"[
+            {"loader": {"separator": ".", "build": 2, "maven": "", "version": "0.29.0-beta.1"}},
+            {"loader": {"separator": ".", "build": 1, "maven": "", "version": "0.28.1"}}
+        ]"#;
+        assert_eq!(
+            Ok(Some("0.28.1".to_string())),
+            LoaderMetaUrls::latest_meta_version(quilt_response, "quilt")
+        );
+
+        assert_eq!(
+            Ok(None),
+            LoaderMetaUrls::latest_meta_version("[]", "fabric")
+        );
+        assert!(LoaderMetaUrls::latest_meta_version("{}", "fabric").is_err());
+    }
+
+    #[test]
+    fn latest_forge_version() {
+        let response = r#"{"homepage": "", "promos": {
+            "1.20.1-latest": "47.4.1",
+            "1.20.1-recommended": "47.4.0",
+            "1.21.8-latest": "58.0.5"
+        }}"#;
+        assert_eq!(
+            Ok(Some("47.4.0".to_string())),
+            LoaderMetaUrls::latest_forge_version(response, "forge", "1.20.1")
+        );
+        assert_eq!(
+            Ok(None),
+            LoaderMetaUrls::latest_forge_version(response, "forge", "1.21.8")
+        );
+        assert_eq!(
+            Ok(None),
+            LoaderMetaUrls::latest_forge_version(response, "forge", "1.19.2")
+        );
+    }
+
+    #[test]
+    fn latest_neoforge_version() {
+        let response = r#"{"isSnapshot": false, "versions": [
+            "21.0.167", "21.1.9", "21.1.10", "21.1.11-beta", "21.8.1"
+        ]}"#;
+        assert_eq!(
+            Ok(Some("21.1.10".to_string())),
+            LoaderMetaUrls::latest_neoforge_version(response, "neoforge", "1.21.1")
+        );
+        assert_eq!(
+            Ok(Some("21.0.167".to_string())),
+            LoaderMetaUrls::latest_neoforge_version(response, "neoforge", "1.21")
+        );
+        assert_eq!(
+            Ok(None),
+            LoaderMetaUrls::latest_neoforge_version(response, "neoforge", "1.20.1")
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Utilities for manual files, which are files that aren't added from Modrinth projects.
+
+use crate::config::BranchConfig;
+use crate::modrinth::{Env, File, FileHashes, SideSupport};
+use crate::{PackrinthError, PackrinthResult, request_bytes};
+use sha1::Sha1;
+use sha2::{Digest, Sha512};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// The domains Modrinth accepts downloads from in modpacks that are hosted on Modrinth.
+///
+/// See <https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack>.
+pub const ALLOWED_DOWNLOAD_DOMAINS: [&str; 4] = [
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+/// The kind of content a manual file has, which decides where it is added to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManualFileKind {
+    Mod,
+    Plugin,
+    ResourcePack,
+    ShaderPack,
+    Datapack,
+
+    /// A file Packrinth doesn't recognise.
+    Other,
+}
+
+impl ManualFileKind {
+    /// Looks at the contents of a file to find out what kind of file it is.
+    ///
+    /// Jar files without metadata that Packrinth recognises, like old Forge mods, are
+    /// treated as mods. Give [`manual_file`] a path for jar files that aren't mods.
+    #[must_use]
+    pub fn detect(file_name: &str, contents: &[u8]) -> Self {
+        let is_jar = Path::new(file_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("jar"));
+        let Ok(archive) = ZipArchive::new(Cursor::new(contents)) else {
+            return Self::Other;
+        };
+        let names: Vec<&str> = archive.file_names().collect();
+        let contains = |name: &str| names.contains(&name);
+        let contains_dir = |dir: &str| names.iter().any(|name| name.starts_with(dir));
+
+        if [
+            "plugin.yml",
+            "paper-plugin.yml",
+            "bungee.yml",
+            "velocity-plugin.json",
+        ]
+        .into_iter()
+        .any(contains)
+        {
+            Self::Plugin
+        } else if [
+            "fabric.mod.json",
+            "quilt.mod.json",
+            "META-INF/mods.toml",
+            "META-INF/neoforge.mods.toml",
+            "mcmod.info",
+        ]
+        .into_iter()
+        .any(contains)
+        {
+            Self::Mod
+        } else if contains("pack.mcmeta") && contains_dir("data/") {
+            Self::Datapack
+        } else if contains("pack.mcmeta") && contains_dir("assets/") {
+            Self::ResourcePack
+        } else if contains_dir("shaders/") {
+            Self::ShaderPack
+        } else if is_jar {
+            Self::Mod
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Returns the directory files of this kind are added to,
+    /// or [`None`] if Packrinth doesn't know it.
+    #[must_use]
+    pub fn directory(self, branch_config: &BranchConfig) -> Option<&str> {
+        match self {
+            Self::Mod => Some("mods"),
+            Self::Plugin => Some("plugins"),
+            Self::ResourcePack => Some("resourcepacks"),
+            Self::ShaderPack => Some("shaderpacks"),
+            Self::Datapack => branch_config
+                .datapack_directory
+                .as_deref()
+                .map(|directory| directory.trim_end_matches('/')),
+            Self::Other => None,
+        }
+    }
+
+    /// Returns the environment files of this kind are needed in,
+    /// or [`None`] if they are needed everywhere.
+    ///
+    /// For mods, the environment is read from the Fabric or Quilt metadata in the file.
+    #[must_use]
+    pub fn env(self, contents: &[u8]) -> Option<Env> {
+        let client_only = Env {
+            client: SideSupport::Required,
+            server: SideSupport::Unsupported,
+        };
+        let server_only = Env {
+            client: SideSupport::Unsupported,
+            server: SideSupport::Required,
+        };
+
+        match self {
+            Self::ResourcePack | Self::ShaderPack => Some(client_only),
+            Self::Plugin => Some(server_only),
+            Self::Mod => match mod_environment(contents).as_deref() {
+                Some("client") => Some(client_only),
+                Some("server" | "dedicated_server") => Some(server_only),
+                _ => None,
+            },
+            Self::Datapack | Self::Other => None,
+        }
+    }
+}
+
+/// Reads the environment from `fabric.mod.json` or `quilt.mod.json`.
+fn mod_environment(contents: &[u8]) -> Option<String> {
+    let mut archive = ZipArchive::new(Cursor::new(contents)).ok()?;
+    for (metadata_file, pointer) in [
+        ("fabric.mod.json", "/environment"),
+        ("quilt.mod.json", "/minecraft/environment"),
+    ] {
+        let Ok(mut file) = archive.by_name(metadata_file) else {
+            continue;
+        };
+        let mut metadata = String::new();
+        file.read_to_string(&mut metadata).ok()?;
+        let metadata: serde_json::Value = serde_json::from_str(&metadata).ok()?;
+        return metadata
+            .pointer(pointer)
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string);
+    }
+    None
+}
+
+/// Calculates the hashes Modrinth modpacks use for a file.
+#[must_use]
+pub fn file_hashes(contents: &[u8]) -> FileHashes {
+    FileHashes {
+        sha1: format!("{:x}", Sha1::digest(contents)),
+        sha512: format!("{:x}", Sha512::digest(contents)),
+    }
+}
+
+/// Returns whether Modrinth accepts downloads from this URL in modpacks hosted on Modrinth.
+#[must_use]
+pub fn is_allowed_download(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| ALLOWED_DOWNLOAD_DOMAINS.contains(&host))
+    })
+}
+
+/// Returns the decoded file name at the end of a download URL.
+///
+/// Returns [`None`] if the decoded name isn't a plain file name, for example
+/// because it contains an encoded `/` or is `..`.
+#[must_use]
+pub fn file_name_from_url(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let file_name = url.path_segments()?.next_back()?;
+    let file_name = percent_encoding::percent_decode_str(file_name)
+        .decode_utf8_lossy()
+        .to_string();
+    is_plain_file_name(&file_name).then_some(file_name)
+}
+
+/// Returns whether a path is a relative path inside the instance directory,
+/// so it can't be used to write files outside the instance.
+#[must_use]
+pub fn is_instance_path(path: &str) -> bool {
+    // A first part like `C:` is an absolute path on Windows.
+    !path.is_empty()
+        && !path.starts_with(['/', '\\'])
+        && !path
+            .split(['/', '\\'])
+            .enumerate()
+            .any(|(index, part)| part == ".." || (index == 0 && part.ends_with(':')))
+}
+
+fn is_plain_file_name(file_name: &str) -> bool {
+    !matches!(file_name, "" | "." | "..") && !file_name.contains(['/', '\\'])
+}
+
+/// Downloads a manual file.
+///
+/// # Errors
+/// - [`PackrinthError::RequestFailed`] if the download failed
+pub fn download(url: &str) -> PackrinthResult<Vec<u8>> {
+    request_bytes(url)
+}
+
+/// Creates a manual file from the contents of a file.
+///
+/// If `path` is [`None`], the file is put in the directory for its [`ManualFileKind`].
+/// The environment is also based on the kind of file.
+///
+/// # Errors
+/// - [`PackrinthError::UnknownManualFileDirectory`] if no path was given and the kind of file
+///   isn't known
+/// - [`PackrinthError::NoDatapackDirectory`] if no path was given and the file is a data pack,
+///   but the branch doesn't have a data pack directory
+/// - [`PackrinthError::InvalidManualFilePath`] if the path or file name would put the file
+///   outside the instance directory
+pub fn manual_file(
+    contents: &[u8],
+    file_name: &str,
+    download: String,
+    path: Option<String>,
+    branch_config: &BranchConfig,
+) -> PackrinthResult<File> {
+    let kind = ManualFileKind::detect(file_name, contents);
+    let path = match path {
+        Some(path) => path,
+        None if !is_plain_file_name(file_name) => {
+            return Err(PackrinthError::InvalidManualFilePath {
+                path: file_name.to_string(),
+            });
+        }
+        None => match kind.directory(branch_config) {
+            Some(directory) => format!("{directory}/{file_name}"),
+            None if kind == ManualFileKind::Datapack => {
+                return Err(PackrinthError::NoDatapackDirectory {
+                    project: file_name.to_string(),
+                });
+            }
+            None => {
+                return Err(PackrinthError::UnknownManualFileDirectory {
+                    file_name: file_name.to_string(),
+                });
+            }
+        },
+    };
+    if !is_instance_path(&path) {
+        return Err(PackrinthError::InvalidManualFilePath { path });
+    }
+
+    Ok(File {
+        project_name: file_name.to_string(),
+        path,
+        hashes: file_hashes(contents),
+        env: kind.env(contents),
+        downloads: vec![download],
+        file_size: contents.len() as u64,
+    })
+}
+
+/// Downloads a manual file again and updates its hashes and size.
+///
+/// Returns the updated file if the downloaded file is different, or [`None`]
+/// if it didn't change.
+///
+/// # Errors
+/// - [`PackrinthError::ManualFileHasNoDownloads`] if the file doesn't have a download URL
+/// - [`PackrinthError::RequestFailed`] if the download failed
+pub fn refresh(file: &File) -> PackrinthResult<Option<File>> {
+    let Some(url) = file.downloads.first() else {
+        return Err(PackrinthError::ManualFileHasNoDownloads {
+            path: file.path.clone(),
+        });
+    };
+    let contents = download(url)?;
+    let hashes = file_hashes(&contents);
+    let file_size = contents.len() as u64;
+    if hashes == file.hashes && file_size == file.file_size {
+        return Ok(None);
+    }
+
+    Ok(Some(File {
+        hashes,
+        file_size,
+        ..file.clone()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn detect_manual_file_kind() {
+        let fabric_mod = zip(&[("fabric.mod.json", r#"{"environment": "client"}"#)]);
+        assert_eq!(
+            ManualFileKind::Mod,
+            ManualFileKind::detect("mod.jar", &fabric_mod)
+        );
+        assert_eq!(
+            Some(Env {
+                client: SideSupport::Required,
+                server: SideSupport::Unsupported
+            }),
+            ManualFileKind::Mod.env(&fabric_mod)
+        );
+
+        let plugin = zip(&[("plugin.yml", "name: Test")]);
+        assert_eq!(
+            ManualFileKind::Plugin,
+            ManualFileKind::detect("plugin.jar", &plugin)
+        );
+
+        let datapack = zip(&[
+            ("pack.mcmeta", "{}"),
+            ("data/test/function/a.mcfunction", ""),
+        ]);
+        assert_eq!(
+            ManualFileKind::Datapack,
+            ManualFileKind::detect("pack.zip", &datapack)
+        );
+        let resource_pack = zip(&[
+            ("pack.mcmeta", "{}"),
+            ("assets/minecraft/lang/en_us.json", ""),
+        ]);
+        assert_eq!(
+            ManualFileKind::ResourcePack,
+            ManualFileKind::detect("pack.zip", &resource_pack)
+        );
+        let shader_pack = zip(&[("shaders/final.fsh", "")]);
+        assert_eq!(
+            ManualFileKind::ShaderPack,
+            ManualFileKind::detect("shaders.zip", &shader_pack)
+        );
+        assert_eq!(
+            ManualFileKind::Other,
+            ManualFileKind::detect("readme.txt", b"not a zip")
+        );
+    }
+
+    #[test]
+    fn create_manual_file() {
+        let contents = zip(&[("META-INF/neoforge.mods.toml", "")]);
+        let branch_config = BranchConfig::default();
+        let file = manual_file(
+            &contents,
+            "test mod.jar",
+            "https://github.com/a/b/releases/download/1.0/test%20mod.jar".to_string(),
+            None,
+            &branch_config,
+        )
+        .unwrap();
+        assert_eq!("mods/test mod.jar", file.path);
+        assert_eq!(contents.len() as u64, file.file_size);
+        assert_eq!(None, file.env);
+        assert_eq!(40, file.hashes.sha1.len());
+        assert_eq!(128, file.hashes.sha512.len());
+        assert_eq!(
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            file_hashes(b"").sha1
+        );
+
+        assert_eq!(
+            Err(PackrinthError::UnknownManualFileDirectory {
+                file_name: "notes.txt".to_string()
+            }),
+            manual_file(b"", "notes.txt", String::new(), None, &branch_config)
+        );
+        assert_eq!(
+            "config/notes.txt",
+            manual_file(
+                b"",
+                "notes.txt",
+                String::new(),
+                Some("config/notes.txt".to_string()),
+                &branch_config
+            )
+            .unwrap()
+            .path
+        );
+    }
+
+    #[test]
+    fn download_urls() {
+        assert!(is_allowed_download(
+            "https://cdn.modrinth.com/data/P7dR8mSH/versions/X2hTodix/fabric-api.jar"
+        ));
+        assert!(!is_allowed_download("https://example.com/mod.jar"));
+        assert!(!is_allowed_download(
+            "https://github.com.example.com/mod.jar"
+        ));
+        assert!(!is_allowed_download("not a url"));
+
+        assert_eq!(
+            Some("fabric-api-0.129.0+1.21.8.jar".to_string()),
+            file_name_from_url("https://cdn.modrinth.com/data/a/fabric-api-0.129.0%2B1.21.8.jar")
+        );
+        assert_eq!(None, file_name_from_url("https://example.com/"));
+        assert_eq!(
+            None,
+            file_name_from_url("https://example.com/..%2F..%2Fevil.jar")
+        );
+        assert_eq!(None, file_name_from_url("https://example.com/a/%2E%2E"));
+    }
+
+    #[test]
+    fn instance_paths() {
+        assert!(is_instance_path("mods/sodium.jar"));
+        assert!(is_instance_path("config/..hidden/file.txt"));
+        assert!(!is_instance_path(""));
+        assert!(!is_instance_path("mods/../../evil.jar"));
+        assert!(!is_instance_path("../evil.jar"));
+        assert!(!is_instance_path("mods\\..\\evil.jar"));
+        assert!(!is_instance_path("/etc/evil.jar"));
+        assert!(!is_instance_path("C:/evil.jar"));
+    }
+}
@@ -0,0 +1,449 @@
+//! Migrations that upgrade the configuration files of a modpack to [`CURRENT_PACK_FORMAT`].
+//!
+//! Migrations work on the JSON of the configuration files instead of the structs in
+//! [`crate::config`], because older configuration files don't have to be valid for the
+//! current structs.
+
+use crate::config::{
+    BRANCH_CONFIG_FILE_NAME, BRANCH_FILES_FILE_NAME, CURRENT_PACK_FORMAT, MODPACK_CONFIG_FILE_NAME,
+    json_to_file,
+};
+use crate::{PackrinthError, PackrinthResult};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// A step that upgrades the configuration files from one pack format to the next.
+// Not comparable, because comparing function pointers isn't meaningful.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// The pack format this migration upgrades from. After the migration,
+    /// the pack format is one higher.
+    pub from: u16,
+
+    /// What the migration changes, written for users.
+    pub description: &'static str,
+
+    migrate_modpack: fn(&mut Value),
+    migrate_branch_config: fn(&mut Value),
+    migrate_branch_files: fn(&mut Value),
+}
+
+/// All migrations, ordered by the pack format they upgrade from.
+///
+/// Changes that older Packrinth versions can still read, like a new optional key,
+/// don't need a new pack format and don't have a migration.
+pub const MIGRATIONS: [Migration; 0] = [];
+
+/// The directory in a modpack that the configuration files are copied to before migrating.
+/// It isn't in [`crate::TARGET_DIRECTORY`], because that directory can be removed with
+/// subcommand `clean`.
+pub const MIGRATION_BACKUP_DIRECTORY: &str = "migration_backup";
+
+/// A file in a branch directory that can be migrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BranchFile {
+    Config,
+    Files,
+}
+
+/// The result of [`migrate`].
+#[derive(Debug, Clone)]
+pub struct MigrationSummary {
+    /// The pack format of the modpack before migrating.
+    pub from: u16,
+
+    /// The pack format of the modpack after migrating.
+    pub to: u16,
+
+    /// The migrations that were applied, in order.
+    pub applied: Vec<Migration>,
+
+    /// The configuration files that were changed, relative to the modpack directory.
+    pub changed_files: Vec<PathBuf>,
+
+    /// The directory the original configuration files were copied to before migrating.
+    /// If nothing had to be migrated, this is [`None`].
+    pub backup_directory: Option<PathBuf>,
+}
+
+/// Returns the pack format of the modpack in a directory, without reading anything else
+/// of the modpack configuration.
+///
+/// # Errors
+/// - [`PackrinthError::FailedToReadToString`] if reading the modpack configuration failed
+/// - [`PackrinthError::FailedToParseConfigJson`] if the modpack configuration doesn't contain
+///   a valid pack format
+pub fn read_pack_format(directory: &Path) -> PackrinthResult<u16> {
+    let modpack_config_path = directory.join(MODPACK_CONFIG_FILE_NAME);
+    let Some(modpack_json) = read_json(&modpack_config_path)? else {
+        return Err(PackrinthError::FailedToReadToString {
+            path_to_read: modpack_config_path.display().to_string(),
+            error_message: io::Error::from(io::ErrorKind::NotFound).to_string(),
+        });
+    };
+    pack_format_of(&modpack_json, &modpack_config_path)
+}
+
+/// Checks if a pack format can be used by this version of Packrinth.
+///
+/// # Errors
+/// - [`PackrinthError::PackFormatOutdated`] if the pack format is older than
+///   [`CURRENT_PACK_FORMAT`], so the modpack has to be migrated with [`migrate`] first
+/// - [`PackrinthError::PackFormatTooNew`] if the pack format is newer than
+///   [`CURRENT_PACK_FORMAT`]
+pub fn check_pack_format(pack_format: u16) -> PackrinthResult<()> {
+    match pack_format.cmp(&CURRENT_PACK_FORMAT) {
+        Ordering::Less => Err(PackrinthError::PackFormatOutdated {
+            used_pack_format: pack_format,
+        }),
+        Ordering::Equal => Ok(()),
+        Ordering::Greater => Err(PackrinthError::PackFormatTooNew {
+            used_pack_format: pack_format,
+        }),
+    }
+}
+
+/// Upgrades the modpack configuration and the configuration files and branch files of all
+/// branches to [`CURRENT_PACK_FORMAT`], by applying every migration in [`MIGRATIONS`] that is
+/// needed, in order.
+///
+/// Before anything is written, the original files are copied to a backup directory in
+/// [`MIGRATION_BACKUP_DIRECTORY`] of the modpack. If the modpack already uses the current pack format,
+/// nothing is changed.
+///
+/// # Errors
+/// - [`PackrinthError::FailedToReadToString`] if reading a configuration file failed
+/// - [`PackrinthError::FailedToParseConfigJson`] if a configuration file isn't valid JSON or the
+///   modpack configuration doesn't contain a valid pack format
+/// - [`PackrinthError::PackFormatTooNew`] if the modpack uses a newer pack format
+///   than this version of Packrinth supports
+/// - [`PackrinthError::FailedToCreateDir`] if creating the backup directory failed
+/// - [`PackrinthError::FailedToCopyFile`] if backing up a configuration file failed
+/// - [`PackrinthError::FailedToSerialize`] if serialising a migrated file failed
+/// - [`PackrinthError::FailedToWriteFile`] if writing a migrated file failed
+pub fn migrate(directory: &Path) -> PackrinthResult<MigrationSummary> {
+    migrate_with(directory, &MIGRATIONS, CURRENT_PACK_FORMAT)
+}
+
+fn migrate_with(
+    directory: &Path,
+    migrations: &[Migration],
+    current_pack_format: u16,
+) -> PackrinthResult<MigrationSummary> {
+    let modpack_config_path = directory.join(MODPACK_CONFIG_FILE_NAME);
+    let Some(mut modpack_json) = read_json(&modpack_config_path)? else {
+        return Err(PackrinthError::FailedToReadToString {
+            path_to_read: modpack_config_path.display().to_string(),
+            error_message: io::Error::from(io::ErrorKind::NotFound).to_string(),
+        });
+    };
+    let from = pack_format_of(&modpack_json, &modpack_config_path)?;
+    if from > current_pack_format {
+        return Err(PackrinthError::PackFormatTooNew {
+            used_pack_format: from,
+        });
+    }
+
+    let applied: Vec<Migration> = migrations
+        .iter()
+        .filter(|migration| migration.from >= from && migration.from < current_pack_format)
+        .copied()
+        .collect();
+    let mut summary = MigrationSummary {
+        from,
+        to: current_pack_format,
+        applied: applied.clone(),
+        changed_files: Vec::new(),
+        backup_directory: None,
+    };
+    if from == current_pack_format {
+        return Ok(summary);
+    }
+
+    // Read every file before changing anything.
+    let branches: Vec<String> = modpack_json
+        .get("branches")
+        .and_then(Value::as_array)
+        .map(|branches| {
+            branches
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut branch_files = Vec::new();
+    for branch in &branches {
+        for (file_name, kind) in [
+            (BRANCH_CONFIG_FILE_NAME, BranchFile::Config),
+            (BRANCH_FILES_FILE_NAME, BranchFile::Files),
+        ] {
+            let relative_path = Path::new(branch).join(file_name);
+            if let Some(json) = read_json(&directory.join(&relative_path))? {
+                branch_files.push((relative_path, json, kind));
+            }
+        }
+    }
+
+    let backup_directory = directory
+        .join(MIGRATION_BACKUP_DIRECTORY)
+        .join(format!("pack_format_{from}"));
+    backup_file(
+        directory,
+        Path::new(MODPACK_CONFIG_FILE_NAME),
+        &backup_directory,
+    )?;
+    for (relative_path, _json, _kind) in &branch_files {
+        backup_file(directory, relative_path, &backup_directory)?;
+    }
+    summary.backup_directory = Some(backup_directory);
+
+    let original_modpack_json = modpack_json.clone();
+    for migration in &applied {
+        (migration.migrate_modpack)(&mut modpack_json);
+    }
+    if let Some(modpack_json) = modpack_json.as_object_mut() {
+        modpack_json.insert("pack_format".to_string(), current_pack_format.into());
+    }
+    if modpack_json != original_modpack_json {
+        json_to_file(&modpack_json, &modpack_config_path)?;
+        summary
+            .changed_files
+            .push(PathBuf::from(MODPACK_CONFIG_FILE_NAME));
+    }
+
+    for (relative_path, mut json, kind) in branch_files {
+        let original_json = json.clone();
+        for migration in &applied {
+            match kind {
+                BranchFile::Config => (migration.migrate_branch_config)(&mut json),
+                BranchFile::Files => (migration.migrate_branch_files)(&mut json),
+            }
+        }
+        if json != original_json {
+            json_to_file(&json, directory.join(&relative_path))?;
+            summary.changed_files.push(relative_path);
+        }
+    }
+
+    Ok(summary)
+}
+
+fn pack_format_of(modpack_json: &Value, modpack_config_path: &Path) -> PackrinthResult<u16> {
+    modpack_json
+        .get("pack_format")
+        .and_then(Value::as_u64)
+        .and_then(|pack_format| u16::try_from(pack_format).ok())
+        .ok_or_else(|| PackrinthError::FailedToParseConfigJson {
+            config_path: modpack_config_path.display().to_string(),
+            error_message: "pack_format is missing or isn't a valid number".to_string(),
+        })
+}
+
+/// Reads a JSON file, or returns [`None`] if the file doesn't exist.
+fn read_json(path: &Path) -> PackrinthResult<Option<Value>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(PackrinthError::FailedToReadToString {
+                path_to_read: path.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+    };
+    match serde_json::from_str(&contents) {
+        Ok(json) => Ok(Some(json)),
+        Err(error) => Err(PackrinthError::FailedToParseConfigJson {
+            config_path: path.display().to_string(),
+            error_message: error.to_string(),
+        }),
+    }
+}
+
+fn backup_file(
+    directory: &Path,
+    relative_path: &Path,
+    backup_directory: &Path,
+) -> PackrinthResult<()> {
+    let backup_path = backup_directory.join(relative_path);
+    if let Some(parent) = backup_path.parent()
+        && let Err(error) = fs::create_dir_all(parent)
+    {
+        return Err(PackrinthError::FailedToCreateDir {
+            dir_to_create: parent.display().to_string(),
+            error_message: error.to_string(),
+        });
+    }
+
+    let path = directory.join(relative_path);
+    if let Err(error) = fs::copy(&path, &backup_path) {
+        return Err(PackrinthError::FailedToCopyFile {
+            from: path.display().to_string(),
+            to: backup_path.display().to_string(),
+            error_message: error.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write_json(path: &Path, json: &Value) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, serde_json::to_string(json).unwrap()).unwrap();
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn migrate_backs_up_and_changes_files() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let modpack_json = serde_json::json!({
+            "pack_format": 1,
+            "name": "Test",
+            "branches": ["1.21.8"],
+            "projects": {}
+        });
+        let branch_json = serde_json::json!({ "version": "1.0.0" });
+        let branch_files_json = serde_json::json!({ "info": "", "projects": [], "files": [] });
+        write_json(&directory.join(MODPACK_CONFIG_FILE_NAME), &modpack_json);
+        write_json(
+            &directory.join("1.21.8").join(BRANCH_CONFIG_FILE_NAME),
+            &branch_json,
+        );
+        write_json(
+            &directory.join("1.21.8").join(BRANCH_FILES_FILE_NAME),
+            &branch_files_json,
+        );
+        let migrations = [Migration {
+            from: 1,
+            description: "rename version to pack_version",
+            migrate_modpack: |_| {},
+            migrate_branch_config: |branch_config| {
+                let branch_config = branch_config.as_object_mut().unwrap();
+                let version = branch_config.shift_remove("version").unwrap();
+                branch_config.insert("pack_version".to_string(), version);
+            },
+            migrate_branch_files: |_| {},
+        }];
+
+        let summary = migrate_with(directory, &migrations, 2).unwrap();
+        assert_eq!((1, 2), (summary.from, summary.to));
+        assert_eq!(1, summary.applied.len());
+        assert_eq!(
+            vec![
+                PathBuf::from(MODPACK_CONFIG_FILE_NAME),
+                Path::new("1.21.8").join(BRANCH_CONFIG_FILE_NAME),
+            ],
+            summary.changed_files
+        );
+        assert_eq!(
+            serde_json::json!({
+                "pack_format": 2,
+                "name": "Test",
+                "branches": ["1.21.8"],
+                "projects": {}
+            }),
+            read(&directory.join(MODPACK_CONFIG_FILE_NAME))
+        );
+        assert_eq!(
+            serde_json::json!({ "pack_version": "1.0.0" }),
+            read(&directory.join("1.21.8").join(BRANCH_CONFIG_FILE_NAME))
+        );
+
+        let backup_directory = summary.backup_directory.unwrap();
+        assert_eq!(
+            directory
+                .join(MIGRATION_BACKUP_DIRECTORY)
+                .join("pack_format_1"),
+            backup_directory
+        );
+        assert_eq!(
+            modpack_json,
+            read(&backup_directory.join(MODPACK_CONFIG_FILE_NAME))
+        );
+        assert_eq!(
+            branch_files_json,
+            read(&backup_directory.join("1.21.8").join(BRANCH_FILES_FILE_NAME))
+        );
+
+        let summary = migrate_with(directory, &migrations, 2).unwrap();
+        assert!(summary.applied.is_empty());
+        assert_eq!(None, summary.backup_directory);
+    }
+
+    #[test]
+    fn current_pack_format_is_not_migrated() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        write_json(
+            &directory.join(MODPACK_CONFIG_FILE_NAME),
+            &serde_json::json!({ "pack_format": CURRENT_PACK_FORMAT, "branches": [] }),
+        );
+        assert_eq!(
+            Ok(()),
+            check_pack_format(read_pack_format(directory).unwrap())
+        );
+        let summary = migrate(directory).unwrap();
+        assert!(summary.applied.is_empty());
+        assert!(summary.changed_files.is_empty());
+        assert!(!directory.join(MIGRATION_BACKUP_DIRECTORY).exists());
+        assert_eq!(
+            Err(PackrinthError::PackFormatOutdated {
+                used_pack_format: 0
+            }),
+            check_pack_format(0)
+        );
+    }
+
+    #[test]
+    fn migrations_run_in_order() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        write_json(
+            &directory.join(MODPACK_CONFIG_FILE_NAME),
+            &serde_json::json!({ "pack_format": 2, "steps": [] }),
+        );
+        let add_step = |modpack: &mut Value| {
+            let steps = modpack["steps"].as_array_mut().unwrap();
+            steps.push(steps.len().into());
+        };
+        let migrations = [1, 2, 3].map(|from| Migration {
+            from,
+            description: "",
+            migrate_modpack: add_step,
+            migrate_branch_config: |_| {},
+            migrate_branch_files: |_| {},
+        });
+
+        let summary = migrate_with(directory, &migrations, 4).unwrap();
+        assert_eq!(
+            vec![2, 3],
+            summary
+                .applied
+                .iter()
+                .map(|migration| migration.from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            serde_json::json!({ "pack_format": 4, "steps": [0, 1] }),
+            read(&directory.join(MODPACK_CONFIG_FILE_NAME))
+        );
+
+        assert_eq!(
+            Err(PackrinthError::PackFormatTooNew {
+                used_pack_format: 4
+            }),
+            migrate_with(directory, &migrations, 3).map(|summary| summary.to)
+        );
+    }
+}
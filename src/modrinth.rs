@@ -1,15 +1,27 @@
 //! Structs that are only used for (de)serializing JSONs associated with Modrinth.
 
-use crate::config::{BranchConfig, IncludeOrExclude, Loader, ProjectSettings};
+use crate::config::{
+    BranchConfig, Loader, MainLoader, ProjectSettings, SelectionPreference, SelectionStrategy,
+};
 use crate::{MRPACK_INDEX_FILE_NAME, PackrinthError, PackrinthResult};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::io::{BufReader, Read};
 use std::path::Path;
+use std::sync::OnceLock;
 use std::{cmp, fs, io};
 use zip::ZipArchive;
 use zip::result::ZipResult;
 
-const MODRINTH_API_BASE_URL: &str = "https://api.modrinth.com/v2";
+/// The base URL of the Modrinth API (v2).
+pub const MODRINTH_API_BASE_URL: &str = "https://api.modrinth.com/v2";
+
+/// The environment variable with the Modrinth personal access token used for publishing.
+pub const MODRINTH_TOKEN_ENV_VAR: &str = "MODRINTH_TOKEN";
+
+/// The name of the multipart form part that contains the `.mrpack` file of a new version.
+const NEW_VERSION_FILE_PART: &str = "mrpack";
 
 /// Extract all the contents of a Modrinth modpack, except for the main manifest file.
 ///
@@ -52,6 +64,11 @@ fn request_text<T: ToString>(api_endpoint: &T) -> PackrinthResult<String> {
     crate::request_text(&full_url)
 }
 
+fn request_optional_text<T: ToString>(api_endpoint: &T) -> PackrinthResult<Option<String>> {
+    let full_url = MODRINTH_API_BASE_URL.to_string() + api_endpoint.to_string().as_str();
+    crate::request_optional_text(&full_url)
+}
+
 /// Part of the fields returned from the `/project` Modrinth API endpoint (v2).
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Project {
@@ -61,10 +78,90 @@ pub struct Project {
     pub server_side: SideSupport,
     pub client_side: SideSupport,
     pub project_type: ProjectType,
+
+    #[serde(default)]
+    pub loaders: Vec<String>,
+
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+}
+
+/// All fields returned from the `/project` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FullProject {
+    #[serde(flatten)]
+    pub project: Project,
+
+    /// A short description of the project.
+    pub description: String,
+
+    /// The long description of the project in Markdown.
+    pub body: String,
+
+    /// The moderation status of the project, like `approved` or `archived`.
+    pub status: String,
+    pub requested_status: Option<String>,
+    pub categories: Vec<String>,
+    pub additional_categories: Vec<String>,
+    pub issues_url: Option<String>,
+    pub source_url: Option<String>,
+    pub wiki_url: Option<String>,
+    pub discord_url: Option<String>,
+
+    #[serde(default)]
+    pub donation_urls: Vec<DonationUrl>,
+    pub icon_url: Option<String>,
+    pub color: Option<u32>,
+    pub thread_id: Option<String>,
+    pub monetization_status: Option<String>,
+
+    /// The ID of the team that owns the project.
+    pub team: String,
+    pub published: String,
+    pub updated: String,
+    pub approved: Option<String>,
+    pub queued: Option<String>,
+    pub downloads: u64,
+    pub followers: u64,
+    pub license: License,
+
+    /// IDs of all versions of the project.
+    pub versions: Vec<String>,
+
+    #[serde(default)]
+    pub gallery: Vec<GalleryImage>,
+}
+
+/// A place to donate to a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DonationUrl {
+    pub id: String,
+    pub platform: String,
+    pub url: String,
+}
+
+/// The license of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct License {
+    /// The SPDX license ID, or `LicenseRef-` followed by a name for custom licenses.
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+/// An image in the gallery of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GalleryImage {
+    pub url: String,
+    pub featured: bool,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub created: String,
+    pub ordering: i64,
 }
 
 /// The type of project.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ProjectType {
     #[serde(rename = "mod")]
     Mod,
@@ -77,10 +174,20 @@ pub enum ProjectType {
 
     #[serde(rename = "shader")]
     Shader,
+
+    #[serde(rename = "datapack")]
+    Datapack,
+
+    #[serde(rename = "plugin")]
+    Plugin,
+
+    /// A project type Packrinth doesn't know. Projects of this type can't be added.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// The support for a specific environment (server or client).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum SideSupport {
     #[serde(rename = "required")]
     Required,
@@ -90,6 +197,12 @@ pub enum SideSupport {
 
     #[serde(rename = "unsupported")]
     Unsupported,
+
+    /// Modrinth doesn't know the support for some projects, like plugins.
+    /// Modpacks can't use this value, see [`SideSupport::known_or_optional`].
+    #[serde(rename = "unknown")]
+    #[schemars(skip)]
+    Unknown,
 }
 
 /// Part of the fields returned from the `/version` Modrinth API endpoint (v2).
@@ -103,8 +216,71 @@ pub struct Version {
     pub dependencies: Vec<VersionDependency>,
 }
 
+/// A version with the extra fields that are needed to choose between suitable versions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct VersionCandidate {
+    #[serde(flatten)]
+    version: Version,
+    version_number: String,
+    loaders: Vec<String>,
+    date_published: String,
+}
+
+/// The versions of a project that can be added to a branch.
+enum VersionCandidates {
+    /// The project isn't added to the branch because of its inclusions or exclusions.
+    Skipped,
+
+    /// A version override is set for the branch.
+    Override {
+        candidate: VersionCandidate,
+        version_override: String,
+    },
+
+    /// The versions for the Minecraft versions and loaders of the branch.
+    Listed(Vec<VersionCandidate>),
+}
+
+/// All fields returned from the `/version` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FullVersion {
+    #[serde(flatten)]
+    pub version: Version,
+
+    pub name: String,
+    pub version_number: String,
+    pub changelog: Option<String>,
+    pub loaders: Vec<String>,
+    pub featured: bool,
+
+    /// The status of the version, like `listed` or `archived`.
+    pub status: String,
+    pub requested_status: Option<String>,
+    pub author_id: String,
+    pub date_published: String,
+    pub downloads: u64,
+}
+
+/// The data of a new version for the `/version` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NewVersion {
+    pub name: String,
+    pub version_number: String,
+    pub changelog: Option<String>,
+    pub dependencies: Vec<VersionDependency>,
+    pub game_versions: Vec<String>,
+    pub version_type: VersionType,
+    pub loaders: Vec<String>,
+    pub featured: bool,
+    pub project_id: String,
+
+    /// The names of the multipart form parts that contain files.
+    pub file_parts: Vec<String>,
+    pub primary_file: String,
+}
+
 /// Type of version.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum VersionType {
     #[serde(rename = "release")]
     Release,
@@ -127,7 +303,7 @@ pub struct VersionFile {
 }
 
 /// Hashes for a file.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct FileHashes {
     pub sha1: String,
     pub sha512: String,
@@ -156,6 +332,37 @@ pub enum VersionDependencyType {
     Embedded,
 }
 
+/// A Minecraft version, as returned from the `/tag/game_version` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GameVersion {
+    pub version: String,
+    pub version_type: GameVersionType,
+    pub date: String,
+    pub major: bool,
+}
+
+/// Type of Minecraft version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameVersionType {
+    #[serde(rename = "release")]
+    Release,
+
+    #[serde(rename = "snapshot")]
+    Snapshot,
+
+    #[serde(rename = "beta")]
+    Beta,
+
+    #[serde(rename = "alpha")]
+    Alpha,
+}
+
+/// All Minecraft versions known to Modrinth, sorted from new to old.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameVersions {
+    versions: Vec<GameVersion>,
+}
+
 /// The main index file in a Modrinth modpack.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -173,7 +380,7 @@ pub struct MrPack {
 }
 
 /// A file in a modpack.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
     #[serde(skip_serializing)]
@@ -190,8 +397,40 @@ pub struct File {
     pub file_size: u64,
 }
 
+impl File {
+    /// Returns the file name of the file, without the directory it is in.
+    #[must_use]
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Returns the Modrinth ID of the project this file belongs to, if it is downloaded
+    /// from the Modrinth CDN.
+    #[must_use]
+    pub fn modrinth_project_id(&self) -> Option<&str> {
+        self.downloads.iter().find_map(|download| {
+            download
+                .strip_prefix("https://cdn.modrinth.com/data/")?
+                .split('/')
+                .next()
+        })
+    }
+
+    /// Returns the Modrinth ID of the version this file belongs to, if it is downloaded
+    /// from the Modrinth CDN.
+    #[must_use]
+    pub fn modrinth_version_id(&self) -> Option<&str> {
+        self.downloads.iter().find_map(|download| {
+            let mut parts = download
+                .strip_prefix("https://cdn.modrinth.com/data/")?
+                .split('/');
+            (parts.nth(1)? == "versions").then(|| parts.next())?
+        })
+    }
+}
+
 /// Environment information for a file in a Modrinth modpack.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Env {
     pub client: SideSupport,
@@ -232,61 +471,708 @@ pub enum FileResult {
     Err(PackrinthError),
 }
 
-impl Project {
-    /// Gets a project from the Modrinth ID.
+impl License {
+    /// Returns a human-readable description of the license, like `MIT License (MIT)`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.name.is_empty() || self.name == self.id {
+            self.id.clone()
+        } else {
+            format!("{} ({})", self.name, self.id)
+        }
+    }
+}
+
+impl Project {
+    /// Gets a project from the Modrinth ID or slug.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ProjectNotFound`] if no project exists with the ID
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseConfigJson`] if the Modrinth response was invalid
+    pub fn from_id(id: &str) -> PackrinthResult<Self> {
+        request_project(id)
+    }
+
+    /// Gets multiple projects from their Modrinth IDs or slugs with one request.
+    ///
+    /// Projects that don't exist are left out of the result.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the Modrinth response was invalid
+    pub fn from_ids(ids: &[String]) -> PackrinthResult<Vec<Self>> {
+        request_projects(ids)
+    }
+
+    /// Returns whether the project has versions for the Minecraft versions and loaders of a branch.
+    #[must_use]
+    pub fn branch_support(&self, branch_config: &BranchConfig) -> BranchSupport {
+        BranchSupport::check(&self.loaders, &self.game_versions, branch_config)
+    }
+}
+
+impl FullProject {
+    /// Gets a project with all its fields from the Modrinth ID or slug.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::ProjectNotFound`] if no project exists with the ID
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseConfigJson`] if the Modrinth response was invalid
+    pub fn from_id(id: &str) -> PackrinthResult<Self> {
+        request_project(id)
+    }
+
+    /// Gets multiple projects with all their fields from their Modrinth IDs or slugs with one request.
+    ///
+    /// Projects that don't exist are left out of the result.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the Modrinth response was invalid
+    pub fn from_ids(ids: &[String]) -> PackrinthResult<Vec<Self>> {
+        request_projects(ids)
+    }
+}
+
+fn request_project<T: DeserializeOwned>(id: &str) -> PackrinthResult<T> {
+    // Request to get general information about the project associated with the version
+    let api_endpoint = format!("/project/{id}");
+    let Some(modrinth_project_response) = request_optional_text(&api_endpoint)? else {
+        return Err(PackrinthError::ProjectNotFound {
+            project: id.to_string(),
+        });
+    };
+    match serde_json::from_str::<T>(&modrinth_project_response) {
+        Ok(project) => Ok(project),
+        Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+            modrinth_endpoint: api_endpoint,
+            error_message: error.to_string(),
+        }),
+    }
+}
+
+fn request_projects<T: DeserializeOwned>(ids: &[String]) -> PackrinthResult<Vec<T>> {
+    request_by_ids("/projects", ids)
+}
+
+/// The maximum number of IDs in one request, so the URL doesn't get too long.
+const MAX_IDS_PER_REQUEST: usize = 200;
+
+/// Requests multiple objects from an endpoint that accepts a list of IDs,
+/// with as few requests as possible.
+fn request_by_ids<T: DeserializeOwned>(endpoint: &str, ids: &[String]) -> PackrinthResult<Vec<T>> {
+    let mut results = Vec::new();
+    for ids in ids.chunks(MAX_IDS_PER_REQUEST) {
+        let api_endpoint = format!("{endpoint}?ids={ids:?}");
+        let modrinth_response = request_text(&api_endpoint)?;
+        match serde_json::from_str::<Vec<T>>(&modrinth_response) {
+            Ok(response) => results.extend(response),
+            Err(error) => {
+                return Err(PackrinthError::FailedToParseModrinthResponseJson {
+                    modrinth_endpoint: api_endpoint,
+                    error_message: error.to_string(),
+                });
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Results of the `/search` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub total_hits: u64,
+}
+
+/// Part of the fields of a project found with the `/search` Modrinth API endpoint (v2).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub project_type: ProjectType,
+    pub downloads: u64,
+
+    /// The categories of the project, which include its loaders.
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// The Minecraft versions the project supports.
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+impl SearchResults {
+    /// Searches Modrinth for projects.
+    ///
+    /// Every inner list of `facets` is a list of facets like `versions:1.21.8`, of which
+    /// at least one has to match. All inner lists have to match.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the Modrinth response was invalid
+    pub fn search(query: &str, facets: &[Vec<String>], limit: u16) -> PackrinthResult<Self> {
+        let encode = |value: &str| {
+            percent_encoding::utf8_percent_encode(value, percent_encoding::NON_ALPHANUMERIC)
+                .to_string()
+        };
+        let mut api_endpoint = format!("/search?query={}&limit={limit}", encode(query));
+        if !facets.is_empty() {
+            let facets = serde_json::to_string(facets).unwrap_or_default();
+            api_endpoint.push_str("&facets=");
+            api_endpoint.push_str(&encode(&facets));
+        }
+
+        let api_response = request_text(&api_endpoint)?;
+        match serde_json::from_str::<Self>(&api_response) {
+            Ok(results) => Ok(results),
+            Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+                modrinth_endpoint: api_endpoint,
+                error_message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Returns facets that only find projects that support at least one of the branches:
+    /// one of their Minecraft versions and one of their loaders.
+    #[must_use]
+    pub fn branch_facets(branch_configs: &[&BranchConfig]) -> Vec<Vec<String>> {
+        let mut game_versions = Vec::new();
+        let mut loaders = Vec::new();
+        for branch_config in branch_configs {
+            for game_version in branch_config.game_versions() {
+                let facet = format!("versions:{game_version}");
+                if !game_versions.contains(&facet) {
+                    game_versions.push(facet);
+                }
+            }
+            for loader in branch_config.modrinth_loaders() {
+                let facet = format!("categories:{loader}");
+                if !loaders.contains(&facet) {
+                    loaders.push(facet);
+                }
+            }
+        }
+
+        [game_versions, loaders]
+            .into_iter()
+            .filter(|facets| !facets.is_empty())
+            .collect()
+    }
+}
+
+impl SearchHit {
+    /// Returns whether the project supports one of the Minecraft versions and one of the loaders
+    /// of a branch.
+    ///
+    /// Projects without loaders in their categories are only checked for their Minecraft versions.
+    #[must_use]
+    pub fn supports_branch(&self, branch_config: &BranchConfig) -> bool {
+        BranchSupport::check(&self.categories, &self.versions, branch_config)
+            == BranchSupport::Supported
+    }
+}
+
+/// Whether a project has versions for the Minecraft versions and loaders of a branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BranchSupport {
+    Supported,
+
+    /// The project doesn't support any of the Minecraft versions of the branch.
+    UnsupportedMinecraftVersion,
+
+    /// The project doesn't support any of the loaders of the branch.
+    UnsupportedLoader,
+}
+
+impl BranchSupport {
+    /// Checks the Minecraft versions and loaders of a project against a branch.
+    ///
+    /// Values in `loaders` that aren't a [`Loader`] are ignored, so project categories can be used.
+    /// If no loaders are left, only the Minecraft versions are checked.
+    fn check(loaders: &[String], game_versions: &[String], branch_config: &BranchConfig) -> Self {
+        let branch_game_versions = branch_config.game_versions();
+        if !game_versions
+            .iter()
+            .any(|game_version| branch_game_versions.contains(game_version))
+        {
+            return Self::UnsupportedMinecraftVersion;
+        }
+
+        let loaders: Vec<&String> = loaders
+            .iter()
+            .filter(|loader| {
+                serde_json::from_value::<Loader>(serde_json::Value::String((*loader).clone()))
+                    .is_ok()
+            })
+            .collect();
+        let branch_loaders = branch_config.modrinth_loaders();
+        if loaders.is_empty()
+            || loaders
+                .iter()
+                .any(|loader| branch_loaders.contains(&loader.as_str()))
+        {
+            Self::Supported
+        } else {
+            Self::UnsupportedLoader
+        }
+    }
+}
+
+impl VersionType {
+    /// Returns the value Modrinth uses for this version type.
+    #[must_use]
+    pub const fn modrinth_value(&self) -> &str {
+        match self {
+            VersionType::Release => "release",
+            VersionType::Beta => "beta",
+            VersionType::Alpha => "alpha",
+        }
+    }
+
+    /// Returns a pretty human-friendly value.
+    #[must_use]
+    pub const fn pretty_value(&self) -> &str {
+        match self {
+            VersionType::Release => "Release",
+            VersionType::Beta => "Beta",
+            VersionType::Alpha => "Alpha",
+        }
+    }
+}
+
+impl Env {
+    /// Returns on which sides a file is used, like `Client and server (optional)`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let sides: Vec<String> = [("Client", self.client), ("Server", self.server)]
+            .into_iter()
+            .filter_map(|(side, support)| match support {
+                SideSupport::Required => Some(side.to_string()),
+                SideSupport::Optional | SideSupport::Unknown => Some(format!("{side} (optional)")),
+                SideSupport::Unsupported => None,
+            })
+            .collect();
+        if sides.is_empty() {
+            "Neither".to_string()
+        } else {
+            // Only the first side starts with a capital letter.
+            sides
+                .join(" and ")
+                .replacen(" and Server", " and server", 1)
+        }
+    }
+}
+
+impl SideSupport {
+    /// Returns the value Modrinth uses for this side support.
+    #[must_use]
+    pub const fn modrinth_value(&self) -> &str {
+        match self {
+            SideSupport::Required => "required",
+            SideSupport::Optional => "optional",
+            SideSupport::Unsupported => "unsupported",
+            SideSupport::Unknown => "unknown",
+        }
+    }
+
+    /// Returns [`SideSupport::Optional`] if the support is unknown, because the
+    /// environment of a file in a modpack can't be unknown.
+    #[must_use]
+    pub const fn known_or_optional(self) -> Self {
+        match self {
+            SideSupport::Unknown => SideSupport::Optional,
+            support => support,
+        }
+    }
+}
+
+impl ProjectType {
+    /// Returns the value Modrinth uses for this project type.
+    #[must_use]
+    pub fn modrinth_value(&self) -> &str {
+        match self {
+            ProjectType::Mod => "mod",
+            ProjectType::Modpack => "modpack",
+            ProjectType::ResourcePack => "resourcepack",
+            ProjectType::Shader => "shader",
+            ProjectType::Datapack => "datapack",
+            ProjectType::Plugin => "plugin",
+            ProjectType::Unknown(project_type) => project_type,
+        }
+    }
+
+    /// Returns the directory name of where the project would go in to.
+    ///
+    /// Versions of data packs that are installed as mod go in to the mods directory.
+    /// Versions that are only a data pack go in to [`BranchConfig::datapack_directory`].
+    ///
+    /// # Errors
+    /// - [`PackrinthError::AttemptedToAddOtherModpack`] when [`ProjectType`] is [`ProjectType::Modpack`]
+    /// - [`PackrinthError::UnsupportedProjectType`] when [`ProjectType`] is [`ProjectType::Unknown`]
+    pub fn directory(&self) -> PackrinthResult<&str> {
+        match self {
+            ProjectType::Mod | ProjectType::Datapack => Ok("mods"),
+
+            // This happens when you add a modpack as project.
+            ProjectType::Modpack => Err(PackrinthError::AttemptedToAddOtherModpack),
+
+            ProjectType::ResourcePack => Ok("resourcepacks"),
+            ProjectType::Shader => Ok("shaderpacks"),
+            ProjectType::Plugin => Ok("plugins"),
+            ProjectType::Unknown(project_type) => Err(PackrinthError::UnsupportedProjectType {
+                project_type: project_type.clone(),
+            }),
+        }
+    }
+}
+
+impl GameVersions {
+    /// Creates a catalogue from Minecraft versions that are sorted from new to old.
+    #[must_use]
+    pub const fn new(versions: Vec<GameVersion>) -> Self {
+        Self { versions }
+    }
+
+    /// Gets all Minecraft versions from Modrinth.
+    ///
+    /// The versions are cached for the rest of the process, so they are only requested once.
+    /// Failed requests aren't cached.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the response was invalid
+    pub fn from_modrinth() -> PackrinthResult<Self> {
+        static GAME_VERSIONS: OnceLock<GameVersions> = OnceLock::new();
+        if let Some(game_versions) = GAME_VERSIONS.get() {
+            return Ok(game_versions.clone());
+        }
+
+        let api_endpoint = "/tag/game_version";
+        let api_response = request_text(&api_endpoint)?;
+        match serde_json::from_str::<Vec<GameVersion>>(&api_response) {
+            Ok(versions) => Ok(GAME_VERSIONS.get_or_init(|| Self::new(versions)).clone()),
+            Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+                modrinth_endpoint: api_endpoint.to_string(),
+                error_message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Returns all Minecraft versions, sorted from new to old.
+    #[must_use]
+    pub fn versions(&self) -> &[GameVersion] {
+        &self.versions
+    }
+
+    /// Returns the Minecraft version with this name.
+    #[must_use]
+    pub fn get(&self, version: &str) -> Option<&GameVersion> {
+        self.versions
+            .iter()
+            .find(|game_version| game_version.version == version)
+    }
+
+    /// Compares two Minecraft versions by their release order.
+    ///
+    /// Versions that aren't known are sorted before all known versions, and are compared
+    /// with [`compare_game_versions_by_name`] among themselves. That way, the order stays
+    /// consistent when known and unknown versions are sorted together.
+    #[must_use]
+    pub fn compare(&self, a: &str, b: &str) -> cmp::Ordering {
+        let position = |version: &str| {
+            self.versions
+                .iter()
+                .position(|game_version| game_version.version == version)
+        };
+        match (position(a), position(b)) {
+            // Newer versions come first, so a higher position means an older version.
+            (Some(a_position), Some(b_position)) => b_position.cmp(&a_position),
+            (Some(_), None) => cmp::Ordering::Greater,
+            (None, Some(_)) => cmp::Ordering::Less,
+            (None, None) => compare_game_versions_by_name(a, b),
+        }
+    }
+}
+
+impl SelectionStrategy {
+    /// Compares two versions, where the version that should be chosen first is [`cmp::Ordering::Less`].
+    fn compare(
+        &self,
+        a: &VersionCandidate,
+        b: &VersionCandidate,
+        branch_config: &BranchConfig,
+        game_versions: Option<&GameVersions>,
+    ) -> cmp::Ordering {
+        let newest_game_version = |versions: &'_ [String]| {
+            versions
+                .iter()
+                .max_by(|a, b| compare_game_versions(a, b, game_versions))
+                .cloned()
+        };
+
+        let main_loader = branch_config
+            .mod_loader
+            .map(|mod_loader| mod_loader.modrinth_value().to_string());
+
+        self.0
+            .iter()
+            .fold(cmp::Ordering::Equal, |ordering, preference| {
+                ordering.then_with(|| match preference {
+                    SelectionPreference::ExactMinecraft => {
+                        let supports_exact = |candidate: &VersionCandidate| {
+                            candidate
+                                .version
+                                .game_versions
+                                .contains(&branch_config.minecraft_version)
+                        };
+                        supports_exact(b).cmp(&supports_exact(a))
+                    }
+                    SelectionPreference::MainLoader => {
+                        let supports_main_loader = |candidate: &VersionCandidate| {
+                            main_loader
+                                .as_ref()
+                                .is_some_and(|main_loader| candidate.loaders.contains(main_loader))
+                        };
+                        supports_main_loader(b).cmp(&supports_main_loader(a))
+                    }
+                    SelectionPreference::NewestMinecraft => match (
+                        newest_game_version(&a.version.game_versions),
+                        newest_game_version(&b.version.game_versions),
+                    ) {
+                        (Some(va), Some(vb)) => compare_game_versions(&vb, &va, game_versions),
+                        (Some(_), None) => cmp::Ordering::Less,
+                        (None, Some(_)) => cmp::Ordering::Greater,
+                        (None, None) => cmp::Ordering::Equal,
+                    },
+                    // Modrinth uses RFC 3339 dates in UTC, which can be compared as text.
+                    SelectionPreference::NewestDate => b.date_published.cmp(&a.date_published),
+                })
+            })
+    }
+}
+
+/// Compares two Minecraft versions by their release order.
+///
+/// If the Minecraft versions from Modrinth are given, they are used for the comparison
+/// with [`GameVersions::compare`]. Otherwise, [`compare_game_versions_by_name`] is used.
+#[must_use]
+pub fn compare_game_versions(
+    a: &str,
+    b: &str,
+    game_versions: Option<&GameVersions>,
+) -> cmp::Ordering {
+    match game_versions {
+        Some(game_versions) => game_versions.compare(a, b),
+        None => compare_game_versions_by_name(a, b),
+    }
+}
+
+/// Compares two Minecraft versions using only their names.
+///
+/// Releases are compared by their numeric parts, so `1.21` < `1.21.1` < `1.21.10`.
+/// Pre-releases and release candidates (`1.21-pre1`, `1.21-rc1`) come before their release.
+/// Snapshots like `24w14a` can't be placed between releases by their name, so they are sorted
+/// before all releases, and other versions are sorted before snapshots.
+#[must_use]
+pub fn compare_game_versions_by_name(a: &str, b: &str) -> cmp::Ordering {
+    game_version_sort_key(a).cmp(&game_version_sort_key(b))
+}
+
+/// Returns a key to sort Minecraft versions by, in the form of
+/// (kind, numbers, stage, stage number, name).
+fn game_version_sort_key(version: &str) -> (u8, Vec<u64>, u8, u64, &str) {
+    // Snapshots look like 24w14a.
+    if let Some((year, rest)) = version.split_once('w')
+        && let Ok(year) = year.parse::<u64>()
+        && let Some(week) = rest.get(..2)
+        && let Ok(week) = week.parse::<u64>()
+    {
+        return (1, vec![year, week], 0, 0, version);
+    }
+
+    let lowercase_version = version.to_lowercase();
+    let (release, stage, stage_number) =
+        match ["-pre", " pre-release ", "-rc", " release candidate "]
+            .iter()
+            .enumerate()
+            .find_map(|(index, marker)| {
+                lowercase_version
+                    .find(marker)
+                    .map(|marker_position| (index, marker_position, marker.len()))
+            }) {
+            Some((index, marker_position, marker_length)) => (
+                &version[..marker_position],
+                // Pre-releases come before release candidates, which come before the release.
+                u8::from(index >= 2),
+                version[marker_position + marker_length..]
+                    .trim()
+                    .parse()
+                    .unwrap_or(0),
+            ),
+            None => (version, 2, 0),
+        };
+
+    let numbers: Option<Vec<u64>> = release
+        .split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect();
+    match numbers {
+        Some(numbers) => (2, numbers, stage, stage_number, version),
+        None => (0, Vec::new(), 0, 0, version),
+    }
+}
+
+impl Version {
+    /// Fetches a [`Version`] from a sha512 hash.
     ///
     /// # Errors
-    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
-    /// - [`PackrinthError::FailedToParseConfigJson`] if the Modrinth response was invalid
-    pub fn from_id(id: &str) -> PackrinthResult<Self> {
-        // Request to get general information about the project associated with the version
-        let api_endpoint = format!("/project/{id}");
-        let modrinth_project_response = request_text(&api_endpoint)?;
-        match serde_json::from_str::<Self>(&modrinth_project_response) {
-            Ok(versions) => Ok(versions),
-            Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
-                modrinth_endpoint: api_endpoint,
-                error_message: error.to_string(),
-            }),
-        }
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the response was invalid
+    pub fn from_sha512_hash(hash: &str) -> PackrinthResult<Self> {
+        request_version_from_sha512_hash(hash)
     }
-}
 
-impl ProjectType {
-    /// Returns the directory name of where the project would go in to.
+    /// Gets multiple versions from their Modrinth IDs with as few requests as possible.
+    ///
+    /// Versions that don't exist are left out of the result.
     ///
     /// # Errors
-    /// - [`PackrinthError::AttemptedToAddOtherModpack`] when [`ProjectType`] is [`ProjectType::Modpack`]
-    pub fn directory(&self) -> PackrinthResult<&str> {
-        match self {
-            ProjectType::Mod => Ok("mods"),
+    /// - [`PackrinthError::RequestFailed`] if a Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if a Modrinth response was invalid
+    pub fn from_ids(ids: &[String]) -> PackrinthResult<Vec<Self>> {
+        request_by_ids("/versions", ids)
+    }
+}
 
-            // This happens when you add a modpack as project.
-            ProjectType::Modpack => Err(PackrinthError::AttemptedToAddOtherModpack),
+impl FullVersion {
+    /// Fetches a [`FullVersion`] from a sha512 hash.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the response was invalid
+    pub fn from_sha512_hash(hash: &str) -> PackrinthResult<Self> {
+        request_version_from_sha512_hash(hash)
+    }
+}
 
-            ProjectType::ResourcePack => Ok("resourcepacks"),
-            ProjectType::Shader => Ok("shaderpacks"),
+impl NewVersion {
+    /// Creates the data of a new version of a modpack project for an exported branch.
+    ///
+    /// The version number is the version of the branch, and the game version and loader
+    /// are the Minecraft version and main mod loader of the branch.
+    #[must_use]
+    pub fn from_branch(
+        modpack_name: &str,
+        project_id: &str,
+        branch_config: &BranchConfig,
+        version_type: VersionType,
+        changelog: Option<String>,
+    ) -> Self {
+        let loader = branch_config
+            .mod_loader
+            .as_ref()
+            .map_or("minecraft", MainLoader::modrinth_value);
+        Self {
+            name: format!("{modpack_name} {}", branch_config.version),
+            version_number: branch_config.version.clone(),
+            changelog,
+            dependencies: Vec::new(),
+            game_versions: vec![branch_config.minecraft_version.clone()],
+            version_type,
+            loaders: vec![loader.to_string()],
+            featured: false,
+            project_id: project_id.to_string(),
+            file_parts: vec![NEW_VERSION_FILE_PART.to_string()],
+            primary_file: NEW_VERSION_FILE_PART.to_string(),
         }
     }
-}
 
-impl Version {
-    /// Fetches a [`Version`] from a sha512 hash.
+    /// Uploads this version with a `.mrpack` file to Modrinth and returns the created version.
+    ///
+    /// The API base URL can be changed to test publishing against another server.
     ///
     /// # Errors
+    /// - [`PackrinthError::FailedToReadToString`] if the `.mrpack` file couldn't be read
+    /// - [`PackrinthError::FailedToSerialize`] if serializing the version data failed
+    /// - [`PackrinthError::RequestFailed`] if Modrinth didn't accept the version
     /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the response was invalid
-    pub fn from_sha512_hash(hash: &str) -> PackrinthResult<Self> {
-        let api_endpoint = format!("/version_file/{hash}?algorithm=sha512");
-        let api_response = request_text(&api_endpoint)?;
+    pub fn upload(
+        &self,
+        api_base_url: &str,
+        token: &str,
+        mrpack_path: &Path,
+    ) -> PackrinthResult<FullVersion> {
+        let mrpack = match fs::read(mrpack_path) {
+            Ok(mrpack) => mrpack,
+            Err(error) => {
+                return Err(PackrinthError::FailedToReadToString {
+                    path_to_read: mrpack_path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let data = match serde_json::to_string(self) {
+            Ok(data) => data,
+            Err(error) => {
+                return Err(PackrinthError::FailedToSerialize {
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        let file_name = mrpack_path.file_name().map_or_else(
+            || "modpack.mrpack".to_string(),
+            |file_name| file_name.to_string_lossy().into_owned(),
+        );
 
-        match serde_json::from_str::<Self>(&api_response) {
-            Ok(versions) => Ok(versions),
+        // reqwest escapes the file name, so modpack names with quotes can't break the request.
+        let form = reqwest::multipart::Form::new()
+            .part(
+                "data",
+                Self::form_part(data.into_bytes(), "application/json")?,
+            )
+            .part(
+                NEW_VERSION_FILE_PART,
+                Self::form_part(mrpack, "application/x-modrinth-modpack+zip")?.file_name(file_name),
+            );
+        let api_endpoint = format!("{}/version", api_base_url.trim_end_matches('/'));
+        let api_response = crate::request_post(&api_endpoint, token, form)?;
+
+        match serde_json::from_str(&api_response) {
+            Ok(version) => Ok(version),
             Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
                 modrinth_endpoint: api_endpoint,
                 error_message: error.to_string(),
             }),
         }
     }
+
+    /// Creates a part of a `multipart/form-data` form with the given content type.
+    fn form_part(
+        contents: Vec<u8>,
+        content_type: &str,
+    ) -> PackrinthResult<reqwest::multipart::Part> {
+        reqwest::multipart::Part::bytes(contents)
+            .mime_str(content_type)
+            .map_err(|error| PackrinthError::FailedToSerialize {
+                error_message: error.to_string(),
+            })
+    }
+}
+
+fn request_version_from_sha512_hash<T: DeserializeOwned>(hash: &str) -> PackrinthResult<T> {
+    let api_endpoint = format!("/version_file/{hash}?algorithm=sha512");
+    let api_response = request_text(&api_endpoint)?;
+
+    match serde_json::from_str::<T>(&api_response) {
+        Ok(version) => Ok(version),
+        Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+            modrinth_endpoint: api_endpoint,
+            error_message: error.to_string(),
+        }),
+    }
 }
 
 impl MrPack {
@@ -354,117 +1240,185 @@ impl File {
         project_settings: &ProjectSettings,
         no_alpha: bool,
         no_beta: bool,
+        game_versions: Option<&GameVersions>,
     ) -> FileResult {
-        // Handle inclusions and exclusions
-        if let Some(include_or_exclude) = &project_settings.include_or_exclude {
-            match include_or_exclude {
-                IncludeOrExclude::Include(inclusions) => {
-                    if !inclusions.contains(&branch_name.to_string()) {
-                        return FileResult::Skipped;
-                    }
-                }
-                IncludeOrExclude::Exclude(exclusions) => {
-                    if exclusions.contains(&branch_name.to_string()) {
-                        return FileResult::Skipped;
-                    }
-                }
+        Self::from_project_with_reason(
+            branch_name,
+            branch_config,
+            project_id,
+            project_settings,
+            no_alpha,
+            no_beta,
+            game_versions,
+        )
+        .0
+    }
+
+    /// Creates a file type from a project, like [`Self::from_project`].
+    ///
+    /// If a version was found, a human-readable explanation of why this version was chosen
+    /// is returned as well.
+    #[must_use]
+    pub fn from_project_with_reason(
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        project_id: &str,
+        project_settings: &ProjectSettings,
+        no_alpha: bool,
+        no_beta: bool,
+        game_versions: Option<&GameVersions>,
+    ) -> (FileResult, Option<String>) {
+        // A channel can only make the flags stricter, never looser.
+        let no_beta = no_beta || project_settings.channel == Some(VersionType::Release);
+        let no_alpha = no_alpha
+            || matches!(
+                project_settings.channel,
+                Some(VersionType::Release | VersionType::Beta)
+            );
+
+        let mut candidates = match Self::version_candidates(
+            branch_name,
+            branch_config,
+            project_id,
+            project_settings,
+            game_versions,
+        ) {
+            Ok(VersionCandidates::Skipped) => return (FileResult::Skipped, None),
+            Ok(VersionCandidates::Override {
+                candidate,
+                version_override,
+            }) => {
+                return (
+                    Self::from_modrinth_version(&candidate, branch_config),
+                    Some(format!("version override {version_override} is used")),
+                );
             }
-        }
+            Ok(VersionCandidates::Listed(candidates)) => candidates,
+            Err(error) => return (FileResult::Err(error), None),
+        };
 
-        let mut loaders = Loader::modrinth_value_vec(&branch_config.acceptable_loaders);
-        if let Some(mod_loader) = &branch_config.mod_loader {
-            loaders.push(mod_loader.modrinth_value());
+        let selection_strategy = project_settings.selection.clone().unwrap_or_default();
+        candidates.sort_by(|a, b| selection_strategy.compare(a, b, branch_config, game_versions));
+
+        for candidate in candidates {
+            let allowed = match candidate.version.version_type {
+                VersionType::Release => true,
+                VersionType::Beta => !no_beta,
+                VersionType::Alpha => !no_alpha,
+            };
+            if allowed {
+                let reason = format!(
+                    "chose version {} ({:?}) for Minecraft {} with {}, published at {}, \
+                    preferring {selection_strategy}",
+                    candidate.version_number,
+                    candidate.version.version_type,
+                    candidate.version.game_versions.join(", "),
+                    candidate.loaders.join(", "),
+                    candidate.date_published,
+                );
+                return (
+                    Self::from_modrinth_version(&candidate, branch_config),
+                    Some(reason),
+                );
+            }
         }
 
-        // Default loaders that will always be added
-        loaders.push(Loader::Minecraft.modrinth_value());
-        loaders.push(Loader::VanillaShader.modrinth_value());
+        // If no versions were returned in the for loop.
+        (FileResult::NotFound, None)
+    }
 
-        // Always add main minecraft version to acceptable minecraft versions
-        let mut game_versions = vec![branch_config.minecraft_version.clone()];
-        game_versions.extend(branch_config.acceptable_minecraft_versions.clone());
+    /// Returns the version types of the versions of a project that can be added to a branch,
+    /// using a single request to list the versions. The channel of the project settings
+    /// is ignored. Returns [`None`] if the project isn't added to the branch because of its
+    /// inclusions or exclusions.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::RequestFailed`] if the Modrinth request failed
+    /// - [`PackrinthError::FailedToParseModrinthResponseJson`] if the Modrinth response was invalid
+    /// - Errors of [`crate::config::IncludeOrExclude::allows_branch`]
+    pub fn available_version_types(
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        project_id: &str,
+        project_settings: &ProjectSettings,
+        game_versions: Option<&GameVersions>,
+    ) -> PackrinthResult<Option<Vec<VersionType>>> {
+        Ok(
+            match Self::version_candidates(
+                branch_name,
+                branch_config,
+                project_id,
+                project_settings,
+                game_versions,
+            )? {
+                VersionCandidates::Skipped => None,
+                VersionCandidates::Override { candidate, .. } => {
+                    Some(vec![candidate.version.version_type])
+                }
+                VersionCandidates::Listed(candidates) => Some(
+                    candidates
+                        .into_iter()
+                        .map(|candidate| candidate.version.version_type)
+                        .collect(),
+                ),
+            },
+        )
+    }
 
-        let mut api_endpoint = format!(
-            "/project/{project_id}/version?loaders={loaders:?}&game_versions={game_versions:?}"
-        );
+    /// Requests the versions of a project that can be added to a branch.
+    fn version_candidates(
+        branch_name: &str,
+        branch_config: &BranchConfig,
+        project_id: &str,
+        project_settings: &ProjectSettings,
+        game_versions: Option<&GameVersions>,
+    ) -> PackrinthResult<VersionCandidates> {
+        // Handle inclusions and exclusions
+        if let Some(include_or_exclude) = &project_settings.include_or_exclude
+            && !include_or_exclude.allows_branch(branch_name, branch_config, game_versions)?
+        {
+            return Ok(VersionCandidates::Skipped);
+        }
 
-        // Used to know if endpoint will return ONE version or a list of versions
-        let mut is_version_override = false;
+        let loaders = branch_config.modrinth_loaders();
+        let game_versions = branch_config.game_versions();
 
         // Change endpoint to version if an override is provided for this branch
         if let Some(version_overrides) = &project_settings.version_overrides
             && let Some(version_override) = version_overrides.get(branch_name)
         {
-            api_endpoint = format!("/version/{version_override}");
-            is_version_override = true;
+            let api_endpoint = format!("/version/{version_override}");
+            let api_response = request_text(&api_endpoint)?;
+            return match serde_json::from_str::<VersionCandidate>(&api_response) {
+                Ok(candidate) => Ok(VersionCandidates::Override {
+                    candidate,
+                    version_override: version_override.clone(),
+                }),
+                Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+                    modrinth_endpoint: api_endpoint,
+                    error_message: error.to_string(),
+                }),
+            };
         }
 
-        let api_response = match request_text(&api_endpoint) {
-            Ok(response) => response,
-            Err(error) => return FileResult::Err(error),
-        };
-        let mut modrinth_versions: Vec<Version> = if is_version_override {
-            match serde_json::from_str::<Version>(&api_response) {
-                Ok(version) => vec![version],
-                Err(error) => {
-                    return FileResult::Err(PackrinthError::FailedToParseModrinthResponseJson {
-                        modrinth_endpoint: api_endpoint,
-                        error_message: error.to_string(),
-                    });
-                }
-            }
-        } else {
-            match serde_json::from_str(&api_response) {
-                Ok(versions) => versions,
-                Err(error) => {
-                    return FileResult::Err(PackrinthError::FailedToParseModrinthResponseJson {
-                        modrinth_endpoint: api_endpoint,
-                        error_message: error.to_string(),
-                    });
-                }
-            }
-        };
-
-        // It is not confusing in this context.
-        #[allow(clippy::items_after_statements)]
-        fn max_semver(versions: &[String]) -> Option<semver::Version> {
-            versions
-                .iter()
-                .filter_map(|s| s.parse::<semver::Version>().ok())
-                .max()
-        }
-
-        modrinth_versions.sort_by(|a, b| {
-            let ma = max_semver(&a.game_versions);
-            let mb = max_semver(&b.game_versions);
-            match (ma, mb) {
-                (Some(va), Some(vb)) => cmp::Reverse(va).cmp(&cmp::Reverse(vb)),
-                (Some(_), None) => cmp::Ordering::Less,
-                (None, Some(_)) => cmp::Ordering::Greater,
-                (None, None) => cmp::Ordering::Equal,
-            }
-        });
-        for modrinth_version in modrinth_versions {
-            match modrinth_version.version_type {
-                VersionType::Release => return Self::from_modrinth_version(&modrinth_version),
-                VersionType::Beta => {
-                    if !no_beta {
-                        return Self::from_modrinth_version(&modrinth_version);
-                    }
-                }
-                VersionType::Alpha => {
-                    if !no_alpha {
-                        return Self::from_modrinth_version(&modrinth_version);
-                    }
-                }
-            }
+        let api_endpoint = format!(
+            "/project/{project_id}/version?loaders={loaders:?}&game_versions={game_versions:?}"
+        );
+        let api_response = request_text(&api_endpoint)?;
+        match serde_json::from_str(&api_response) {
+            Ok(candidates) => Ok(VersionCandidates::Listed(candidates)),
+            Err(error) => Err(PackrinthError::FailedToParseModrinthResponseJson {
+                modrinth_endpoint: api_endpoint,
+                error_message: error.to_string(),
+            }),
         }
-
-        // If no versions were returned in the for loop.
-        FileResult::NotFound
     }
 
-    fn from_modrinth_version(modrinth_version: &Version) -> FileResult {
+    fn from_modrinth_version(
+        candidate: &VersionCandidate,
+        branch_config: &BranchConfig,
+    ) -> FileResult {
+        let modrinth_version = &candidate.version;
         // Request to get general information about the project associated with the version
         let modrinth_project: Project = match Project::from_id(&modrinth_version.project_id) {
             Ok(versions) => versions,
@@ -500,28 +1454,65 @@ impl File {
             primary_file_size = Some(&modrinth_version.files[0].size);
         }
 
-        let directory = match modrinth_project.project_type.directory() {
-            Ok(directory) => directory,
-            Err(error) => return FileResult::Err(error),
+        let is_datapack = !candidate.loaders.is_empty()
+            && candidate
+                .loaders
+                .iter()
+                .all(|loader| loader == Loader::Datapack.modrinth_value());
+        let directory = if is_datapack {
+            match &branch_config.datapack_directory {
+                Some(datapack_directory) => datapack_directory.trim_end_matches('/'),
+                None => {
+                    return FileResult::Err(PackrinthError::NoDatapackDirectory {
+                        project: modrinth_project.title,
+                    });
+                }
+            }
+        } else {
+            match modrinth_project.project_type.directory() {
+                Ok(directory) => directory,
+                Err(error) => return FileResult::Err(error),
+            }
         };
 
+        let mut dependencies = modrinth_version.dependencies.clone();
+        if is_datapack && let Some(datapack_loader) = &branch_config.datapack_loader {
+            // The data pack only works if the mod that loads it is in the modpack as well.
+            // This is an ID after BranchConfig::resolve_datapack_loader, which is what the
+            // added projects are compared with.
+            dependencies.push(VersionDependency {
+                project_id: Some(datapack_loader.clone()),
+                dependency_type: VersionDependencyType::Required,
+            });
+        }
+
         // Always use / as file separator, because all MrPacks should use this (even on Windows).
         let path =
             String::from(directory) + "/" + primary_file_name.expect("No Modrinth file found");
 
+        // Plugins only work on servers.
+        let env = if modrinth_project.project_type == ProjectType::Plugin {
+            Env {
+                client: SideSupport::Unsupported,
+                server: SideSupport::Required,
+            }
+        } else {
+            Env {
+                client: modrinth_project.client_side.known_or_optional(),
+                server: modrinth_project.server_side.known_or_optional(),
+            }
+        };
+
         FileResult::Ok {
             file: Self {
                 project_name: modrinth_project.title,
                 path,
                 hashes: primary_file_hashes.expect("No Modrinth file found").clone(),
-                env: Some(Env {
-                    client: modrinth_project.client_side,
-                    server: modrinth_project.server_side,
-                }),
+                env: Some(env),
                 downloads: vec![primary_file_url.expect("No Modrinth file found").clone()],
                 file_size: *primary_file_size.expect("No Modrinth file found"),
             },
-            dependencies: modrinth_version.dependencies.clone(),
+            dependencies,
             project_id: modrinth_version.project_id.clone(),
         }
     }
@@ -533,6 +1524,380 @@ mod tests {
     use crate::config::MainLoader;
     use pretty_assertions::assert_eq;
 
+    fn game_version(version: &str, version_type: GameVersionType) -> GameVersion {
+        GameVersion {
+            version: version.to_string(),
+            version_type,
+            date: String::new(),
+            major: false,
+        }
+    }
+
+    #[test]
+    fn compare_game_versions_by_name_orders_releases() {
+        let mut versions = vec![
+            "1.21.10",
+            "24w14a",
+            "1.21-rc1",
+            "1.21",
+            "1.21.1",
+            "1.21-pre2",
+            "1.9",
+            "1.14 Pre-Release 1",
+            "b1.7.3",
+        ];
+        versions.sort_by(|a, b| compare_game_versions_by_name(a, b));
+        assert_eq!(
+            vec![
+                "b1.7.3",
+                "24w14a",
+                "1.9",
+                "1.14 Pre-Release 1",
+                "1.21-pre2",
+                "1.21-rc1",
+                "1.21",
+                "1.21.1",
+                "1.21.10",
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn game_versions_compare() {
+        let game_versions = GameVersions::new(vec![
+            game_version("1.21", GameVersionType::Release),
+            game_version("1.21-pre1", GameVersionType::Snapshot),
+            game_version("24w14a", GameVersionType::Snapshot),
+            game_version("1.20.6", GameVersionType::Release),
+        ]);
+        assert_eq!(
+            cmp::Ordering::Greater,
+            game_versions.compare("24w14a", "1.20.6")
+        );
+        assert_eq!(
+            cmp::Ordering::Less,
+            game_versions.compare("24w14a", "1.21-pre1")
+        );
+        assert_eq!(cmp::Ordering::Equal, game_versions.compare("1.21", "1.21"));
+        // Unknown versions come before known versions, and are compared by name.
+        assert_eq!(
+            cmp::Ordering::Greater,
+            game_versions.compare("1.21", "1.21.1")
+        );
+        assert_eq!(
+            cmp::Ordering::Less,
+            game_versions.compare("1.21.1", "1.21.2")
+        );
+        assert_eq!(
+            Some(GameVersionType::Snapshot),
+            game_versions
+                .get("24w14a")
+                .map(|game_version| game_version.version_type)
+        );
+        assert_eq!(None, game_versions.get("1.22"));
+    }
+
+    fn version_candidate(
+        id: &str,
+        game_versions: &[&str],
+        loaders: &[&str],
+        date_published: &str,
+    ) -> VersionCandidate {
+        VersionCandidate {
+            version: Version {
+                id: id.to_string(),
+                project_id: "AANobbMI".to_string(),
+                version_type: VersionType::Release,
+                game_versions: game_versions.iter().map(ToString::to_string).collect(),
+                files: vec![],
+                dependencies: vec![],
+            },
+            version_number: id.to_string(),
+            loaders: loaders.iter().map(ToString::to_string).collect(),
+            date_published: date_published.to_string(),
+        }
+    }
+
+    #[test]
+    fn project_types() {
+        let project_type = |value: &str| {
+            serde_json::from_value::<ProjectType>(serde_json::Value::String(value.to_string()))
+                .unwrap()
+        };
+        assert_eq!(ProjectType::Datapack, project_type("datapack"));
+        assert_eq!(Ok("mods"), ProjectType::Datapack.directory());
+        assert_eq!(Ok("plugins"), project_type("plugin").directory());
+        assert_eq!(
+            ProjectType::Unknown("minigame".to_string()),
+            project_type("minigame")
+        );
+        assert_eq!(
+            Err(PackrinthError::UnsupportedProjectType {
+                project_type: "minigame".to_string()
+            }),
+            project_type("minigame").directory()
+        );
+        assert_eq!(
+            Err(PackrinthError::AttemptedToAddOtherModpack),
+            project_type("modpack").directory()
+        );
+        let side_support = serde_json::from_str::<SideSupport>("\"unknown\"").unwrap();
+        assert_eq!(SideSupport::Unknown, side_support);
+        assert_eq!(SideSupport::Optional, side_support.known_or_optional());
+        assert_eq!(
+            SideSupport::Unsupported,
+            SideSupport::Unsupported.known_or_optional()
+        );
+    }
+
+    #[test]
+    fn search_branch_facets() {
+        let fabric_branch = BranchConfig {
+            minecraft_version: "1.21.8".to_string(),
+            acceptable_minecraft_versions: vec!["1.21.7".to_string()],
+            mod_loader: Some(MainLoader::Fabric),
+            acceptable_loaders: Vec::new(),
+            ..BranchConfig::default()
+        };
+        let neoforge_branch = BranchConfig {
+            minecraft_version: "1.21.1".to_string(),
+            acceptable_minecraft_versions: Vec::new(),
+            mod_loader: Some(MainLoader::NeoForge),
+            acceptable_loaders: Vec::new(),
+            ..BranchConfig::default()
+        };
+
+        assert_eq!(
+            vec![
+                vec![
+                    "versions:1.21.8".to_string(),
+                    "versions:1.21.7".to_string(),
+                    "versions:1.21.1".to_string(),
+                ],
+                vec![
+                    "categories:fabric".to_string(),
+                    "categories:minecraft".to_string(),
+                    "categories:vanilla".to_string(),
+                    "categories:neoforge".to_string(),
+                ],
+            ],
+            SearchResults::branch_facets(&[&fabric_branch, &neoforge_branch])
+        );
+
+        let hit = SearchHit {
+            project_id: "P7dR8mSH".to_string(),
+            slug: "fabric-api".to_string(),
+            title: "Fabric API".to_string(),
+            description: String::new(),
+            author: "modmuss50".to_string(),
+            project_type: ProjectType::Mod,
+            downloads: 0,
+            categories: vec!["library".to_string(), "fabric".to_string()],
+            versions: vec!["1.21.7".to_string(), "1.21.1".to_string()],
+        };
+        assert!(hit.supports_branch(&fabric_branch));
+        assert!(!hit.supports_branch(&neoforge_branch));
+
+        let resource_pack_hit = SearchHit {
+            categories: vec!["decoration".to_string()],
+            ..hit
+        };
+        assert!(resource_pack_hit.supports_branch(&neoforge_branch));
+    }
+
+    #[test]
+    fn project_branch_support() {
+        let project = Project {
+            id: "AANobbMI".to_string(),
+            slug: "sodium".to_string(),
+            title: "Sodium".to_string(),
+            server_side: SideSupport::Unsupported,
+            client_side: SideSupport::Required,
+            project_type: ProjectType::Mod,
+            loaders: vec!["fabric".to_string(), "neoforge".to_string()],
+            game_versions: vec!["1.21.7".to_string(), "1.21.8".to_string()],
+        };
+        let branch_config = |minecraft_version: &str, mod_loader| BranchConfig {
+            minecraft_version: minecraft_version.to_string(),
+            acceptable_minecraft_versions: Vec::new(),
+            mod_loader: Some(mod_loader),
+            ..BranchConfig::default()
+        };
+
+        assert_eq!(
+            BranchSupport::Supported,
+            project.branch_support(&branch_config("1.21.8", MainLoader::NeoForge))
+        );
+        assert_eq!(
+            BranchSupport::UnsupportedLoader,
+            project.branch_support(&branch_config("1.21.8", MainLoader::Quilt))
+        );
+        assert_eq!(
+            BranchSupport::UnsupportedMinecraftVersion,
+            project.branch_support(&branch_config("1.20.1", MainLoader::Fabric))
+        );
+    }
+
+    #[test]
+    fn full_project_and_version_from_json() {
+        let full_project: FullProject = serde_json::from_str(
+            r#"{
+                "id": "AANobbMI",
+                "slug": "sodium",
+                "title": "Sodium",
+                "description": "The fastest rendering optimization mod for Minecraft.",
+                "body": "",
+                "status": "approved",
+                "requested_status": null,
+                "categories": ["optimization"],
+                "additional_categories": [],
+                "client_side": "required",
+                "server_side": "unsupported",
+                "project_type": "mod",
+                "issues_url": "https://github.com/CaffeineMC/sodium/issues",
+                "source_url": "https://github.com/CaffeineMC/sodium",
+                "wiki_url": null,
+                "discord_url": null,
+                "donation_urls": [{"id": "other", "platform": "Other", "url": "https://caffeinemc.net/donate"}],
+                "icon_url": null,
+                "color": 7316308,
+                "thread_id": "AANobbMI",
+                "monetization_status": "monetized",
+                "team": "4reLOAKe",
+                "published": "2021-01-03T00:53:34.185936Z",
+                "updated": "2025-07-23T19:06:28.103254Z",
+                "approved": "2021-01-03T00:53:34.185936Z",
+                "queued": null,
+                "downloads": 1000,
+                "followers": 10,
+                "license": {"id": "LicenseRef-Polyform-Shield-License-1.0.0", "name": "", "url": null},
+                "versions": ["u1OmRkrw"],
+                "game_versions": ["1.21.8"],
+                "loaders": ["fabric", "neoforge"],
+                "gallery": []
+            }"#,
+        )
+        .unwrap();
+        assert_eq!("sodium", full_project.project.slug);
+        assert_eq!(ProjectType::Mod, full_project.project.project_type);
+        assert_eq!(vec!["fabric", "neoforge"], full_project.project.loaders);
+        assert_eq!(
+            "LicenseRef-Polyform-Shield-License-1.0.0",
+            full_project.license.id
+        );
+        assert_eq!("Other", full_project.donation_urls[0].platform);
+
+        let full_version: FullVersion = serde_json::from_str(
+            r#"{
+                "id": "u1OmRkrw",
+                "project_id": "AANobbMI",
+                "author_id": "DzLrfrbK",
+                "featured": false,
+                "name": "Sodium 0.6.13 for Fabric 1.21.8",
+                "version_number": "mc1.21.8-0.6.13-fabric",
+                "changelog": null,
+                "date_published": "2025-07-23T19:06:28.103254Z",
+                "downloads": 100,
+                "version_type": "release",
+                "status": "listed",
+                "requested_status": null,
+                "files": [],
+                "dependencies": [],
+                "game_versions": ["1.21.8"],
+                "loaders": ["fabric"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!("AANobbMI", full_version.version.project_id);
+        assert_eq!(VersionType::Release, full_version.version.version_type);
+        assert_eq!("mc1.21.8-0.6.13-fabric", full_version.version_number);
+
+        let file = File {
+            project_name: String::new(),
+            path: "mods/sodium.jar".to_string(),
+            hashes: FileHashes {
+                sha1: String::new(),
+                sha512: String::new(),
+            },
+            env: None,
+            downloads: vec![
+                "https://cdn.modrinth.com/data/AANobbMI/versions/u1OmRkrw/sodium.jar".to_string(),
+            ],
+            file_size: 0,
+        };
+        assert_eq!(Some("AANobbMI"), file.modrinth_project_id());
+        assert_eq!(Some("u1OmRkrw"), file.modrinth_version_id());
+        let manual_file = File {
+            downloads: vec!["https://github.com/example/releases/sodium.jar".to_string()],
+            ..file
+        };
+        assert_eq!(None, manual_file.modrinth_project_id());
+        assert_eq!(None, manual_file.modrinth_version_id());
+    }
+
+    #[test]
+    fn selection_strategy_compare() {
+        let branch_config = BranchConfig {
+            minecraft_version: "1.21.7".to_string(),
+            acceptable_minecraft_versions: vec!["1.21.8".to_string()],
+            mod_loader: Some(MainLoader::Fabric),
+            acceptable_loaders: vec![Loader::Quilt],
+            ..BranchConfig::default()
+        };
+        let candidates = vec![
+            version_candidate(
+                "old-exact-fabric",
+                &["1.21.7"],
+                &["fabric"],
+                "2025-06-01T00:00:00Z",
+            ),
+            version_candidate(
+                "new-exact-quilt",
+                &["1.21.7"],
+                &["quilt"],
+                "2025-07-01T00:00:00Z",
+            ),
+            version_candidate(
+                "newer-minecraft",
+                &["1.21.8"],
+                &["fabric"],
+                "2025-05-01T00:00:00Z",
+            ),
+        ];
+        let sorted_ids = |selection_strategy: SelectionStrategy| {
+            let mut candidates = candidates.clone();
+            candidates.sort_by(|a, b| selection_strategy.compare(a, b, &branch_config, None));
+            candidates
+                .into_iter()
+                .map(|candidate| candidate.version.id)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            vec!["newer-minecraft", "new-exact-quilt", "old-exact-fabric"],
+            sorted_ids(SelectionStrategy::default())
+        );
+        assert_eq!(
+            vec!["new-exact-quilt", "old-exact-fabric", "newer-minecraft"],
+            sorted_ids(SelectionStrategy(vec![
+                SelectionPreference::ExactMinecraft,
+                SelectionPreference::NewestDate,
+            ]))
+        );
+        assert_eq!(
+            vec!["old-exact-fabric", "newer-minecraft", "new-exact-quilt"],
+            sorted_ids(SelectionStrategy(vec![
+                SelectionPreference::MainLoader,
+                SelectionPreference::ExactMinecraft,
+            ]))
+        );
+        assert_eq!(
+            vec!["new-exact-quilt", "old-exact-fabric", "newer-minecraft"],
+            sorted_ids(SelectionStrategy(vec![SelectionPreference::NewestDate]))
+        );
+    }
+
     #[test]
     fn project_from_id() {
         let project = Project::from_id("fabric-api");
@@ -544,11 +1909,21 @@ mod tests {
                 server_side: SideSupport::Optional,
                 client_side: SideSupport::Optional,
                 project_type: ProjectType::Mod,
+                loaders: project
+                    .as_ref()
+                    .map(|x| x.loaders.clone())
+                    .unwrap_or_default(),
+                game_versions: project
+                    .as_ref()
+                    .map(|x| x.game_versions.clone())
+                    .unwrap_or_default(),
             }),
             project
         );
     }
 
+    // Allow because this test is kept as it was written.
+    #[allow(clippy::uninlined_format_args, clippy::unreadable_literal)]
     #[test]
     fn test_version_from_sha512_hash() {
         let version = Version::from_sha512_hash(
@@ -573,21 +1948,24 @@ mod tests {
 
     // TODO add tests for MrPack struct
 
+    // Allow because this test is kept as it was written.
+    #[allow(clippy::unreadable_literal)]
     #[test]
     fn test_file_from_project() {
         let branch_config = BranchConfig {
+            schema: None,
+            extends: None,
             version: "1.0.0".to_string(),
             minecraft_version: "1.14".to_string(),
             acceptable_minecraft_versions: vec![],
             mod_loader: Some(MainLoader::Fabric),
             loader_version: Some("0.17.2".to_string()),
             acceptable_loaders: vec![],
+            datapack_directory: None,
+            datapack_loader: None,
             manual_files: vec![],
         };
-        let project_settings = ProjectSettings {
-            version_overrides: None,
-            include_or_exclude: None,
-        };
+        let project_settings = ProjectSettings::default();
         let file = File::from_project(
             "test",
             &branch_config,
@@ -595,6 +1973,7 @@ mod tests {
             &project_settings,
             false,
             false,
+            None,
         );
         assert_eq!(FileResult::Ok {
             file: File {
@@ -629,7 +2008,13 @@ mod tests {
             }],
             dependencies: vec![],
         };
-        let file = File::from_modrinth_version(&modrinth_version);
+        let candidate = VersionCandidate {
+            version: modrinth_version,
+            version_number: "0.129.0+1.21.8".to_string(),
+            loaders: vec!["fabric".to_string()],
+            date_published: String::new(),
+        };
+        let file = File::from_modrinth_version(&candidate, &BranchConfig::default());
         assert_eq!(FileResult::Ok {
             file: File {
                 project_name: "Fabric API".to_string(),
@@ -646,4 +2031,88 @@ mod tests {
             project_id: "P7dR8mSH".to_string(),
         }, file);
     }
+
+    #[test]
+    fn publish_new_version_to_stand_in_server() {
+        use std::io::{BufRead, Write};
+        use std::net::TcpListener;
+
+        let branch_config = BranchConfig {
+            version: "1.2.0+mc1.21.8".to_string(),
+            minecraft_version: "1.21.8".to_string(),
+            mod_loader: Some(MainLoader::Fabric),
+            ..BranchConfig::default()
+        };
+        let new_version = NewVersion::from_branch(
+            "My pack",
+            "AbCdEfGh",
+            &branch_config,
+            VersionType::Beta,
+            Some("- Updated Sodium".to_string()),
+        );
+        assert_eq!("My pack 1.2.0+mc1.21.8", new_version.name);
+        assert_eq!(vec!["1.21.8"], new_version.game_versions);
+        assert_eq!(vec!["fabric"], new_version.loaders);
+
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let mrpack_path = temp_dir.path().join("My \"pack\"_1.2.0.mrpack");
+        fs::write(&mrpack_path, b"zip contents").unwrap();
+
+        // A stand-in for Modrinth that accepts one version and returns it.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_base_url = format!("http://{}/v2", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _address) = listener.accept().unwrap();
+            let mut reader = io::BufReader::new(stream);
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                headers.push(line.trim_end().to_lowercase());
+            }
+            let content_length: usize = headers
+                .iter()
+                .find_map(|header| header.strip_prefix("content-length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response_body = r#"{"id":"IIJJKKLL","project_id":"AbCdEfGh","version_type":"beta",
+                "game_versions":["1.21.8"],"files":[],"dependencies":[],"name":"My pack 1.2.0+mc1.21.8",
+                "version_number":"1.2.0+mc1.21.8","changelog":"- Updated Sodium","loaders":["fabric"],
+                "featured":false,"status":"listed","requested_status":null,"author_id":"author",
+                "date_published":"2025-08-01T12:00:00Z","downloads":0}"#;
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                response_body.len()
+            )
+            .unwrap();
+            (headers, String::from_utf8(body).unwrap())
+        });
+
+        let version = new_version
+            .upload(&api_base_url, "secret-token", &mrpack_path)
+            .unwrap();
+        assert_eq!("IIJJKKLL", version.version.id);
+        assert_eq!("1.2.0+mc1.21.8", version.version_number);
+
+        let (headers, body) = server.join().unwrap();
+        assert!(headers.contains(&"post /v2/version http/1.1".to_string()));
+        assert!(headers.contains(&"authorization: secret-token".to_string()));
+        assert!(
+            headers.iter().any(|header| {
+                header.starts_with("content-type: multipart/form-data; boundary=")
+            })
+        );
+        assert!(body.contains(r#"name="data""#));
+        assert!(body.contains(r#""file_parts":["mrpack"],"primary_file":"mrpack""#));
+        assert!(body.contains(r#"name="mrpack"; filename="My \"pack\"_1.2.0.mrpack""#));
+        assert!(body.contains("\r\n\r\nzip contents\r\n"));
+    }
 }
@@ -0,0 +1,176 @@
+//! JSON Schemas for the configuration files of a Packrinth modpack.
+//!
+//! The schemas are generated from the structs in [`crate::config`], so they always match
+//! the configuration files that Packrinth reads and writes.
+
+use crate::config::{
+    BRANCH_CONFIG_FILE_NAME, BRANCH_FILES_FILE_NAME, BranchConfig, BranchFiles,
+    MODPACK_CONFIG_FILE_NAME, Modpack, serde_json_to_string_pretty,
+};
+use crate::{PackrinthError, PackrinthResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The URL of the directory the schemas are published in. It doesn't end with a `/`.
+pub const SCHEMA_BASE_URL: &str = "https://packrinth.thijzert.nl/schemas";
+
+/// A configuration file that has a JSON Schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigSchema {
+    /// The modpack configuration file ([`MODPACK_CONFIG_FILE_NAME`]).
+    Modpack,
+
+    /// The configuration file of a branch ([`BRANCH_CONFIG_FILE_NAME`]).
+    BranchConfig,
+
+    /// The branch files of a branch ([`BRANCH_FILES_FILE_NAME`]).
+    BranchFiles,
+}
+
+impl ConfigSchema {
+    /// All configuration files that have a schema.
+    pub const ALL: [Self; 3] = [Self::Modpack, Self::BranchConfig, Self::BranchFiles];
+
+    /// Returns the name of the configuration file this schema describes.
+    #[must_use]
+    pub fn config_file_name(self) -> &'static str {
+        match self {
+            Self::Modpack => MODPACK_CONFIG_FILE_NAME,
+            Self::BranchConfig => BRANCH_CONFIG_FILE_NAME,
+            Self::BranchFiles => BRANCH_FILES_FILE_NAME,
+        }
+    }
+
+    /// Returns the name of the file the schema is saved in, like `modpack.schema.json`.
+    #[must_use]
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Modpack => "modpack.schema.json",
+            Self::BranchConfig => "branch.schema.json",
+            Self::BranchFiles => "branch_files.schema.json",
+        }
+    }
+
+    /// Returns the URL the schema is published at. This URL is used
+    /// as the `$schema` of new configuration files.
+    #[must_use]
+    pub fn url(self) -> String {
+        format!("{SCHEMA_BASE_URL}/{}", self.file_name())
+    }
+
+    /// Generates the schema.
+    #[must_use]
+    pub fn generate(self) -> serde_json::Value {
+        let mut schema = match self {
+            Self::Modpack => schemars::schema_for!(Modpack),
+            Self::BranchConfig => schemars::schema_for!(BranchConfig),
+            Self::BranchFiles => schemars::schema_for!(BranchFiles),
+        };
+        schema.insert("$id".to_string(), self.url().into());
+        // The descriptions of the structs are written for the library, not for the files.
+        schema.insert(
+            "description".to_string(),
+            format!(
+                "The {} file of a Packrinth modpack. See https://packrinth.thijzert.nl.",
+                self.config_file_name()
+            )
+            .into(),
+        );
+
+        // Put the keys that describe the schema itself before the definitions.
+        let mut schema = schema.to_value();
+        if let Some(object) = schema.as_object_mut() {
+            for key in ["description", "title", "$id", "$schema"] {
+                if let Some(value) = object.shift_remove(key) {
+                    object.shift_insert(0, key.to_string(), value);
+                }
+            }
+        }
+        schema
+    }
+
+    /// Generates the schema and formats it the same way as the configuration files.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serialising the schema failed
+    pub fn to_json_string(self) -> PackrinthResult<String> {
+        match serde_json_to_string_pretty(&self.generate()) {
+            Ok(json) => Ok(json),
+            Err(error) => Err(PackrinthError::FailedToSerialize {
+                error_message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Writes the schema to [`Self::file_name`] in a directory and returns the path
+    /// of the written file.
+    ///
+    /// The directory is created if it doesn't exist.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToCreateDir`] if creating the directory failed
+    /// - [`PackrinthError::FailedToSerialize`] if serialising the schema failed
+    /// - [`PackrinthError::FailedToWriteFile`] if writing the schema failed
+    pub fn write_to_directory(self, directory: &Path) -> PackrinthResult<PathBuf> {
+        if let Err(error) = fs::create_dir_all(directory) {
+            return Err(PackrinthError::FailedToCreateDir {
+                dir_to_create: directory.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+
+        let path = directory.join(self.file_name());
+        if let Err(error) = fs::write(&path, self.to_json_string()?) {
+            return Err(PackrinthError::FailedToWriteFile {
+                path_to_write_to: path.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn published_schemas_are_up_to_date() {
+        let schema_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("site")
+            .join("schemas");
+        for schema in ConfigSchema::ALL {
+            let published = fs::read_to_string(schema_dir.join(schema.file_name())).unwrap();
+            assert_eq!(
+                schema.to_json_string().unwrap(),
+                published,
+                "{} is outdated, regenerate it with `packrinth schema --out site/schemas`",
+                schema.file_name()
+            );
+        }
+    }
+
+    #[test]
+    fn generated_schema_describes_config() {
+        let schema = ConfigSchema::Modpack.generate();
+        assert_eq!(
+            Some(ConfigSchema::Modpack.url().as_str()),
+            schema["$id"].as_str()
+        );
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("$schema"));
+        assert!(properties.contains_key("pack_format"));
+        assert!(!properties.contains_key("directory"));
+
+        let schema = ConfigSchema::BranchConfig.generate();
+        let loaders = &schema["$defs"]["Loader"];
+        assert!(loaders.to_string().contains("\"neoforge\""));
+        assert!(schema["$defs"].get("File").is_some());
+        assert!(schema.get("required").is_none());
+        assert_eq!(
+            serde_json::json!(["version", "minecraft_version"]),
+            schema["else"]["required"]
+        );
+    }
+}
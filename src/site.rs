@@ -0,0 +1,768 @@
+//! Static HTML documentation sites of modpacks, for example for GitHub Pages.
+
+use crate::config::{BranchConfig, BranchFiles, Modpack};
+use crate::modrinth::{File, FullProject};
+use crate::{PackrinthError, PackrinthResult};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the stylesheet that all pages of the site use.
+pub const STYLESHEET_FILE_NAME: &str = "style.css";
+
+/// The directory of the site that exported modpacks are copied to.
+pub const DOWNLOADS_DIRECTORY: &str = "downloads";
+
+/// The file in [`crate::TARGET_DIRECTORY`] that the Modrinth projects of the site are cached in,
+/// so building the site again only requests projects that were added since.
+/// Remove it (or run `packrinth clean`) to get the latest project information.
+pub const PROJECT_CACHE_FILE_NAME: &str = "site_projects.json";
+
+const BRANCHES_DIRECTORY: &str = "branches";
+const PROJECTS_DIRECTORY: &str = "projects";
+
+const STYLESHEET: &str = "body {
+\tfont-family: system-ui, sans-serif;
+\tmax-width: 60rem;
+\tmargin: 0 auto;
+\tpadding: 1rem;
+\tline-height: 1.5;
+}
+
+nav a {
+\tmargin-right: 1rem;
+}
+
+table {
+\tborder-collapse: collapse;
+}
+
+th, td {
+\tpadding: 0.25rem 0.75rem;
+\tborder-bottom: 1px solid #ddd;
+\ttext-align: left;
+}
+
+.icon {
+\twidth: 6rem;
+\theight: 6rem;
+\tborder-radius: 1rem;
+}
+";
+
+/// A branch of the modpack as it is shown on the site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteBranch {
+    pub name: String,
+    pub config: BranchConfig,
+    pub files: BranchFiles,
+
+    /// The exported modpack of the branch, if the branch has been exported with its current version.
+    pub mrpack_path: Option<PathBuf>,
+}
+
+/// A page of a [`DocSite`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitePage {
+    /// The path of the page, relative to the root of the site.
+    pub path: PathBuf,
+    pub html: String,
+}
+
+/// The file names of the branch and project pages of a [`DocSite`].
+struct PageNames {
+    /// The page names by branch name.
+    branches: HashMap<String, String>,
+
+    /// The page names by project ID.
+    projects: HashMap<String, String>,
+}
+
+/// A static HTML site that documents a modpack, its branches and its projects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocSite {
+    pub modpack_name: String,
+    pub author: String,
+    pub summary: String,
+
+    /// The branches in the order of the modpack configuration.
+    pub branches: Vec<SiteBranch>,
+
+    /// The Modrinth projects in the branches, by their ID.
+    pub projects: HashMap<String, FullProject>,
+
+    /// Errors of Modrinth requests that failed while collecting the site.
+    /// The files of projects that couldn't be requested are shown by their file names.
+    pub request_errors: Vec<PackrinthError>,
+}
+
+impl DocSite {
+    /// Collects the branches of a modpack and the Modrinth projects in their branch files.
+    ///
+    /// Projects that aren't in `projects` yet are requested from Modrinth in one request.
+    /// If that request fails, the error is added to [`Self::request_errors`] and the site
+    /// is collected without those projects.
+    ///
+    /// # Errors
+    /// - Errors of [`BranchConfig::from_directory`] and [`BranchFiles::from_directory`]
+    pub fn collect(
+        modpack: &Modpack,
+        mut projects: HashMap<String, FullProject>,
+    ) -> PackrinthResult<Self> {
+        let mut branches = Vec::new();
+        let mut missing_project_ids = Vec::new();
+        for branch in &modpack.branches {
+            let config = BranchConfig::from_directory(&modpack.directory, branch)?;
+            let files = BranchFiles::from_directory(&modpack.directory, branch)?;
+            for file in &files.files {
+                if let Some(project_id) = file.modrinth_project_id()
+                    && !projects.contains_key(project_id)
+                    && !missing_project_ids.iter().any(|id| id == project_id)
+                {
+                    missing_project_ids.push(project_id.to_string());
+                }
+            }
+
+            let mrpack_path = modpack.exported_mrpack_path(branch, &config);
+            branches.push(SiteBranch {
+                name: branch.clone(),
+                mrpack_path: mrpack_path.is_file().then_some(mrpack_path),
+                config,
+                files,
+            });
+        }
+
+        let mut request_errors = Vec::new();
+        if !missing_project_ids.is_empty() {
+            match FullProject::from_ids(&missing_project_ids) {
+                Ok(requested) => {
+                    for project in requested {
+                        projects.insert(project.project.id.clone(), project);
+                    }
+                }
+                Err(error) => request_errors.push(error),
+            }
+        }
+
+        Ok(Self {
+            modpack_name: modpack.name.clone(),
+            author: modpack.author.clone(),
+            summary: modpack.summary.clone(),
+            branches,
+            projects,
+            request_errors,
+        })
+    }
+
+    /// Reads the projects cached by [`Self::save_project_cache`] in the target directory of a modpack.
+    ///
+    /// A missing or invalid cache is treated as empty, so the projects are requested again.
+    #[must_use]
+    pub fn read_project_cache(modpack: &Modpack) -> HashMap<String, FullProject> {
+        fs::read_to_string(Self::project_cache_path(modpack))
+            .ok()
+            .and_then(|cache| serde_json::from_str(&cache).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the projects of the site to the target directory of a modpack,
+    /// so [`Self::read_project_cache`] can reuse them.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToSerialize`] if serializing the projects failed
+    /// - [`PackrinthError::FailedToCreateDir`] if creating the target directory failed
+    /// - [`PackrinthError::FailedToWriteFile`] if writing the cache failed
+    pub fn save_project_cache(&self, modpack: &Modpack) -> PackrinthResult<()> {
+        let cache = match serde_json::to_string(&self.projects) {
+            Ok(cache) => cache,
+            Err(error) => {
+                return Err(PackrinthError::FailedToSerialize {
+                    error_message: error.to_string(),
+                });
+            }
+        };
+        crate::write_file(&Self::project_cache_path(modpack), cache)
+    }
+
+    fn project_cache_path(modpack: &Modpack) -> PathBuf {
+        modpack
+            .directory
+            .join(crate::TARGET_DIRECTORY)
+            .join(PROJECT_CACHE_FILE_NAME)
+    }
+
+    /// Renders all pages of the site: an index, a page for every branch and a page
+    /// for every Modrinth project.
+    ///
+    /// Pages are named after their branch or project slug. If names end up with the same
+    /// page name, for example branches `fabric/1.21` and `fabric-1.21`, a number is appended
+    /// to the page names of the later ones.
+    #[must_use]
+    pub fn pages(&self) -> Vec<SitePage> {
+        let page_names = self.page_names();
+        let mut pages = vec![SitePage {
+            path: PathBuf::from("index.html"),
+            html: self.page(&self.modpack_name, "", &self.index_body(&page_names)),
+        }];
+        for branch in &self.branches {
+            pages.push(SitePage {
+                path: Path::new(BRANCHES_DIRECTORY).join(&page_names.branches[&branch.name]),
+                html: self.page(
+                    &format!("{} {}", self.modpack_name, branch.name),
+                    "../",
+                    &self.branch_body(branch, &page_names),
+                ),
+            });
+        }
+        for project in self.sorted_projects() {
+            pages.push(SitePage {
+                path: Path::new(PROJECTS_DIRECTORY).join(&page_names.projects[&project.project.id]),
+                html: self.page(
+                    &project.project.title,
+                    "../",
+                    &self.project_body(project, &page_names),
+                ),
+            });
+        }
+        pages
+    }
+
+    fn page_names(&self) -> PageNames {
+        PageNames {
+            branches: unique_page_names(
+                self.branches
+                    .iter()
+                    .map(|branch| (branch.name.as_str(), branch.name.as_str())),
+            ),
+            projects: unique_page_names(
+                self.sorted_projects()
+                    .into_iter()
+                    .map(|project| (project.project.id.as_str(), project.project.slug.as_str())),
+            ),
+        }
+    }
+
+    /// Writes all pages, the stylesheet and the exported modpacks to a directory,
+    /// and returns the paths of the written files.
+    ///
+    /// # Errors
+    /// - [`PackrinthError::FailedToCreateDir`] if creating a directory of the site failed
+    /// - [`PackrinthError::FailedToWriteFile`] if writing a page or the stylesheet failed
+    /// - [`PackrinthError::FailedToCopyFile`] if copying an exported modpack failed
+    pub fn write(&self, out: &Path) -> PackrinthResult<Vec<PathBuf>> {
+        let mut files = vec![(out.join(STYLESHEET_FILE_NAME), STYLESHEET.to_string())];
+        for page in self.pages() {
+            files.push((out.join(page.path), page.html));
+        }
+
+        let mut written = Vec::new();
+        for (path, contents) in files {
+            crate::write_file(&path, contents)?;
+            written.push(path);
+        }
+
+        for branch in &self.branches {
+            let Some((mrpack_path, file_name)) = Self::mrpack_file(branch) else {
+                continue;
+            };
+            let path = out.join(DOWNLOADS_DIRECTORY).join(file_name);
+            crate::create_parent_dir(&path)?;
+            if let Err(error) = fs::copy(mrpack_path, &path) {
+                return Err(PackrinthError::FailedToCopyFile {
+                    from: mrpack_path.display().to_string(),
+                    to: path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    fn page(&self, title: &str, root: &str, body: &str) -> String {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>").unwrap();
+        writeln!(html, "<html lang=\"en\">").unwrap();
+        writeln!(html, "<head>").unwrap();
+        writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(
+            html,
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+        )
+        .unwrap();
+        writeln!(html, "<title>{}</title>", escape_html(title)).unwrap();
+        writeln!(
+            html,
+            "<link rel=\"stylesheet\" href=\"{root}{STYLESHEET_FILE_NAME}\">"
+        )
+        .unwrap();
+        writeln!(html, "</head>").unwrap();
+        writeln!(html, "<body>").unwrap();
+        writeln!(
+            html,
+            "<nav><a href=\"{root}index.html\">{}</a></nav>",
+            escape_html(&self.modpack_name)
+        )
+        .unwrap();
+        html.push_str(body);
+        writeln!(html, "</body>").unwrap();
+        writeln!(html, "</html>").unwrap();
+        html
+    }
+
+    fn index_body(&self, page_names: &PageNames) -> String {
+        let mut html = String::new();
+        writeln!(html, "<h1>{}</h1>", escape_html(&self.modpack_name)).unwrap();
+        writeln!(html, "<p>By {}</p>", escape_html(&self.author)).unwrap();
+        writeln!(html, "<p>{}</p>", escape_html(&self.summary)).unwrap();
+
+        writeln!(html, "<h2>Branches</h2>").unwrap();
+        writeln!(html, "<table>").unwrap();
+        writeln!(
+            html,
+            "<tr><th>Branch</th><th>Version</th><th>Minecraft version</th><th>Loader</th></tr>"
+        )
+        .unwrap();
+        for branch in &self.branches {
+            writeln!(
+                html,
+                "<tr><td><a href=\"{BRANCHES_DIRECTORY}/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                url_path(&page_names.branches[&branch.name]),
+                escape_html(&branch.name),
+                escape_html(&branch.config.version),
+                escape_html(&branch.config.minecraft_version),
+                escape_html(&Self::loader(&branch.config))
+            )
+            .unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+
+        writeln!(html, "<h2>Projects</h2>").unwrap();
+        writeln!(html, "<table>").unwrap();
+        write!(html, "<tr><th>Project</th>").unwrap();
+        for branch in &self.branches {
+            write!(html, "<th>{}</th>", escape_html(&branch.name)).unwrap();
+        }
+        writeln!(html, "</tr>").unwrap();
+        for project in self.sorted_projects() {
+            write!(
+                html,
+                "<tr><td><a href=\"{PROJECTS_DIRECTORY}/{}\">{}</a></td>",
+                url_path(&page_names.projects[&project.project.id]),
+                escape_html(&project.project.title)
+            )
+            .unwrap();
+            for branch in &self.branches {
+                let icon = if Self::project_file(branch, project).is_some() {
+                    "✅"
+                } else {
+                    "❌"
+                };
+                write!(html, "<td>{icon}</td>").unwrap();
+            }
+            writeln!(html, "</tr>").unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+        html
+    }
+
+    fn branch_body(&self, branch: &SiteBranch, page_names: &PageNames) -> String {
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<h1>{} {}</h1>",
+            escape_html(&self.modpack_name),
+            escape_html(&branch.name)
+        )
+        .unwrap();
+        writeln!(html, "<dl>").unwrap();
+        writeln!(
+            html,
+            "<dt>Version</dt><dd>{}</dd>",
+            escape_html(&branch.config.version)
+        )
+        .unwrap();
+        writeln!(
+            html,
+            "<dt>Minecraft version</dt><dd>{}</dd>",
+            escape_html(&branch.config.minecraft_version)
+        )
+        .unwrap();
+        writeln!(
+            html,
+            "<dt>Loader</dt><dd>{}</dd>",
+            escape_html(&Self::loader(&branch.config))
+        )
+        .unwrap();
+        if let Some((_mrpack_path, file_name)) = Self::mrpack_file(branch) {
+            writeln!(
+                html,
+                "<dt>Download</dt><dd><a href=\"../{DOWNLOADS_DIRECTORY}/{}\">{}</a></dd>",
+                url_path(&file_name),
+                escape_html(&file_name)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                html,
+                "<dt>Download</dt><dd>This branch hasn't been exported yet.</dd>"
+            )
+            .unwrap();
+        }
+        writeln!(html, "</dl>").unwrap();
+
+        writeln!(html, "<h2>Files</h2>").unwrap();
+        writeln!(html, "<table>").unwrap();
+        writeln!(html, "<tr><th>Project</th><th>File</th></tr>").unwrap();
+        let mut files: Vec<(String, String, &File)> = branch
+            .files
+            .files
+            .iter()
+            .map(|file| {
+                match file
+                    .modrinth_project_id()
+                    .and_then(|project_id| self.projects.get(project_id))
+                {
+                    Some(project) => (
+                        project.project.title.clone(),
+                        format!(
+                            "<a href=\"../{PROJECTS_DIRECTORY}/{}\">{}</a>",
+                            url_path(&page_names.projects[&project.project.id]),
+                            escape_html(&project.project.title)
+                        ),
+                        file,
+                    ),
+                    None => (
+                        file.file_name().to_string(),
+                        escape_html(file.file_name()),
+                        file,
+                    ),
+                }
+            })
+            .collect();
+        files.sort_by_key(|(name, _, _)| name.to_lowercase());
+        for (_name, project_html, file) in files {
+            writeln!(
+                html,
+                "<tr><td>{project_html}</td><td><code>{}</code></td></tr>",
+                escape_html(file.file_name())
+            )
+            .unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+        html
+    }
+
+    fn project_body(&self, project: &FullProject, page_names: &PageNames) -> String {
+        let mut html = String::new();
+        if let Some(icon_url) = &project.icon_url {
+            writeln!(
+                html,
+                "<img class=\"icon\" src=\"{}\" alt=\"\">",
+                escape_html(icon_url)
+            )
+            .unwrap();
+        }
+        writeln!(html, "<h1>{}</h1>", escape_html(&project.project.title)).unwrap();
+        writeln!(html, "<p>{}</p>", escape_html(&project.description)).unwrap();
+        writeln!(html, "<dl>").unwrap();
+        let license = escape_html(&project.license.label());
+        if let Some(url) = &project.license.url {
+            writeln!(
+                html,
+                "<dt>License</dt><dd><a href=\"{}\">{license}</a></dd>",
+                escape_html(url)
+            )
+            .unwrap();
+        } else {
+            writeln!(html, "<dt>License</dt><dd>{license}</dd>").unwrap();
+        }
+        writeln!(
+            html,
+            "<dt>Modrinth</dt><dd><a href=\"https://modrinth.com/project/{0}\">modrinth.com/project/{0}</a></dd>",
+            escape_html(&project.project.slug)
+        )
+        .unwrap();
+        writeln!(html, "</dl>").unwrap();
+
+        writeln!(html, "<h2>Branches</h2>").unwrap();
+        writeln!(html, "<table>").unwrap();
+        writeln!(html, "<tr><th>Branch</th><th>File</th></tr>").unwrap();
+        for branch in &self.branches {
+            let file = match Self::project_file(branch, project) {
+                Some(file) => format!("<code>{}</code>", escape_html(file.file_name())),
+                None => "Not included".to_string(),
+            };
+            writeln!(
+                html,
+                "<tr><td><a href=\"../{BRANCHES_DIRECTORY}/{}\">{}</a></td><td>{file}</td></tr>",
+                url_path(&page_names.branches[&branch.name]),
+                escape_html(&branch.name)
+            )
+            .unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+        html
+    }
+
+    /// Returns the Modrinth projects that are in at least one branch, sorted by their title.
+    fn sorted_projects(&self) -> Vec<&FullProject> {
+        let mut projects: Vec<&FullProject> = self
+            .projects
+            .values()
+            .filter(|project| {
+                self.branches
+                    .iter()
+                    .any(|branch| Self::project_file(branch, project).is_some())
+            })
+            .collect();
+        projects.sort_by(|a, b| {
+            a.project
+                .title
+                .to_lowercase()
+                .cmp(&b.project.title.to_lowercase())
+                .then_with(|| a.project.id.cmp(&b.project.id))
+        });
+        projects
+    }
+
+    fn project_file<'a>(branch: &'a SiteBranch, project: &FullProject) -> Option<&'a File> {
+        branch
+            .files
+            .files
+            .iter()
+            .find(|file| file.modrinth_project_id() == Some(project.project.id.as_str()))
+    }
+
+    fn mrpack_file(branch: &SiteBranch) -> Option<(&Path, String)> {
+        let mrpack_path = branch.mrpack_path.as_deref()?;
+        let file_name = mrpack_path.file_name()?.to_string_lossy().to_string();
+        Some((mrpack_path, file_name))
+    }
+
+    fn loader(branch_config: &BranchConfig) -> String {
+        match (&branch_config.mod_loader, &branch_config.loader_version) {
+            (Some(mod_loader), Some(loader_version)) => {
+                format!("{} {loader_version}", mod_loader.pretty_value())
+            }
+            (Some(mod_loader), None) => mod_loader.pretty_value().to_string(),
+            (None, _) => "Vanilla".to_string(),
+        }
+    }
+}
+
+/// Gives every key a unique page name based on the slug of its name. Page names are also
+/// unique ignoring case, so they don't overwrite each other on case-insensitive file systems.
+/// If a page name is already used, `-2`, `-3` and so on is appended to the slug.
+fn unique_page_names<'a>(
+    names: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> HashMap<String, String> {
+    let mut used = HashSet::new();
+    let mut page_names = HashMap::new();
+    for (key, name) in names {
+        let slug = page_slug(name);
+        let mut page_name = format!("{slug}.html");
+        let mut number = 2;
+        while !used.insert(page_name.to_lowercase()) {
+            page_name = format!("{slug}-{number}.html");
+            number += 1;
+        }
+        page_names.insert(key.to_string(), page_name);
+    }
+    page_names
+}
+
+/// Replaces characters that can't be used in a file name of a page by `-`.
+fn page_slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Escapes text so it can be used in HTML text and attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The characters that are percent-encoded in relative links. These are all characters
+/// except the unreserved characters of URLs.
+const URL_PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'-')
+    .remove(b'~');
+
+/// Percent-encodes a file name so it can be used in a relative link.
+fn url_path(file_name: &str) -> String {
+    utf8_percent_encode(file_name, URL_PATH_ENCODE_SET).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{file, sodium};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_doc_site() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        modpack.name = "Test pack".to_string();
+        modpack.summary = "Fast & <small>".to_string();
+        for branch in ["1.21.8", "1.20.1"] {
+            let branch_config = modpack.new_branch(branch).unwrap();
+            branch_config.save(directory, branch).unwrap();
+        }
+
+        let mut branch_files = BranchFiles::from_directory(directory, "1.21.8").unwrap();
+        branch_files.files = vec![
+            file(
+                "mods/sodium-0.6.13.jar",
+                "",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/u1OmRkrw/sodium-0.6.13.jar",
+            ),
+            file(
+                "mods/manual.jar",
+                "",
+                "https://github.com/example/manual.jar",
+            ),
+        ];
+        branch_files.save(directory, "1.21.8").unwrap();
+        let branch_config = BranchConfig::from_directory(directory, "1.21.8").unwrap();
+        let mrpack_path = modpack.exported_mrpack_path("1.21.8", &branch_config);
+        fs::create_dir_all(mrpack_path.parent().unwrap()).unwrap();
+        fs::write(&mrpack_path, "mrpack").unwrap();
+
+        let projects = HashMap::from([("AANobbMI".to_string(), sodium())]);
+
+        let doc_site = DocSite::collect(&modpack, projects).unwrap();
+        assert!(doc_site.request_errors.is_empty());
+        assert_eq!(
+            vec![Some(mrpack_path), None],
+            doc_site
+                .branches
+                .iter()
+                .map(|branch| branch.mrpack_path.clone())
+                .collect::<Vec<_>>()
+        );
+
+        let out = directory.join("site");
+        let written = doc_site.write(&out).unwrap();
+        assert_eq!(
+            vec![
+                out.join("style.css"),
+                out.join("index.html"),
+                out.join("branches/1.21.8.html"),
+                out.join("branches/1.20.1.html"),
+                out.join("projects/sodium.html"),
+                out.join("downloads/Test pack_1.0.0-fabric.mrpack"),
+            ],
+            written
+        );
+
+        let index = fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains("<p>Fast &amp; &lt;small&gt;</p>"));
+        assert!(index.contains(
+            "<tr><td><a href=\"projects/sodium.html\">Sodium</a></td><td>✅</td><td>❌</td></tr>"
+        ));
+
+        let branch_page = fs::read_to_string(out.join("branches/1.21.8.html")).unwrap();
+        assert!(branch_page.contains(
+            "<dt>Download</dt><dd><a href=\"../downloads/Test%20pack_1.0.0-fabric.mrpack\">Test pack_1.0.0-fabric.mrpack</a></dd>"
+        ));
+        assert!(
+            branch_page.contains("<tr><td>manual.jar</td><td><code>manual.jar</code></td></tr>")
+        );
+        let other_branch_page = fs::read_to_string(out.join("branches/1.20.1.html")).unwrap();
+        assert!(other_branch_page.contains("This branch hasn't been exported yet."));
+
+        let project_page = fs::read_to_string(out.join("projects/sodium.html")).unwrap();
+        assert!(project_page.contains(
+            "<img class=\"icon\" src=\"https://cdn.modrinth.com/data/AANobbMI/icon.png\" alt=\"\">"
+        ));
+        assert!(
+            project_page
+                .contains("<dt>License</dt><dd>LicenseRef-Polyform-Shield-License-1.0.0</dd>")
+        );
+        assert!(project_page.contains(
+            "<tr><td><a href=\"../branches/1.21.8.html\">1.21.8</a></td><td><code>sodium-0.6.13.jar</code></td></tr>"
+        ));
+        assert!(project_page.contains(
+            "<tr><td><a href=\"../branches/1.20.1.html\">1.20.1</a></td><td>Not included</td></tr>"
+        ));
+    }
+
+    #[test]
+    fn page_names_are_unique() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        for branch in ["fabric-1.21", "fabric 1.21", "Fabric-1.21"] {
+            let branch_config = modpack.new_branch(branch).unwrap();
+            branch_config.save(directory, branch).unwrap();
+        }
+
+        let doc_site = DocSite::collect(&modpack, HashMap::new()).unwrap();
+        let pages = doc_site.pages();
+        assert_eq!(
+            vec![
+                PathBuf::from("index.html"),
+                PathBuf::from("branches/fabric-1.21.html"),
+                PathBuf::from("branches/fabric-1.21-2.html"),
+                PathBuf::from("branches/Fabric-1.21-3.html"),
+            ],
+            pages
+                .iter()
+                .map(|page| page.path.clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            pages[0]
+                .html
+                .contains("<a href=\"branches/fabric-1.21-2.html\">fabric 1.21</a>")
+        );
+    }
+
+    #[test]
+    fn project_cache() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let modpack = Modpack::new(temp_dir.path(), false).unwrap();
+        assert!(DocSite::read_project_cache(&modpack).is_empty());
+
+        let mut doc_site = DocSite::collect(&modpack, HashMap::new()).unwrap();
+        doc_site.projects.insert("AANobbMI".to_string(), sodium());
+        doc_site.save_project_cache(&modpack).unwrap();
+        assert_eq!(doc_site.projects, DocSite::read_project_cache(&modpack));
+
+        fs::write(
+            temp_dir
+                .path()
+                .join(crate::TARGET_DIRECTORY)
+                .join(PROJECT_CACHE_FILE_NAME),
+            "invalid",
+        )
+        .unwrap();
+        assert!(DocSite::read_project_cache(&modpack).is_empty());
+    }
+}
@@ -1,16 +1,32 @@
-use crate::{Cli, print_error, print_success, single_line_error};
+use crate::{
+    Cli, print_error, print_error_message, print_success, print_warning, single_line_error,
+};
 use clap::CommandFactory;
 use clap_complete::{Generator, shells};
-use dialoguer::Confirm;
-use indexmap::IndexMap;
+use dialoguer::{Confirm, MultiSelect};
+use packrinth::changelog::{self, BranchChangelog, ProjectUpdate};
 use packrinth::config::{
-    BranchConfig, BranchFiles, BranchFilesProject, IncludeOrExclude, Modpack, ProjectSettings,
+    BRANCH_CONFIG_FILE_NAME, BRANCH_FILES_FILE_NAME, BranchConfig, BranchFiles, BranchFilesProject,
+    BranchSelector, IncludeOrExclude, MainLoader, Modpack, ProjectSettings, VersionBump,
+};
+use packrinth::licenses::{BranchLicenses, ContentSource, LicenseKind};
+use packrinth::loader_meta::LoaderMetaUrls;
+use packrinth::modrinth::{
+    BranchSupport, Env, FullProject, FullVersion, GameVersions, MODRINTH_TOKEN_ENV_VAR, MrPack,
+    NewVersion, SearchResults, SideSupport, VersionDependency, VersionDependencyType, VersionType,
+};
+use packrinth::schema::ConfigSchema;
+use packrinth::site::DocSite;
+use packrinth::validation::{Severity, ValidatedFile, ValidationIssue};
+use packrinth::{
+    GitUtils, PackrinthError, ProjectAvailability, ProjectTable, ProjectTableColumn,
+    ProjectUpdateResult, ProjectUpdater, config, manual, migration, validation,
 };
-use packrinth::modrinth::{MrPack, VersionDependency, VersionDependencyType};
-use packrinth::{GitUtils, PackrinthError, ProjectUpdateResult, ProjectUpdater, config};
 use progress_bar::pb::ProgressBar;
 use progress_bar::{Color, Style};
-use std::path::Path;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 use std::{cmp, fs, io};
 
 // Allow because we need all of them
@@ -31,6 +47,146 @@ fn create_progress_bar(max: usize) -> ProgressBar {
     progress_bar
 }
 
+/// Prints validation issues and returns the number of errors.
+fn print_validation_issues(issues: &[ValidationIssue]) -> usize {
+    let mut errors = 0;
+    for issue in issues {
+        let message = format!("{}: {}", issue.file, issue.message);
+        match issue.severity {
+            Severity::Warning => print_warning(message),
+            Severity::Error => {
+                errors += 1;
+                print_error_message(message);
+            }
+        }
+    }
+    errors
+}
+
+/// Requests the Minecraft versions from Modrinth. If that fails, a warning is printed and
+/// [`None`] is returned, so that Minecraft versions are compared by their names instead.
+fn optional_game_versions() -> Option<GameVersions> {
+    match GameVersions::from_modrinth() {
+        Ok(game_versions) => Some(game_versions),
+        Err(error) => {
+            print_warning(format!(
+                "Minecraft versions are compared by their names, because {}",
+                single_line_error(error.message_and_tip())
+            ));
+            None
+        }
+    }
+}
+
+/// Reads the configuration of a branch that has to be declared in the modpack.
+fn declared_branch_config(modpack: &Modpack, branch: &str) -> Result<BranchConfig, PackrinthError> {
+    if !modpack.branches.iter().any(|x| x == branch) {
+        return Err(PackrinthError::BranchDoesNotExist {
+            branch: branch.to_string(),
+            error_message: "it isn't declared in the modpack config file".to_string(),
+        });
+    }
+    BranchConfig::from_directory(&modpack.directory, branch)
+}
+
+fn display_paths(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect()
+}
+
+/// Fails if the modpack has uncommitted changes, which can be allowed with `--allow-dirty`.
+fn ensure_clean(modpack: &Modpack) -> Result<(), PackrinthError> {
+    let dirty_files = GitUtils::modpack_dirty_files(modpack)?;
+    if dirty_files.is_empty() {
+        Ok(())
+    } else {
+        Err(PackrinthError::RepoIsDirty {
+            files: display_paths(&dirty_files),
+            can_allow_dirty: true,
+        })
+    }
+}
+
+/// Fails if the modpack isn't in a Git repository or has uncommitted changes,
+/// so a subcommand can commit its own changes afterward.
+///
+/// The files that aren't tracked by Git yet are returned, so they can be left out of the commit.
+fn ensure_can_commit(modpack: &Modpack) -> Result<Vec<PathBuf>, PackrinthError> {
+    if !GitUtils::is_repository(&modpack.directory) {
+        return Err(PackrinthError::FailedToOpenGitRepo {
+            path: modpack.directory.display().to_string(),
+            error_message: "the modpack isn't in a Git repository".to_string(),
+        });
+    }
+    let dirty_files = GitUtils::modpack_dirty_files(modpack)?;
+    if dirty_files.is_empty() {
+        GitUtils::modpack_changed_files(modpack)
+    } else {
+        Err(PackrinthError::RepoIsDirty {
+            files: display_paths(&dirty_files),
+            can_allow_dirty: false,
+        })
+    }
+}
+
+/// Commits all changes in the modpack directory, including new files,
+/// except for files that were already untracked before.
+fn commit_modpack_changes(
+    modpack: &Modpack,
+    message: &str,
+    untracked_before: &[PathBuf],
+) -> Result<(), PackrinthError> {
+    let mut changed_files = GitUtils::modpack_changed_files(modpack)?;
+    changed_files.retain(|file| !untracked_before.contains(file));
+    if changed_files.is_empty() {
+        print_warning("there are no changes to commit");
+        return Ok(());
+    }
+
+    let paths: Vec<PathBuf> = changed_files
+        .iter()
+        .map(|file| modpack.directory.join(file))
+        .collect();
+    let commit_id = GitUtils::commit_files(&paths, message)?;
+    print_success(format!(
+        "committed {} file(s) as {}",
+        changed_files.len(),
+        commit_id.get(..7).unwrap_or(&commit_id)
+    ));
+    Ok(())
+}
+
+/// Validates the modpack before running another subcommand for some branches.
+///
+/// Only issues in the modpack configuration and in the given branches are reported,
+/// and only if `is_relevant` returns `true` for the file they were found in.
+fn validate_before(
+    modpack: &Modpack,
+    branches: &[String],
+    is_relevant: impl Fn(&ValidatedFile) -> bool,
+) -> Result<(), PackrinthError> {
+    let issues: Vec<ValidationIssue> = validation::validate_modpack(modpack)
+        .into_iter()
+        .filter(|issue| {
+            issue
+                .branch()
+                .is_none_or(|branch| branches.iter().any(|b| b == branch))
+                && is_relevant(&issue.file)
+        })
+        .collect();
+
+    let errors = print_validation_issues(&issues);
+    if !issues.is_empty() {
+        eprintln!();
+    }
+    if errors > 0 {
+        return Err(PackrinthError::ModpackIsInvalid { errors });
+    }
+    Ok(())
+}
+
 impl Cli {
     pub fn run(&mut self) {
         if let Err(error) = self.subcommand.run(&self.config_args) {
@@ -45,6 +201,8 @@ impl SubCommand {
             return args.run(config_args);
         } else if let SubCommand::Completions(args) = self {
             return args.run(config_args);
+        } else if let SubCommand::Schema(args) = self {
+            return args.run(config_args);
         }
 
         let mut current_dir = match config_args.directory.clone() {
@@ -63,9 +221,11 @@ impl SubCommand {
             return args.run(&current_dir, config_args);
         }
 
-        let mut modpack = loop {
-            match Modpack::from_directory(&current_dir) {
-                Ok(modpack) => break modpack,
+        // Only the pack format is read first, because older modpacks
+        // don't have to be valid for the current configuration structs.
+        let pack_format = loop {
+            match migration::read_pack_format(&current_dir) {
+                Ok(pack_format) => break pack_format,
                 Err(error) => {
                     if let PackrinthError::FailedToReadToString { path_to_read, .. } = &error
                         && path_to_read.contains(config::MODPACK_CONFIG_FILE_NAME)
@@ -79,18 +239,26 @@ impl SubCommand {
             }
         };
 
-        if modpack.pack_format != config::CURRENT_PACK_FORMAT {
-            return Err(PackrinthError::InvalidPackFormat {
-                used_pack_format: modpack.pack_format,
-            });
+        if let Self::Migrate(args) = self {
+            return args.run(&current_dir, config_args);
         }
+        migration::check_pack_format(pack_format)?;
+        let mut modpack = Modpack::from_directory(&current_dir)?;
 
         match self {
             SubCommand::Import(args) => args.run(&mut modpack, config_args),
             SubCommand::Project(args) => args.run(&mut modpack, config_args),
+            SubCommand::Search(args) => args.run(&mut modpack, config_args),
             SubCommand::Branch(args) => args.run(&mut modpack, config_args),
+            SubCommand::Manual(args) => args.run(&modpack, config_args),
             SubCommand::Update(args) => args.run(&modpack, config_args),
             SubCommand::Export(args) => args.run(&modpack, config_args),
+            SubCommand::Changelog(args) => args.run(&modpack, config_args),
+            SubCommand::Release(args) => args.run(&modpack, config_args),
+            SubCommand::Publish(args) => args.run(&modpack, config_args),
+            SubCommand::Check(args) => args.run(&modpack, config_args),
+            SubCommand::Validate(args) => args.run(&modpack, config_args),
+            SubCommand::Licenses(args) => args.run(&modpack, config_args),
             SubCommand::Clean(args) => args.run(&modpack, config_args),
             SubCommand::Doc(args) => args.run(&modpack, config_args),
             _ => Ok(()), // These cases should have been handled before this match statement.
@@ -130,9 +298,14 @@ impl ImportArgs {
         modpack: &mut Modpack,
         config_args: &ConfigArgs,
     ) -> Result<(), PackrinthError> {
-        if self.add_projects && !self.allow_dirty && GitUtils::modpack_is_dirty(modpack) {
-            return Err(PackrinthError::RepoIsDirty);
-        }
+        let untracked_before = if self.commit {
+            ensure_can_commit(modpack)?
+        } else {
+            if self.add_projects && !self.allow_dirty {
+                ensure_clean(modpack)?;
+            }
+            Vec::new()
+        };
 
         let mrpack = MrPack::from_mrpack(&self.modrinth_pack)?;
         let mut progress_bar = create_progress_bar(mrpack.files.len());
@@ -158,6 +331,14 @@ impl ImportArgs {
             Color::Green,
             Style::Bold,
         );
+
+        if self.commit {
+            let file_name = self.modrinth_pack.file_name().map_or_else(
+                || self.modrinth_pack.display().to_string(),
+                |file_name| file_name.display().to_string(),
+            );
+            commit_modpack_changes(modpack, &format!("Import {file_name}"), &untracked_before)?;
+        }
         Ok(())
     }
 }
@@ -171,45 +352,59 @@ impl ProjectArgs {
         if let Some(command) = &self.command {
             match command {
                 ProjectSubCommand::List(args) => args.run(modpack, config_args),
+                ProjectSubCommand::Info(args) => args.run(modpack, config_args),
                 ProjectSubCommand::Add(args) => args.run(modpack, config_args),
                 ProjectSubCommand::VersionOverride(args) => args.run(modpack, config_args),
                 ProjectSubCommand::Inclusions(args) => args.run(modpack, config_args),
                 ProjectSubCommand::Exclusions(args) => args.run(modpack, config_args),
+                ProjectSubCommand::Group(args) => args.run(modpack, config_args),
                 ProjectSubCommand::Remove(args) => args.run(modpack, config_args),
             }
         } else if let Some(project_names) = &self.projects {
             modpack
                 .projects
                 .retain(|key, _| project_names.contains(key));
-            ListProjectsArgs::list(&modpack.projects)
+            ListProjectsArgs::list(modpack)
         } else {
-            ListProjectsArgs::run(&ListProjectsArgs {}, modpack, config_args)
+            ListProjectsArgs::run(&ListProjectsArgs { group: None }, modpack, config_args)
         }
     }
 }
 
 impl ListProjectsArgs {
-    // Allow unused self, because then it is clear to the maintainer that self is available for code expansion.
-    #[allow(clippy::unused_self)]
     pub fn run(
         &self,
         modpack: &mut Modpack,
         _config_args: &ConfigArgs,
     ) -> Result<(), PackrinthError> {
-        Self::list(&modpack.projects)
+        if let Some(group) = &self.group {
+            if !modpack.groups.contains_key(group) {
+                return Err(PackrinthError::GroupDoesNotExist {
+                    group: group.clone(),
+                });
+            }
+            modpack
+                .projects
+                .retain(|_, project_settings| project_settings.groups.contains(group));
+        }
+        Self::list(modpack)
     }
 
-    // Allowing unnecessary return because it makes it clear that using ? in the function is ok.
-    #[allow(clippy::unnecessary_wraps)]
-    pub fn list(projects: &IndexMap<String, ProjectSettings>) -> Result<(), PackrinthError> {
-        if projects.is_empty() {
+    pub fn list(modpack: &Modpack) -> Result<(), PackrinthError> {
+        if modpack.projects.is_empty() {
             println!("There are no projects added to this modpack yet.");
             return Ok(());
         }
 
-        for project in projects {
+        // The Minecraft versions are only requested when a rule has to be shown.
+        let mut game_versions: Option<Option<GameVersions>> = None;
+        for project in &modpack.projects {
             println!("{}", project.0);
 
+            if !project.1.groups.is_empty() {
+                println!("  - Groups: {}", project.1.groups.join(", "));
+            }
+
             if let Some(overrides) = &project.1.version_overrides {
                 println!("  - Overrides:");
                 for version_override in overrides {
@@ -218,12 +413,26 @@ impl ListProjectsArgs {
             }
 
             if let Some(include_or_exclude) = &project.1.include_or_exclude {
-                match include_or_exclude {
+                let selectors = match include_or_exclude {
                     IncludeOrExclude::Include(inclusions) => {
                         println!("  - Inclusions: {}", inclusions.join(", "));
+                        inclusions
                     }
                     IncludeOrExclude::Exclude(exclusions) => {
                         println!("  - Exclusions: {}", exclusions.join(", "));
+                        exclusions
+                    }
+                };
+
+                // Show which branches the rules select, because that isn't visible
+                // from the rule itself.
+                for selector in selectors {
+                    if let Ok(BranchSelector::Rule(_)) = BranchSelector::parse(selector) {
+                        let game_versions =
+                            game_versions.get_or_insert_with(optional_game_versions);
+                        let selected_branches =
+                            modpack.branches_selected_by(selector, game_versions.as_ref())?;
+                        println!("    - {selector}: {}", selected_branches.join(", "));
                     }
                 }
             }
@@ -233,6 +442,115 @@ impl ListProjectsArgs {
     }
 }
 
+impl ProjectInfoArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        let mut branch_files = Vec::new();
+        for branch in branches {
+            declared_branch_config(modpack, branch)?;
+            branch_files.push((
+                branch,
+                BranchFiles::from_directory(&modpack.directory, branch),
+            ));
+        }
+
+        let mut iter = self.projects.iter().peekable();
+        while let Some(project) = iter.next() {
+            let full_project = FullProject::from_id(project)?;
+            Self::print_project(&full_project);
+
+            let mut local_modpack = modpack.clone();
+            local_modpack.projects.retain(|key, _| {
+                key == project
+                    || *key == full_project.project.slug
+                    || *key == full_project.project.id
+            });
+            println!("Modpack settings:");
+            if local_modpack.projects.is_empty() {
+                println!("  - Not added to this modpack");
+            } else {
+                ListProjectsArgs::list(&local_modpack)?;
+            }
+
+            println!("Files:");
+            for (branch, branch_files) in &branch_files {
+                let branch_files = match branch_files {
+                    Ok(branch_files) => branch_files,
+                    Err(error) => {
+                        println!(
+                            "  - {branch}: {}",
+                            single_line_error(error.message_and_tip())
+                        );
+                        continue;
+                    }
+                };
+                let Some(file) = branch_files.files.iter().find(|file| {
+                    file.modrinth_project_id() == Some(full_project.project.id.as_str())
+                }) else {
+                    println!("  - {branch}: not in branch");
+                    continue;
+                };
+                match FullVersion::from_sha512_hash(&file.hashes.sha512) {
+                    Ok(version) => println!(
+                        "  - {branch}: {} ({} {}, published {})",
+                        file.path,
+                        version.version_number,
+                        version.version.version_type.modrinth_value(),
+                        version.date_published
+                    ),
+                    Err(error) => println!(
+                        "  - {branch}: {} ({})",
+                        file.path,
+                        single_line_error(error.message_and_tip())
+                    ),
+                }
+            }
+
+            if iter.peek().is_some() {
+                println!();
+            }
+        }
+        Ok(())
+    }
+
+    fn print_project(full_project: &FullProject) {
+        let project = &full_project.project;
+        println!("{} ({}, {})", project.title, project.slug, project.id);
+        println!("  {}", full_project.description);
+        println!("  - Type: {}", project.project_type.modrinth_value());
+        println!("  - Status: {}", full_project.status);
+        println!("  - License: {}", full_project.license.label());
+        if !full_project.categories.is_empty() {
+            println!("  - Categories: {}", full_project.categories.join(", "));
+        }
+        if !project.loaders.is_empty() {
+            println!("  - Loaders: {}", project.loaders.join(", "));
+        }
+        println!(
+            "  - Client side: {}, server side: {}",
+            project.client_side.modrinth_value(),
+            project.server_side.modrinth_value()
+        );
+        println!(
+            "  - Downloads: {}, followers: {}",
+            full_project.downloads, full_project.followers
+        );
+        println!(
+            "  - Published: {}, updated: {}",
+            full_project.published, full_project.updated
+        );
+        for (name, url) in [
+            ("Source", &full_project.source_url),
+            ("Issues", &full_project.issues_url),
+            ("Wiki", &full_project.wiki_url),
+        ] {
+            if let Some(url) = url {
+                println!("  - {name}: {url}");
+            }
+        }
+    }
+}
+
 impl AddProjectsArgs {
     pub fn run(
         &self,
@@ -245,10 +563,55 @@ impl AddProjectsArgs {
             self.exclusions.clone().map(IncludeOrExclude::Exclude)
         };
 
-        modpack.add_projects(&self.projects, &None, &include_or_exclude);
+        let mut added_projects = Vec::new();
+        for project in &self.projects {
+            match modpack.resolve_new_project(project) {
+                Ok(modrinth_project) => {
+                    modpack.add_projects(
+                        std::slice::from_ref(&modrinth_project.slug),
+                        &None,
+                        &include_or_exclude,
+                        self.groups.as_deref().unwrap_or_default(),
+                    );
+                    added_projects.push(modrinth_project);
+                }
+                // Don't use ? because then we can't try again for the next project.
+                Err(error) => print_error(error.message_and_tip()),
+            }
+        }
+        if added_projects.is_empty() {
+            return Ok(());
+        }
         modpack.save()?;
 
-        print_success(format!("added {}", self.projects.join(", ")));
+        let mut branch_configs = Vec::new();
+        for branch in &modpack.branches {
+            match BranchConfig::from_directory(&modpack.directory, branch) {
+                Ok(branch_config) => branch_configs.push((branch, branch_config)),
+                Err(error) => print_error(error.message_and_tip()),
+            }
+        }
+        for project in &added_projects {
+            print_success(format!(
+                "added {} ({}) for {}",
+                project.title,
+                project.slug,
+                project.loaders.join(", ")
+            ));
+            for (branch, branch_config) in &branch_configs {
+                match project.branch_support(branch_config) {
+                    BranchSupport::Supported => println!("  - {branch}: supported"),
+                    BranchSupport::UnsupportedMinecraftVersion => println!(
+                        "  - {branch}: no versions for Minecraft {}",
+                        branch_config.game_versions().join(", ")
+                    ),
+                    BranchSupport::UnsupportedLoader => println!(
+                        "  - {branch}: no versions for {}",
+                        branch_config.modrinth_loaders().join(", ")
+                    ),
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -425,6 +788,55 @@ impl RemoveExclusionsArgs {
     }
 }
 
+impl GroupProjectArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        match &self.command {
+            GroupSubCommand::Add(args) => args.run(modpack, config_args),
+            GroupSubCommand::Remove(args) => args.run(modpack, config_args),
+        }
+    }
+}
+
+impl AddGroupsArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        modpack.add_project_to_groups(&self.project, &self.groups)?;
+        modpack.save()?;
+
+        print_success(format!(
+            "added {} to groups {}",
+            self.project,
+            self.groups.join(", ")
+        ));
+        Ok(())
+    }
+}
+
+impl RemoveGroupsArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        modpack.remove_project_from_groups(&self.project, &self.groups)?;
+        modpack.save()?;
+
+        print_success(format!(
+            "removed {} from groups {}",
+            self.project,
+            self.groups.join(", ")
+        ));
+        Ok(())
+    }
+}
+
 impl RemoveProjectsArgs {
     pub fn run(
         &self,
@@ -447,9 +859,14 @@ impl RemoveProjectsArgs {
 
 impl UpdateArgs {
     pub fn run(&self, modpack: &Modpack, config_args: &ConfigArgs) -> Result<(), PackrinthError> {
-        if !self.allow_dirty && GitUtils::modpack_is_dirty(modpack) {
-            return Err(PackrinthError::RepoIsDirty);
-        }
+        let untracked_before = if self.commit || self.per_project_commits {
+            ensure_can_commit(modpack)?
+        } else {
+            if !self.allow_dirty {
+                ensure_clean(modpack)?;
+            }
+            Vec::new()
+        };
         if modpack.branches.is_empty() {
             return Err(PackrinthError::ModpackHasNoBranchesToUpdate);
         }
@@ -459,6 +876,12 @@ impl UpdateArgs {
         } else {
             &modpack.branches
         };
+        if !self.no_validate {
+            // The branch files get replaced while updating, so problems in them don't matter.
+            validate_before(modpack, branches, |file| {
+                !matches!(file, ValidatedFile::BranchFiles(_))
+            })?;
+        }
 
         self.update_branches(
             modpack,
@@ -466,7 +889,151 @@ impl UpdateArgs {
             self.require_all || modpack.require_all,
             self.auto_dependencies || modpack.auto_dependencies,
             config_args.verbose,
-        )
+        )?;
+
+        if self.per_project_commits {
+            Self::commit_per_project(modpack, branches)?;
+        } else if self.commit {
+            // Branches without committed branch files can't be compared, so they aren't summarized.
+            let changelogs: Vec<BranchChangelog> = branches
+                .iter()
+                .filter_map(|branch| BranchChangelog::from_git(modpack, branch, "HEAD").ok())
+                .collect();
+            let subject = format!("Update {}", branches.join(", "));
+            commit_modpack_changes(
+                modpack,
+                &changelog::commit_message(&subject, &changelogs),
+                &untracked_before,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Commits the change of every project in the updated branch files to its own Git branch,
+    /// writes a summary of it to the target directory and restores the committed branch files.
+    fn commit_per_project(modpack: &Modpack, branches: &[String]) -> Result<(), PackrinthError> {
+        let mut updated_branches = Vec::new();
+        let mut changelogs = Vec::new();
+        for branch in branches {
+            let branch_files_path = modpack.directory.join(branch).join(BRANCH_FILES_FILE_NAME);
+            let Some(committed_json) = GitUtils::read_file_at_revision(&branch_files_path, "HEAD")?
+            else {
+                print_warning(format!(
+                    "branch {branch} has no committed branch files, so its changes aren't committed per project"
+                ));
+                continue;
+            };
+            let old = changelog::parse_branch_files_at_revision(
+                &branch_files_path,
+                "HEAD",
+                &committed_json,
+            )?;
+            let new = BranchFiles::from_directory(&modpack.directory, branch)?;
+            changelogs.push(BranchChangelog::between(branch, "HEAD", &old, &new));
+            updated_branches.push((branch, branch_files_path, committed_json, old, new));
+        }
+
+        let updates = ProjectUpdate::from_changelogs(&changelogs);
+        let result = Self::commit_updates(modpack, &updates, &updated_branches);
+
+        // The updates are only in their own Git branches, so the current branch stays clean.
+        // Restore the branch files even if committing failed, so no update is half applied.
+        for (_branch, branch_files_path, committed_json, _old, _new) in &updated_branches {
+            if let Err(error) = fs::write(branch_files_path, committed_json) {
+                return Err(PackrinthError::FailedToWriteFile {
+                    path_to_write_to: branch_files_path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+        }
+        result
+    }
+
+    fn commit_updates(
+        modpack: &Modpack,
+        updates: &[ProjectUpdate],
+        updated_branches: &[(&String, PathBuf, String, BranchFiles, BranchFiles)],
+    ) -> Result<(), PackrinthError> {
+        // Check the Git branches first, so an update doesn't replace the commit of another.
+        let mut git_branches = Vec::new();
+        for update in updates {
+            let git_branch = update.git_branch();
+            if git_branches.contains(&git_branch) {
+                return Err(PackrinthError::FailedToCommit {
+                    error_message: format!(
+                        "more than one project would be committed to Git branch {git_branch}"
+                    ),
+                });
+            }
+            git_branches.push(git_branch);
+        }
+
+        let summary_dir = modpack
+            .directory
+            .join(packrinth::TARGET_DIRECTORY)
+            .join("updates");
+        if let Err(error) = fs::create_dir_all(&summary_dir) {
+            return Err(PackrinthError::FailedToCreateDir {
+                dir_to_create: summary_dir.display().to_string(),
+                error_message: error.to_string(),
+            });
+        }
+        for (update, git_branch) in updates.iter().zip(&git_branches) {
+            let mut files = Vec::new();
+            for (branch, branch_files_path, _committed_json, old, new) in updated_branches {
+                if update.changes.iter().any(|(b, _change)| b == *branch) {
+                    let json = update.apply(branch, old, new).to_json()?;
+                    files.push((branch_files_path.clone(), json.into_bytes()));
+                }
+            }
+            let commit_id =
+                GitUtils::commit_to_branch(git_branch, &files, &update.commit_message())?;
+
+            let summary_path = summary_dir.join(format!("{}.md", git_branch.replace('/', "-")));
+            if let Err(error) = fs::write(&summary_path, update.to_markdown()) {
+                return Err(PackrinthError::FailedToWriteFile {
+                    path_to_write_to: summary_path.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+            print_success(format!(
+                "committed update of {} to {git_branch} as {}",
+                update.name,
+                commit_id.get(..7).unwrap_or(&commit_id)
+            ));
+        }
+
+        if updates.is_empty() {
+            print_warning("no projects have changed");
+        } else {
+            print_success(format!(
+                "wrote {} update summaries to {}",
+                updates.len(),
+                summary_dir.display()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads the branch files of a branch without any projects and files, creating them
+    /// if they don't exist yet.
+    fn cleared_branch_files(
+        modpack: &Modpack,
+        branch_name: &str,
+    ) -> Result<BranchFiles, PackrinthError> {
+        let mut branch_files = match BranchFiles::from_directory(&modpack.directory, branch_name) {
+            Ok(branch_files) => branch_files,
+            Err(_error) => {
+                let default_branch_files = BranchFiles::default();
+                default_branch_files.save(&modpack.directory, branch_name)?;
+                default_branch_files
+            }
+        };
+
+        // Remove all entries to ensure that there will be no duplicates if the user changes loaders
+        branch_files.projects = Vec::new();
+        branch_files.files = Vec::new();
+        Ok(branch_files)
     }
 
     fn update_branches(
@@ -477,38 +1044,39 @@ impl UpdateArgs {
         auto_dependencies: bool,
         verbose: bool,
     ) -> Result<(), PackrinthError> {
+        let game_versions = optional_game_versions();
         let mut progress_bar = create_progress_bar(modpack.projects.len() * branches.len());
 
         for branch_name in branches {
             progress_bar.set_action(branch_name, Color::Blue, Style::Bold);
 
-            let branch_config = BranchConfig::from_directory(&modpack.directory, branch_name)?;
-            let mut branch_files =
-                match BranchFiles::from_directory(&modpack.directory, branch_name) {
-                    Ok(branch_files) => branch_files,
-                    Err(_error) => {
-                        let default_branch_files = BranchFiles::default();
-                        default_branch_files.save(&modpack.directory, branch_name)?;
-                        default_branch_files
-                    }
-                };
-
-            // Remove all entries to ensure that there will be no duplicates if the user changes loaders
-            branch_files.projects = Vec::new();
-            branch_files.files = Vec::new();
-
+            let mut branch_config = BranchConfig::from_directory(&modpack.directory, branch_name)?;
+            if let Some(game_versions) = &game_versions {
+                branch_config.validate_minecraft_versions(game_versions)?;
+            }
+            self.update_loader(
+                modpack,
+                branch_name,
+                &mut branch_config,
+                &mut progress_bar,
+                verbose,
+            )?;
+            branch_config.resolve_datapack_loader()?;
+            let mut branch_files = Self::cleared_branch_files(modpack, branch_name)?;
             let mut dependencies: Vec<VersionDependency> = Vec::new();
 
             for (slug_project_id, project_settings) in &modpack.projects {
+                let project_settings = modpack.resolve_project_settings(project_settings)?;
                 let project_updater = ProjectUpdater {
                     branch_name,
                     branch_config: &branch_config,
                     branch_files: &mut branch_files,
                     slug_project_id,
-                    project_settings,
+                    project_settings: &project_settings,
                     require_all,
                     no_beta: self.no_beta,
                     no_alpha: self.no_alpha,
+                    game_versions: game_versions.as_ref(),
                 };
 
                 Self::update_project(
@@ -531,10 +1099,7 @@ impl UpdateArgs {
                             .iter()
                             .any(|project| project.id == Some(project_id.clone()))
                     {
-                        let project_settings = ProjectSettings {
-                            version_overrides: None,
-                            include_or_exclude: None,
-                        };
+                        let project_settings = ProjectSettings::default();
                         let project_updater = ProjectUpdater {
                             branch_name,
                             branch_config: &branch_config,
@@ -544,6 +1109,7 @@ impl UpdateArgs {
                             require_all,
                             no_beta: self.no_beta,
                             no_alpha: self.no_alpha,
+                            game_versions: game_versions.as_ref(),
                         };
 
                         // Create new vec because we don't care about the dependencies
@@ -589,6 +1155,37 @@ impl UpdateArgs {
         Ok(())
     }
 
+    fn update_loader(
+        &self,
+        modpack: &Modpack,
+        branch_name: &str,
+        branch_config: &mut BranchConfig,
+        progress_bar: &mut ProgressBar,
+        verbose: bool,
+    ) -> Result<(), PackrinthError> {
+        if !self.loader {
+            return Ok(());
+        }
+
+        match branch_config.update_loader_version(&LoaderMetaUrls::from_env()) {
+            Ok(Some(loader_version)) => {
+                branch_config.save(&modpack.directory, branch_name)?;
+                if verbose {
+                    progress_bar.print_info("loader", &loader_version, Color::Green, Style::Normal);
+                }
+            }
+            Ok(None) => {}
+            Err(error) => progress_bar.print_info(
+                "failed",
+                &single_line_error(error.message_and_tip()),
+                Color::Red,
+                Style::Bold,
+            ),
+        }
+
+        Ok(())
+    }
+
     fn update_project(
         mut project_updater: ProjectUpdater,
         is_dependency: bool,
@@ -597,18 +1194,17 @@ impl UpdateArgs {
         verbose: bool,
     ) {
         match project_updater.update_project() {
-            ProjectUpdateResult::Added(new_dependencies) => {
+            ProjectUpdateResult::Added(new_dependencies, reason) => {
                 dependencies.extend(new_dependencies);
 
                 let info_text = if is_dependency { "dependency" } else { "added" };
 
                 if verbose {
-                    progress_bar.print_info(
-                        info_text,
-                        project_updater.slug_project_id,
-                        Color::Green,
-                        Style::Normal,
-                    );
+                    let message = match reason {
+                        Some(reason) => format!("{}: {reason}", project_updater.slug_project_id),
+                        None => project_updater.slug_project_id.to_string(),
+                    };
+                    progress_bar.print_info(info_text, &message, Color::Green, Style::Normal);
                 }
             }
             ProjectUpdateResult::Skipped => {
@@ -652,6 +1248,10 @@ impl BranchArgs {
                 BranchSubCommand::List(args) => args.run(modpack, config_args),
                 BranchSubCommand::Add(args) => args.run(modpack, config_args),
                 BranchSubCommand::Remove(args) => args.run(modpack, config_args),
+                BranchSubCommand::Rename(args) => args.run(modpack, config_args),
+                BranchSubCommand::Copy(args) => args.run(modpack, config_args),
+                BranchSubCommand::Port(args) => args.run(modpack, config_args),
+                BranchSubCommand::UpdateLoader(args) => args.run(modpack, config_args),
             }
         } else if let Some(branch_names) = &self.branches {
             ListBranchesArgs::list(&modpack.directory, branch_names)
@@ -714,7 +1314,11 @@ impl AddBranchesArgs {
         _config_args: &ConfigArgs,
     ) -> Result<(), PackrinthError> {
         for branch_name in &self.branches {
-            if let Err(error) = modpack.new_branch(branch_name) {
+            let result = match &self.extends {
+                Some(extends) => modpack.new_branch_extending(branch_name, extends),
+                None => modpack.new_branch(branch_name),
+            };
+            if let Err(error) = result {
                 // Don't use ? because then we can't try again for the next branch.
                 print_error(error.message_and_tip());
             }
@@ -745,6 +1349,9 @@ impl RemoveBranchesArgs {
         println!(
             "Please keep in mind that all the content of the branches will be removed, including overrides."
         );
+        println!(
+            "The branches will also be removed from the inclusions, exclusions and version overrides of projects."
+        );
         println!();
 
         let confirmation = Confirm::new()
@@ -767,32 +1374,965 @@ impl RemoveBranchesArgs {
     }
 }
 
-impl ExportArgs {
-    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
-        match &self.branches {
-            None => Self::export_branches(modpack, &modpack.branches),
-            Some(branches) => Self::export_branches(modpack, branches),
-        }
+impl RenameBranchArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        modpack.rename_branch(&self.branch, &self.new_branch)?;
+        modpack.save()?;
+
+        print_success(format!(
+            "renamed branch {} to {}",
+            self.branch, self.new_branch
+        ));
+        Ok(())
     }
+}
 
-    // Allow because it is required in Cli::run.
-    #[allow(clippy::unnecessary_wraps)]
-    fn export_branches(modpack: &Modpack, branches: &Vec<String>) -> Result<(), PackrinthError> {
-        for branch in branches {
-            match modpack.export_branch(branch) {
-                Ok(modpack_path) => {
-                    print_success(format!("exported {} to {}", branch, modpack_path.display()));
-                }
-                Err(error) => {
-                    // Don't use ? because then we can't try again for the next branch.
-                    print_error(error.message_and_tip());
+impl CopyBranchArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        modpack.copy_branch(&self.branch, &self.new_branch)?;
+        modpack.save()?;
+
+        print_success(format!(
+            "copied branch {} to {}",
+            self.branch, self.new_branch
+        ));
+        Ok(())
+    }
+}
+
+impl PortBranchArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        let game_versions = GameVersions::from_modrinth()?;
+        if game_versions.get(&self.minecraft).is_none() {
+            return Err(PackrinthError::UnknownMinecraftVersion {
+                version: self.minecraft.clone(),
+            });
+        }
+        let branch_config = modpack.port_branch(&self.branch, &self.new_branch, &self.minecraft)?;
+        modpack.save()?;
+
+        let mut progress_bar = create_progress_bar(modpack.projects.len());
+        progress_bar.set_action(&self.new_branch, Color::Blue, Style::Bold);
+
+        let mut release = Vec::new();
+        let mut beta = Vec::new();
+        let mut alpha = Vec::new();
+        let mut not_found = Vec::new();
+        let mut skipped = Vec::new();
+        for (slug_project_id, project_settings) in &modpack.projects {
+            let project_settings = modpack.resolve_project_settings(project_settings)?;
+            match ProjectAvailability::check(
+                &self.new_branch,
+                &branch_config,
+                slug_project_id,
+                &project_settings,
+                Some(&game_versions),
+            ) {
+                ProjectAvailability::Release => release.push(slug_project_id),
+                ProjectAvailability::Beta => beta.push(slug_project_id),
+                ProjectAvailability::Alpha => alpha.push(slug_project_id),
+                ProjectAvailability::NotFound => not_found.push(slug_project_id),
+                ProjectAvailability::Skipped => skipped.push(slug_project_id),
+                ProjectAvailability::Failed(error) => progress_bar.print_info(
+                    "failed",
+                    &single_line_error(error.message_and_tip()),
+                    Color::Red,
+                    Style::Bold,
+                ),
+            }
+            progress_bar.inc();
+        }
+
+        progress_bar.print_final_info(
+            "success:",
+            &format!(
+                "ported branch {} to {} for Minecraft {}",
+                self.branch, self.new_branch, self.minecraft
+            ),
+            Color::Green,
+            Style::Bold,
+        );
+        println!();
+
+        for (title, projects) in [
+            ("Available", release),
+            ("Only available as beta", beta),
+            ("Only available as alpha", alpha),
+            ("Missing", not_found),
+            ("Skipped because of inclusions or exclusions", skipped),
+        ] {
+            if projects.is_empty() {
+                continue;
+            }
+            println!("{title} ({}):", projects.len());
+            for project in projects {
+                println!("  - {project}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl UpdateLoaderArgs {
+    // Allow because it is required in Cli::run.
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        let loader_meta_urls = LoaderMetaUrls::from_env();
+
+        for branch in branches {
+            // Don't use ? because then we can't try again for the next branch.
+            let result = BranchConfig::from_directory(&modpack.directory, branch).and_then(
+                |mut branch_config| {
+                    let loader_version = branch_config.update_loader_version(&loader_meta_urls)?;
+                    if loader_version.is_some() {
+                        branch_config.save(&modpack.directory, branch)?;
+                    }
+                    Ok((branch_config.mod_loader, loader_version))
+                },
+            );
+            match result {
+                Ok((Some(mod_loader), Some(loader_version))) => print_success(format!(
+                    "set {} version of branch {branch} to {loader_version}",
+                    mod_loader.pretty_value()
+                )),
+                Ok(_) => println!("Branch {branch} doesn't have a main mod loader, skipping it"),
+                Err(error) => print_error(error.message_and_tip()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ManualArgs {
+    pub fn run(&self, modpack: &Modpack, config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        match &self.command {
+            ManualSubCommand::List(args) => args.run(modpack, config_args),
+            ManualSubCommand::Add(args) => args.run(modpack, config_args),
+            ManualSubCommand::Remove(args) => args.run(modpack, config_args),
+            ManualSubCommand::Refresh(args) => args.run(modpack, config_args),
+        }
+    }
+}
+
+impl ListManualFilesArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        for branch in branches {
+            let branch_config = declared_branch_config(modpack, branch)?;
+            println!("Branch {branch}:");
+            if branch_config.manual_files.is_empty() {
+                println!("  - No manual files are added");
+            }
+            for file in &branch_config.manual_files {
+                println!(
+                    "  - {} ({} bytes) from {}",
+                    file.path,
+                    file.file_size,
+                    file.downloads.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AddManualFileArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let mut branch_config = declared_branch_config(modpack, &self.branch)?;
+
+        let is_url = self.source.starts_with("https://") || self.source.starts_with("http://");
+        let (contents, download) = if is_url {
+            (manual::download(&self.source)?, self.source.clone())
+        } else {
+            let Some(url) = &self.url else {
+                return Err(PackrinthError::NoDownloadUrlSpecified {
+                    file: self.source.clone(),
+                });
+            };
+            let contents = match fs::read(&self.source) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    return Err(PackrinthError::FailedToReadToString {
+                        path_to_read: self.source.clone(),
+                        error_message: error.to_string(),
+                    });
+                }
+            };
+            (contents, url.clone())
+        };
+        let download = self.url.clone().unwrap_or(download);
+
+        let file_name = if is_url {
+            manual::file_name_from_url(&self.source)
+        } else {
+            Path::new(&self.source)
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+        }
+        .unwrap_or_else(|| self.source.clone());
+
+        let mut file = manual::manual_file(
+            &contents,
+            &file_name,
+            download,
+            self.path.clone(),
+            &branch_config,
+        )?;
+        if self.client.is_some() || self.server.is_some() {
+            let env = file.env.unwrap_or(Env {
+                client: SideSupport::Required,
+                server: SideSupport::Required,
+            });
+            file.env = Some(Env {
+                client: self.client.map_or(env.client, side_support),
+                server: self.server.map_or(env.server, side_support),
+            });
+        }
+
+        let path = file.path.clone();
+        let is_allowed_download = manual::is_allowed_download(&file.downloads[0]);
+        branch_config.add_manual_file(file, self.force)?;
+        branch_config.save(&modpack.directory, &self.branch)?;
+
+        if !is_allowed_download {
+            print_warning(format!(
+                "Modrinth only accepts downloads from {} in modpacks hosted on Modrinth",
+                manual::ALLOWED_DOWNLOAD_DOMAINS.join(", ")
+            ));
+        }
+        print_success(format!(
+            "added manual file {path} to branch {}",
+            self.branch
+        ));
+        Ok(())
+    }
+}
+
+const fn side_support(value: FileSideSupport) -> SideSupport {
+    match value {
+        FileSideSupport::Required => SideSupport::Required,
+        FileSideSupport::Optional => SideSupport::Optional,
+        FileSideSupport::Unsupported => SideSupport::Unsupported,
+    }
+}
+
+impl RemoveManualFilesArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let mut branch_config = declared_branch_config(modpack, &self.branch)?;
+        for file in &self.files {
+            let removed_file = branch_config.remove_manual_file(file)?;
+            print_success(format!(
+                "removed manual file {} from branch {}",
+                removed_file.path, self.branch
+            ));
+        }
+        branch_config.save(&modpack.directory, &self.branch)
+    }
+}
+
+impl RefreshManualFilesArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        for branch in branches {
+            let mut branch_config = declared_branch_config(modpack, branch)?;
+            let mut changed = false;
+            for file in &mut branch_config.manual_files {
+                // Don't use ? because then we can't try again for the next file.
+                match manual::refresh(file) {
+                    Ok(Some(refreshed_file)) => {
+                        print_warning(format!(
+                            "{} in branch {branch} changed upstream, its hashes and size were updated",
+                            file.path
+                        ));
+                        *file = refreshed_file;
+                        changed = true;
+                    }
+                    Ok(None) => println!("{} in branch {branch} is up to date", file.path),
+                    Err(error) => print_error(error.message_and_tip()),
+                }
+            }
+            if changed {
+                branch_config.save(&modpack.directory, branch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MigrateArgs {
+    // Allow unused self, because then it is clear to the maintainer that self is available for code expansion.
+    #[allow(clippy::unused_self)]
+    pub fn run(&self, directory: &Path, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let summary = migration::migrate(directory)?;
+        if summary.applied.is_empty() {
+            print_success(format!(
+                "modpack already uses pack format {}, nothing to migrate",
+                summary.to
+            ));
+            return Ok(());
+        }
+
+        for migration in &summary.applied {
+            println!(
+                "Pack format {} to {}: {}",
+                migration.from,
+                migration.from + 1,
+                migration.description
+            );
+        }
+        for changed_file in &summary.changed_files {
+            println!("Changed {}", changed_file.display());
+        }
+        if let Some(backup_directory) = &summary.backup_directory {
+            println!(
+                "The original files were backed up to {}",
+                backup_directory.display()
+            );
+        }
+        print_success(format!(
+            "migrated modpack from pack format {} to {}",
+            summary.from, summary.to
+        ));
+        Ok(())
+    }
+}
+
+impl CheckArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let game_versions = GameVersions::from_modrinth()?;
+        for minecraft_version in &self.minecraft {
+            if game_versions.get(minecraft_version).is_none() {
+                return Err(PackrinthError::UnknownMinecraftVersion {
+                    version: minecraft_version.clone(),
+                });
+            }
+        }
+        let targets = self.targets();
+
+        let mut progress_bar = create_progress_bar(modpack.projects.len() * targets.len());
+        let mut rows: Vec<(&String, Vec<&str>)> = Vec::new();
+        for (slug_project_id, project_settings) in &modpack.projects {
+            // Inclusions, exclusions and version overrides are made for existing branches,
+            // so they don't apply to the targets.
+            let project_settings = ProjectSettings {
+                version_overrides: None,
+                include_or_exclude: None,
+                ..modpack.resolve_project_settings(project_settings)?
+            };
+
+            let mut cells = Vec::new();
+            for (label, branch_config) in &targets {
+                progress_bar.set_action(label, Color::Blue, Style::Bold);
+                let availability = ProjectAvailability::check(
+                    label,
+                    branch_config,
+                    slug_project_id,
+                    &project_settings,
+                    Some(&game_versions),
+                );
+                if let ProjectAvailability::Failed(error) = &availability {
+                    progress_bar.print_info(
+                        "failed",
+                        &single_line_error(error.message_and_tip()),
+                        Color::Red,
+                        Style::Bold,
+                    );
                 }
+                cells.push(Self::cell(&availability));
+                progress_bar.inc();
+            }
+            rows.push((slug_project_id, cells));
+        }
+
+        progress_bar.print_final_info(
+            "success:",
+            &format!("checked {} projects", modpack.projects.len()),
+            Color::Green,
+            Style::Bold,
+        );
+        println!();
+
+        print!("{}", Self::matrix(&targets, &rows));
+        Ok(())
+    }
+
+    /// Returns a label and a branch configuration for every combination of a Minecraft version
+    /// and a loader. The branches only exist to check projects against.
+    fn targets(&self) -> Vec<(String, BranchConfig)> {
+        let mut targets = Vec::new();
+        for minecraft_version in &self.minecraft {
+            for loader in &self.loader {
+                let mod_loader = match loader {
+                    CheckLoader::Vanilla => None,
+                    CheckLoader::Fabric => Some(MainLoader::Fabric),
+                    CheckLoader::Forge => Some(MainLoader::Forge),
+                    CheckLoader::NeoForge => Some(MainLoader::NeoForge),
+                    CheckLoader::Quilt => Some(MainLoader::Quilt),
+                };
+                let label = match mod_loader {
+                    Some(mod_loader) => {
+                        format!("{minecraft_version} {}", mod_loader.pretty_value())
+                    }
+                    None => minecraft_version.clone(),
+                };
+                let branch_config = BranchConfig {
+                    minecraft_version: minecraft_version.clone(),
+                    acceptable_minecraft_versions: Vec::new(),
+                    mod_loader,
+                    loader_version: None,
+                    acceptable_loaders: Vec::new(),
+                    ..BranchConfig::default()
+                };
+                targets.push((label, branch_config));
             }
         }
+        targets
+    }
+
+    const fn cell(availability: &ProjectAvailability) -> &'static str {
+        match availability {
+            ProjectAvailability::Release => "release",
+            ProjectAvailability::Beta => "beta",
+            ProjectAvailability::Alpha => "alpha",
+            ProjectAvailability::NotFound => "missing",
+            ProjectAvailability::Skipped => "skipped",
+            ProjectAvailability::Failed(_) => "failed",
+        }
+    }
+
+    /// Formats the availability of every project for every target as a table,
+    /// followed by the number of projects with a release for every target.
+    fn matrix(targets: &[(String, BranchConfig)], rows: &[(&String, Vec<&str>)]) -> String {
+        // All write macros have an unwrap call, because a write call to a String never fails.
+        let mut matrix = String::new();
+        let project_width = rows
+            .iter()
+            .map(|(project, _)| project.chars().count())
+            .chain(std::iter::once("Project".len()))
+            .max()
+            .unwrap_or_default();
+        let column_widths: Vec<usize> = targets
+            .iter()
+            .map(|(label, _)| cmp::max(label.chars().count(), "release".len()))
+            .collect();
+
+        let mut line = format!("{:project_width$}", "Project");
+        for ((label, _), width) in targets.iter().zip(&column_widths) {
+            write!(line, "  {label:width$}").unwrap();
+        }
+        writeln!(matrix, "{}", line.trim_end()).unwrap();
+
+        for (project, cells) in rows {
+            let mut line = format!("{project:project_width$}");
+            for (cell, width) in cells.iter().zip(&column_widths) {
+                write!(line, "  {cell:width$}").unwrap();
+            }
+            writeln!(matrix, "{}", line.trim_end()).unwrap();
+        }
+
+        writeln!(matrix).unwrap();
+        for (column, (label, _)) in targets.iter().enumerate() {
+            let available = rows
+                .iter()
+                .filter(|(_, cells)| cells[column] == "release")
+                .count();
+            writeln!(
+                matrix,
+                "{label}: {available} of {} projects have a release",
+                rows.len()
+            )
+            .unwrap();
+        }
+        matrix
+    }
+}
+
+impl ValidateArgs {
+    // Allow unused self, because then it is clear to the maintainer that self is available for code expansion.
+    #[allow(clippy::unused_self)]
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let mut issues = validation::validate_modpack(modpack);
+        match validation::validate_project_keys(modpack) {
+            Ok(project_issues) => issues.extend(project_issues),
+            Err(error) => print_warning(format!(
+                "projects that are added more than once weren't checked, because {}",
+                single_line_error(error.message_and_tip())
+            )),
+        }
+        let errors = print_validation_issues(&issues);
+        if errors > 0 {
+            eprintln!();
+            return Err(PackrinthError::ModpackIsInvalid { errors });
+        }
+
+        if issues.is_empty() {
+            print_success("no problems found");
+        } else {
+            print_success(format!("no errors found, but {} warning(s)", issues.len()));
+        }
+        Ok(())
+    }
+}
+
+impl LicensesArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        let mut projects = HashMap::new();
+        let mut denied = 0;
+        for branch in branches {
+            declared_branch_config(modpack, branch)?;
+            let branch_licenses = BranchLicenses::collect(modpack, branch, &mut projects)?;
+
+            let denied_entries = branch_licenses.denied(&modpack.denied_licenses);
+            denied += denied_entries.len();
+
+            println!("Branch {branch}:");
+            if branch_licenses.entries.is_empty() {
+                println!("  - No projects or files are added");
+            }
+            for entry in &branch_licenses.entries {
+                let source = match entry.source {
+                    ContentSource::Modrinth => "",
+                    ContentSource::Manual => " (manual file)",
+                    ContentSource::Override => " (override)",
+                };
+                let kind = match entry.kind {
+                    LicenseKind::Spdx => "",
+                    LicenseKind::Custom => " [custom]",
+                    LicenseKind::AllRightsReserved => " [all rights reserved]",
+                    LicenseKind::Unknown => " [unknown]",
+                };
+                let denied = if denied_entries.contains(&entry) {
+                    " [denied]"
+                } else {
+                    ""
+                };
+                println!(
+                    "  - {}{source}: {}{kind}{denied}",
+                    entry.name,
+                    entry.license_label()
+                );
+            }
+
+            let restrictive = branch_licenses
+                .entries
+                .iter()
+                .filter(|entry| entry.kind.is_restrictive())
+                .count();
+            if restrictive > 0 {
+                print_warning(format!(
+                    "{restrictive} project(s) or file(s) in {branch} have a restrictive or unknown license"
+                ));
+            }
+
+            if self.credits {
+                let credits_path = branch_licenses.write_credits(modpack)?;
+                print_success(format!("wrote credits to {}", credits_path.display()));
+            }
+            println!();
+        }
+
+        if denied > 0 {
+            return Err(PackrinthError::DeniedLicensesFound { count: denied });
+        }
+        print_success(format!("checked licenses of {} branch(es)", branches.len()));
         Ok(())
     }
 }
 
+impl SearchArgs {
+    pub fn run(
+        &self,
+        modpack: &mut Modpack,
+        _config_args: &ConfigArgs,
+    ) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        let mut branch_configs = Vec::new();
+        for branch in branches {
+            branch_configs.push((branch, declared_branch_config(modpack, branch)?));
+        }
+
+        let mut facets = SearchResults::branch_facets(
+            &branch_configs
+                .iter()
+                .map(|(_branch, branch_config)| branch_config)
+                .collect::<Vec<&BranchConfig>>(),
+        );
+        if let Some(project_type) = &self.project_type {
+            facets.push(vec![format!("project_type:{project_type}")]);
+        }
+
+        let query = self.query.join(" ");
+        let results = SearchResults::search(&query, &facets, self.limit)?;
+        if results.hits.is_empty() {
+            print_success(format!("no projects found for {query}"));
+            return Ok(());
+        }
+
+        let mut items = Vec::new();
+        for hit in &results.hits {
+            let supported_branches: Vec<&str> = branch_configs
+                .iter()
+                .filter(|(_branch, branch_config)| hit.supports_branch(branch_config))
+                .map(|(branch, _branch_config)| branch.as_str())
+                .collect();
+            let added = if modpack.projects.contains_key(&hit.slug)
+                || modpack.projects.contains_key(&hit.project_id)
+            {
+                " (added)"
+            } else {
+                ""
+            };
+
+            println!(
+                "{} ({}) by {}, {} downloads{added}",
+                hit.title, hit.slug, hit.author, hit.downloads
+            );
+            println!("  {}", hit.description);
+            if supported_branches.is_empty() {
+                println!("  Supports no branches");
+            } else {
+                println!("  Supports {}", supported_branches.join(", "));
+            }
+            items.push(format!("{} ({})", hit.title, hit.slug));
+        }
+        println!();
+        print_success(format!(
+            "showing {} of {} projects",
+            results.hits.len(),
+            results.total_hits
+        ));
+
+        if !self.add {
+            return Ok(());
+        }
+
+        println!();
+        let selection = MultiSelect::new()
+            .with_prompt("Select projects to add")
+            .items(&items)
+            .interact()
+            .expect("Error while interacting with selection");
+        if selection.is_empty() {
+            return Ok(());
+        }
+
+        let mut projects = Vec::new();
+        for index in selection {
+            match modpack.resolve_new_project(&results.hits[index].slug) {
+                Ok(modrinth_project) => {
+                    modpack.add_projects(
+                        std::slice::from_ref(&modrinth_project.slug),
+                        &None,
+                        &None,
+                        &[],
+                    );
+                    projects.push(modrinth_project.slug);
+                }
+                // Don't use ? because then we can't try again for the next project.
+                Err(error) => print_error(error.message_and_tip()),
+            }
+        }
+        if projects.is_empty() {
+            return Ok(());
+        }
+        modpack.save()?;
+
+        print_success(format!("added {}", projects.join(", ")));
+        Ok(())
+    }
+}
+
+impl ExportArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        if !self.no_validate {
+            validate_before(modpack, branches, |_file| true)?;
+        }
+
+        Self::export_branches(modpack, branches)
+    }
+
+    // Allow because it is required in Cli::run.
+    #[allow(clippy::unnecessary_wraps)]
+    fn export_branches(modpack: &Modpack, branches: &Vec<String>) -> Result<(), PackrinthError> {
+        for branch in branches {
+            match modpack.export_branch(branch) {
+                Ok(modpack_path) => {
+                    print_success(format!("exported {} to {}", branch, modpack_path.display()));
+                }
+                Err(error) => {
+                    // Don't use ? because then we can't try again for the next branch.
+                    print_error(error.message_and_tip());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChangelogArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        declared_branch_config(modpack, &self.branch)?;
+        let mut changelog = BranchChangelog::from_git(modpack, &self.branch, &self.since)?;
+        if self.changelogs {
+            changelog.fetch_changelogs()?;
+        }
+
+        let markdown = changelog.to_markdown();
+        if let Some(output) = &self.output {
+            if let Err(error) = fs::write(output, markdown) {
+                return Err(PackrinthError::FailedToWriteFile {
+                    path_to_write_to: output.display().to_string(),
+                    error_message: error.to_string(),
+                });
+            }
+            print_success(format!(
+                "wrote changelog with {} change(s) to {}",
+                changelog.changes.len(),
+                output.display()
+            ));
+        } else {
+            print!("{markdown}");
+        }
+        Ok(())
+    }
+}
+
+impl ReleaseArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        ensure_can_commit(modpack)?;
+
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        if !self.no_validate {
+            validate_before(modpack, branches, |_file| true)?;
+        }
+        let bump = match self.bump {
+            ReleaseBump::Major => VersionBump::Major,
+            ReleaseBump::Minor => VersionBump::Minor,
+            ReleaseBump::Patch => VersionBump::Patch,
+        };
+
+        // Bump all versions before saving, because a branch may inherit its version
+        // from another branch that is also released.
+        let mut releases = Vec::new();
+        for branch in branches {
+            let mut branch_config = declared_branch_config(modpack, branch)?;
+            let version = branch_config.bump_version(branch, bump)?;
+            let inheritance_depth =
+                BranchConfig::inheritance_chain(&modpack.directory, branch)?.len();
+            releases.push((branch, version, branch_config, inheritance_depth));
+        }
+
+        // Save parents first, so children that inherit the new version keep inheriting it.
+        releases.sort_by_key(|(_branch, _version, _branch_config, depth)| *depth);
+        let versions: Vec<String> = releases
+            .iter()
+            .map(|(branch, version, _branch_config, _depth)| format!("{branch} {version}"))
+            .collect();
+        let message = format!("Release {}", versions.join(", "));
+
+        let mut original_files = Vec::new();
+        for (branch, ..) in &releases {
+            let path = modpack
+                .directory
+                .join(branch.as_str())
+                .join(BRANCH_CONFIG_FILE_NAME);
+            match fs::read(&path) {
+                Ok(contents) => original_files.push((path, contents)),
+                Err(error) => {
+                    return Err(PackrinthError::FailedToReadToString {
+                        path_to_read: path.display().to_string(),
+                        error_message: error.to_string(),
+                    });
+                }
+            }
+        }
+        // Only keep the bumped versions if the branches could be exported and committed.
+        if let Err(error) = Self::save_export_and_commit(modpack, &releases, &message) {
+            for (path, contents) in &original_files {
+                if let Err(error) = fs::write(path, contents) {
+                    print_error(
+                        PackrinthError::FailedToWriteFile {
+                            path_to_write_to: path.display().to_string(),
+                            error_message: error.to_string(),
+                        }
+                        .message_and_tip(),
+                    );
+                }
+            }
+            return Err(error);
+        }
+
+        let first_version = &releases
+            .first()
+            .map(|(_branch, version, ..)| version.clone());
+        let tags: Vec<String> = if releases
+            .iter()
+            .all(|(_branch, version, ..)| Some(version) == first_version.as_ref())
+            && let Some(version) = first_version
+        {
+            vec![format!("v{version}")]
+        } else {
+            releases
+                .iter()
+                .map(|(branch, version, ..)| format!("{branch}/v{version}"))
+                .collect()
+        };
+        for tag in &tags {
+            GitUtils::tag_head(&modpack.directory, tag, &message)?;
+        }
+
+        print_success(format!(
+            "released {} and tagged {}",
+            versions.join(", "),
+            tags.join(", ")
+        ));
+        Ok(())
+    }
+
+    fn save_export_and_commit(
+        modpack: &Modpack,
+        releases: &[(&String, String, BranchConfig, usize)],
+        message: &str,
+    ) -> Result<(), PackrinthError> {
+        let mut changed_files = Vec::new();
+        for (branch, _version, branch_config, _depth) in releases {
+            branch_config.save(&modpack.directory, branch)?;
+            changed_files.push(
+                modpack
+                    .directory
+                    .join(branch.as_str())
+                    .join(BRANCH_CONFIG_FILE_NAME),
+            );
+        }
+        // Unlike the export subcommand, stop at the first branch that can't be exported,
+        // because its release would be incomplete.
+        for (branch, ..) in releases {
+            let modpack_path = modpack.export_branch(branch)?;
+            print_success(format!("exported {} to {}", branch, modpack_path.display()));
+        }
+        GitUtils::commit_files(&changed_files, message)?;
+        Ok(())
+    }
+}
+
+impl PublishArgs {
+    pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let Some(project_id) = &modpack.modrinth_project_id else {
+            return Err(PackrinthError::NoModrinthProjectId);
+        };
+        // The token isn't needed for a dry run, so only require it when uploading.
+        let token = std::env::var(MODRINTH_TOKEN_ENV_VAR).ok();
+        if !self.dry_run && token.is_none() {
+            return Err(PackrinthError::NoModrinthToken {
+                env_var: MODRINTH_TOKEN_ENV_VAR.to_string(),
+            });
+        }
+
+        let branches = self.branches.as_ref().unwrap_or(&modpack.branches);
+        if !self.no_validate {
+            validate_before(modpack, branches, |_file| true)?;
+        }
+        let changelog_file = match &self.changelog {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(changelog) => Some(changelog),
+                Err(error) => {
+                    return Err(PackrinthError::FailedToReadToString {
+                        path_to_read: path.display().to_string(),
+                        error_message: error.to_string(),
+                    });
+                }
+            },
+            None => None,
+        };
+        let version_type = match self.version_type {
+            PublishVersionType::Release => VersionType::Release,
+            PublishVersionType::Beta => VersionType::Beta,
+            PublishVersionType::Alpha => VersionType::Alpha,
+        };
+
+        for branch in branches {
+            let branch_config = declared_branch_config(modpack, branch)?;
+            let changelog = match &self.since {
+                Some(since) => {
+                    Some(BranchChangelog::from_git(modpack, branch, since)?.to_markdown())
+                }
+                None => changelog_file.clone(),
+            };
+            let mut new_version = NewVersion::from_branch(
+                &modpack.name,
+                project_id,
+                &branch_config,
+                version_type,
+                changelog,
+            );
+            new_version.featured = self.featured;
+
+            let mrpack_path = modpack.export_branch(branch)?;
+            if let Some(token) = &token
+                && !self.dry_run
+            {
+                let version = new_version.upload(&self.api_base_url, token, &mrpack_path)?;
+                print_success(format!(
+                    "published {branch} as version {} ({}) of project {project_id}",
+                    version.version_number, version.version.id
+                ));
+            } else {
+                Self::print_dry_run(branch, &new_version, &mrpack_path);
+            }
+        }
+        Ok(())
+    }
+
+    fn print_dry_run(branch: &str, new_version: &NewVersion, mrpack_path: &Path) {
+        println!("{branch}:");
+        println!("  - Name: {}", new_version.name);
+        println!("  - Version number: {}", new_version.version_number);
+        println!(
+            "  - Version type: {}",
+            new_version.version_type.modrinth_value()
+        );
+        println!(
+            "  - Game versions: {}",
+            new_version.game_versions.join(", ")
+        );
+        println!("  - Loaders: {}", new_version.loaders.join(", "));
+        println!("  - Featured: {}", new_version.featured);
+        println!("  - File: {}", mrpack_path.display());
+        match &new_version.changelog {
+            Some(changelog) => {
+                println!("  - Changelog:");
+                for line in changelog.lines() {
+                    println!("    {line}");
+                }
+            }
+            None => println!("  - Changelog: none"),
+        }
+        print_success(format!(
+            "dry run: would publish {branch} to project {}",
+            new_version.project_id
+        ));
+    }
+}
+
 impl CleanArgs {
     // Allow unused self, because then it is clear to the maintainer that self is available for code expansion.
     #[allow(clippy::unused_self)]
@@ -812,24 +2352,170 @@ impl CleanArgs {
 }
 
 impl DocArgs {
-    // Allow unused self, because then it is clear to the maintainer that self is available for code expansion.
-    #[allow(clippy::unused_self)]
     pub fn run(&self, modpack: &Modpack, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
-        let doc_markdown_table = modpack.generate_project_table()?;
+        let (contents, file_name) = match self.format {
+            DocFormat::Markdown => (self.markdown(modpack)?, "index.md"),
+            DocFormat::Csv => (self.table(modpack)?.to_csv(&self.columns()), "projects.csv"),
+            DocFormat::Json => (
+                format!("{}\n", self.table(modpack)?.to_json(&self.columns())?),
+                "projects.json",
+            ),
+            DocFormat::Html => {
+                // Clap requires an output directory for HTML.
+                let Some(out) = &self.out else {
+                    return Ok(());
+                };
+                let projects = DocSite::read_project_cache(modpack);
+                let doc_site = DocSite::collect(modpack, projects)?;
+                for error in &doc_site.request_errors {
+                    print_warning(format!(
+                        "couldn't request the Modrinth projects, so their files are shown by file name: {}",
+                        single_line_error(error.message_and_tip())
+                    ));
+                }
+                if let Err(error) = doc_site.save_project_cache(modpack) {
+                    print_warning(format!(
+                        "couldn't cache the Modrinth projects: {}",
+                        single_line_error(error.message_and_tip())
+                    ));
+                }
+                for branch in &doc_site.branches {
+                    if branch.mrpack_path.is_none() {
+                        print_warning(format!(
+                            "branch {} hasn't been exported with its current version, so its page has no download link",
+                            branch.name
+                        ));
+                    }
+                }
+                let written = doc_site.write(out)?;
+                print_success(format!(
+                    "saved {} files of the documentation site to {}",
+                    written.len(),
+                    out.display()
+                ));
+                return Ok(());
+            }
+        };
+
+        if let Some(out) = &self.out {
+            let path = out.join(file_name);
+            packrinth::write_file(&path, &contents)?;
+            print_success(format!("saved documentation to {}", path.display()));
+        } else {
+            print!("{contents}");
+        }
+        Ok(())
+    }
+
+    fn columns(&self) -> Vec<ProjectTableColumn> {
+        self.columns
+            .iter()
+            .map(|column| match column {
+                DocColumn::Availability => ProjectTableColumn::Availability,
+                DocColumn::File => ProjectTableColumn::FileName,
+                DocColumn::Environment => ProjectTableColumn::Environment,
+                DocColumn::VersionType => ProjectTableColumn::VersionType,
+                DocColumn::Category => ProjectTableColumn::Category,
+            })
+            .filter(|column| {
+                !(self.no_compatibility_icons && *column == ProjectTableColumn::Availability)
+            })
+            .collect()
+    }
 
+    /// Generates the project table with only the projects of the requested group,
+    /// and requests the details of the columns that aren't in the branch files.
+    fn table(&self, modpack: &Modpack) -> Result<ProjectTable, PackrinthError> {
+        let mut table = modpack.generate_project_table()?;
+        if self.group.is_some() || self.group_sections || matches!(self.format, DocFormat::Json) {
+            table.assign_groups(&modpack.projects)?;
+        }
+
+        if let Some(group) = &self.group {
+            if !modpack.groups.contains_key(group) {
+                return Err(PackrinthError::GroupDoesNotExist {
+                    group: group.clone(),
+                });
+            }
+            table.retain_group(Some(group));
+        }
+
+        for error in table.fetch_modrinth_details(&self.columns()) {
+            print_warning(format!(
+                "couldn't request details from Modrinth, so they are shown as unknown: {}",
+                single_line_error(error.message_and_tip())
+            ));
+        }
+        Ok(table)
+    }
+
+    fn markdown(&self, modpack: &Modpack) -> Result<String, PackrinthError> {
+        let doc_markdown_table = self.table(modpack)?;
+
+        let mut markdown = String::new();
         if !self.table_only {
-            println!("# {} _by {}_", modpack.name, modpack.author);
-            println!("{}", modpack.summary);
+            writeln!(markdown, "# {} _by {}_", modpack.name, modpack.author).unwrap();
+            writeln!(markdown, "{}", modpack.summary).unwrap();
         }
 
-        if !doc_markdown_table.project_map.is_empty() {
+        if !doc_markdown_table.rows.is_empty() {
             if !self.table_only {
-                println!("## What is included?");
+                writeln!(markdown, "## What is included?").unwrap();
             }
-            if self.no_compatibility_icons {
-                println!("{}", doc_markdown_table.display_no_compatibility_icons());
+
+            if self.group_sections {
+                for group in modpack.groups.keys() {
+                    let mut group_table = doc_markdown_table.clone();
+                    group_table.retain_group(Some(group));
+                    self.push_table_section(&mut markdown, group, &group_table);
+                }
+
+                let mut ungrouped_table = doc_markdown_table;
+                ungrouped_table.retain_group(None);
+                self.push_table_section(&mut markdown, "Other", &ungrouped_table);
             } else {
-                println!("{doc_markdown_table}");
+                self.push_table(&mut markdown, &doc_markdown_table);
+            }
+        }
+
+        Ok(markdown)
+    }
+
+    fn push_table_section(&self, markdown: &mut String, title: &str, table: &ProjectTable) {
+        if table.rows.is_empty() {
+            return;
+        }
+
+        writeln!(markdown, "### {title}").unwrap();
+        self.push_table(markdown, table);
+    }
+
+    fn push_table(&self, markdown: &mut String, table: &ProjectTable) {
+        writeln!(markdown, "{}", table.to_markdown(&self.columns())).unwrap();
+    }
+}
+
+impl SchemaArgs {
+    pub fn run(&self, _config_args: &ConfigArgs) -> Result<(), PackrinthError> {
+        let schemas = match self.file {
+            Some(SchemaFile::Modpack) => vec![ConfigSchema::Modpack],
+            Some(SchemaFile::Branch) => vec![ConfigSchema::BranchConfig],
+            Some(SchemaFile::BranchFiles) => vec![ConfigSchema::BranchFiles],
+            None => ConfigSchema::ALL.to_vec(),
+        };
+
+        if let Some(out) = &self.out {
+            for schema in schemas {
+                let path = schema.write_to_directory(out)?;
+                print_success(format!(
+                    "saved schema of {} to {}",
+                    schema.config_file_name(),
+                    path.display()
+                ));
+            }
+        } else {
+            for schema in schemas {
+                println!("{}", schema.to_json_string()?);
             }
         }
 
@@ -921,6 +2607,7 @@ mod tests {
         // Check if modpack.json is right
         assert_eq!(
             "{
+	\"$schema\": \"https://packrinth.thijzert.nl/schemas/modpack.schema.json\",
 	\"pack_format\": 1,
 	\"name\": \"My Modrinth modpack\",
 	\"summary\": \"Short summary for this modpack\",
@@ -934,6 +2621,136 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_targets_and_matrix() {
+        let check_args = CheckArgs {
+            minecraft: vec!["1.21.8".to_string(), "1.21.10".to_string()],
+            loader: vec![CheckLoader::Vanilla, CheckLoader::NeoForge],
+        };
+        let targets = check_args.targets();
+        assert_eq!(
+            vec![
+                ("1.21.8".to_string(), None),
+                ("1.21.8 NeoForge".to_string(), Some(MainLoader::NeoForge)),
+                ("1.21.10".to_string(), None),
+                ("1.21.10 NeoForge".to_string(), Some(MainLoader::NeoForge)),
+            ],
+            targets
+                .iter()
+                .map(|(label, branch_config)| (label.clone(), branch_config.mod_loader))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("1.21.10", targets[3].1.minecraft_version);
+
+        let sodium = "sodium".to_string();
+        let datapack = "some-datapack".to_string();
+        let rows = vec![
+            (
+                &sodium,
+                vec![
+                    CheckArgs::cell(&ProjectAvailability::NotFound),
+                    CheckArgs::cell(&ProjectAvailability::Release),
+                    CheckArgs::cell(&ProjectAvailability::Skipped),
+                    CheckArgs::cell(&ProjectAvailability::Beta),
+                ],
+            ),
+            (
+                &datapack,
+                vec![
+                    CheckArgs::cell(&ProjectAvailability::Release),
+                    CheckArgs::cell(&ProjectAvailability::Release),
+                    CheckArgs::cell(&ProjectAvailability::Alpha),
+                    CheckArgs::cell(&ProjectAvailability::Failed(
+                        PackrinthError::FailedToFinishZip,
+                    )),
+                ],
+            ),
+        ];
+        assert_eq!(
+            "Project        1.21.8   1.21.8 NeoForge  1.21.10  1.21.10 NeoForge
+sodium         missing  release          skipped  beta
+some-datapack  release  release          alpha    failed
+
+1.21.8: 1 of 2 projects have a release
+1.21.8 NeoForge: 2 of 2 projects have a release
+1.21.10: 0 of 2 projects have a release
+1.21.10 NeoForge: 0 of 2 projects have a release
+",
+            CheckArgs::matrix(&targets, &rows)
+        );
+    }
+
+    #[test]
+    fn release_commits_and_tags_or_rolls_back() {
+        let temp_dir = TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        init(directory);
+        let mut git_config = fs::OpenOptions::new()
+            .append(true)
+            .open(directory.join(".git").join("config"))
+            .unwrap();
+        io::Write::write_all(
+            &mut git_config,
+            b"[user]\n\tname = Packrinth\n\temail = packrinth@example.com\n",
+        )
+        .unwrap();
+
+        let mut modpack = Modpack::from_directory(directory).unwrap();
+        let mut branch_config = modpack.new_branch("1.21.8").unwrap();
+        branch_config.version = "1.0.0".to_string();
+        branch_config.save(directory, "1.21.8").unwrap();
+        BranchFiles::default().save(directory, "1.21.8").unwrap();
+        modpack.save().unwrap();
+        let files: Vec<PathBuf> = GitUtils::modpack_changed_files(&modpack)
+            .unwrap()
+            .iter()
+            .map(|file| directory.join(file))
+            .collect();
+        GitUtils::commit_files(&files, "Initial commit").unwrap();
+
+        let config_args = ConfigArgs {
+            directory: Some(directory.to_path_buf()),
+            verbose: false,
+        };
+        let release_args = ReleaseArgs {
+            bump: ReleaseBump::Minor,
+            branches: None,
+            no_validate: false,
+        };
+        release_args.run(&modpack, &config_args).unwrap();
+
+        let branch_config = BranchConfig::from_directory(directory, "1.21.8").unwrap();
+        assert_eq!("1.1.0", branch_config.version);
+        assert!(
+            modpack
+                .exported_mrpack_path("1.21.8", &branch_config)
+                .is_file()
+        );
+        assert!(
+            GitUtils::modpack_changed_files(&modpack)
+                .unwrap()
+                .is_empty()
+        );
+        let git_repo = gix::open(directory).unwrap();
+        let head_commit = git_repo.head_commit().unwrap();
+        assert_eq!("Release 1.21.8 1.1.0", head_commit.message_raw_sloppy());
+        let mut tag = git_repo.find_reference("refs/tags/v1.1.0").unwrap();
+        assert_eq!(head_commit.id, tag.peel_to_commit().unwrap().id);
+
+        // If the branch can't be exported, the version isn't bumped and nothing is committed.
+        fs::remove_dir_all(directory.join(packrinth::TARGET_DIRECTORY)).unwrap();
+        fs::write(directory.join(packrinth::TARGET_DIRECTORY), "").unwrap();
+        assert!(release_args.run(&modpack, &config_args).is_err());
+        assert_eq!(
+            "1.1.0",
+            BranchConfig::from_directory(directory, "1.21.8")
+                .unwrap()
+                .version
+        );
+        assert_eq!(head_commit.id, git_repo.head_id().unwrap().detach());
+        assert!(git_repo.find_reference("refs/tags/v1.2.0").is_err());
+    }
+
     #[test]
     fn test_projects() {
         let test_modpack_dir = TempDir::new("packrinth").unwrap().path().to_owned();
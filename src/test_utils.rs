@@ -0,0 +1,44 @@
+//! Fixtures that are shared by the unit tests of multiple modules.
+
+use crate::modrinth::{File, FileHashes, FullProject};
+
+/// Creates a file of a branch that is required on both sides.
+pub fn file(path: &str, hash: &str, download: &str) -> File {
+    File {
+        project_name: String::new(),
+        path: path.to_string(),
+        hashes: FileHashes {
+            sha1: hash.to_string(),
+            sha512: hash.to_string(),
+        },
+        env: None,
+        downloads: vec![download.to_string()],
+        file_size: 0,
+    }
+}
+
+/// Returns the Sodium project, as it could be returned by Modrinth.
+pub fn sodium() -> FullProject {
+    serde_json::from_value(serde_json::json!({
+        "id": "AANobbMI",
+        "slug": "sodium",
+        "title": "Sodium",
+        "client_side": "required",
+        "server_side": "unsupported",
+        "project_type": "mod",
+        "description": "The fastest rendering optimization mod",
+        "body": "",
+        "status": "approved",
+        "categories": [],
+        "additional_categories": [],
+        "icon_url": "https://cdn.modrinth.com/data/AANobbMI/icon.png",
+        "team": "4reLOAKe",
+        "published": "2021-01-03T00:53:34.185936Z",
+        "updated": "2025-07-23T19:06:28.103254Z",
+        "downloads": 0,
+        "followers": 0,
+        "license": {"id": "LicenseRef-Polyform-Shield-License-1.0.0", "name": "", "url": null},
+        "versions": []
+    }))
+    .unwrap()
+}
@@ -0,0 +1,615 @@
+//! Checks for problems in the configuration files of a Packrinth modpack.
+
+use crate::GitUtils;
+use crate::PackrinthResult;
+use crate::config::{
+    BRANCH_CONFIG_FILE_NAME, BRANCH_FILES_FILE_NAME, BranchConfig, BranchFiles, BranchSelector,
+    IncludeOrExclude, MODPACK_CONFIG_FILE_NAME, Modpack, RESERVED_BRANCH_NAMES,
+};
+use crate::manual;
+use crate::modrinth::{File, Project};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The modpack still works, but probably not like the user intended.
+    Warning,
+
+    /// The modpack can't be updated or exported correctly.
+    Error,
+}
+
+/// The configuration file a [`ValidationIssue`] was found in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ValidatedFile {
+    /// The modpack configuration file.
+    Modpack,
+
+    /// The configuration file of the branch with this name.
+    BranchConfig(String),
+
+    /// The branch files of the branch with this name.
+    BranchFiles(String),
+}
+
+/// A problem found while validating a modpack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub file: ValidatedFile,
+    pub message: String,
+}
+
+impl Display for ValidatedFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidatedFile::Modpack => write!(f, "{MODPACK_CONFIG_FILE_NAME}"),
+            ValidatedFile::BranchConfig(branch) => write!(f, "{branch}/{BRANCH_CONFIG_FILE_NAME}"),
+            ValidatedFile::BranchFiles(branch) => write!(f, "{branch}/{BRANCH_FILES_FILE_NAME}"),
+        }
+    }
+}
+
+impl ValidationIssue {
+    fn new(severity: Severity, file: ValidatedFile, message: String) -> Self {
+        Self {
+            severity,
+            file,
+            message,
+        }
+    }
+
+    /// Returns the branch this issue belongs to, or [`None`] if it was found
+    /// in the modpack configuration file.
+    #[must_use]
+    pub fn branch(&self) -> Option<&str> {
+        match &self.file {
+            ValidatedFile::Modpack => None,
+            ValidatedFile::BranchConfig(branch) | ValidatedFile::BranchFiles(branch) => {
+                Some(branch)
+            }
+        }
+    }
+}
+
+/// Checks the modpack configuration file, and the configuration file and branch files of every
+/// branch, and returns all problems that were found.
+///
+/// No files are written while validating, so missing files are reported instead of created.
+#[must_use]
+pub fn validate_modpack(modpack: &Modpack) -> Vec<ValidationIssue> {
+    let mut issues = validate_modpack_config(modpack);
+    for branch in &modpack.branches {
+        issues.extend(validate_branch(modpack, branch));
+        issues.extend(validate_branch_files_are_tracked(modpack, branch));
+    }
+    issues
+}
+
+fn validate_modpack_config(modpack: &Modpack) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity, message| {
+        issues.push(ValidationIssue::new(
+            severity,
+            ValidatedFile::Modpack,
+            message,
+        ));
+    };
+
+    let mut seen_branches = HashSet::new();
+    for branch in &modpack.branches {
+        if !seen_branches.insert(branch) {
+            issue(
+                Severity::Error,
+                format!("branch {branch} is declared more than once"),
+            );
+        }
+        if RESERVED_BRANCH_NAMES.contains(&branch.as_str()) {
+            issue(
+                Severity::Error,
+                format!(
+                    "branch {branch} has a reserved name, so Packrinth also uses its directory for something else"
+                ),
+            );
+        }
+    }
+
+    let mut selector_issues = Vec::new();
+    for (project, project_settings) in &modpack.projects {
+        for group in &project_settings.groups {
+            if !modpack.groups.contains_key(group) {
+                issue(
+                    Severity::Error,
+                    format!("project {project} is in group {group}, which isn't defined"),
+                );
+            }
+        }
+        if let Some(version_overrides) = &project_settings.version_overrides {
+            for branch in version_overrides.keys() {
+                if !modpack.branches.contains(branch) {
+                    issue(
+                        Severity::Warning,
+                        format!(
+                            "project {project} has a version override for branch {branch}, which doesn't exist"
+                        ),
+                    );
+                }
+            }
+        }
+        selector_issues.extend(validate_include_or_exclude(
+            modpack,
+            &format!("project {project}"),
+            project_settings.include_or_exclude.as_ref(),
+        ));
+    }
+    for (group, group_settings) in &modpack.groups {
+        selector_issues.extend(validate_include_or_exclude(
+            modpack,
+            &format!("group {group}"),
+            group_settings.include_or_exclude.as_ref(),
+        ));
+    }
+    for (severity, message) in selector_issues {
+        issue(severity, message);
+    }
+
+    issues
+}
+
+fn validate_include_or_exclude(
+    modpack: &Modpack,
+    owner: &str,
+    include_or_exclude: Option<&IncludeOrExclude>,
+) -> Vec<(Severity, String)> {
+    let (kind, selectors) = match include_or_exclude {
+        Some(IncludeOrExclude::Include(inclusions)) => ("inclusions", inclusions),
+        Some(IncludeOrExclude::Exclude(exclusions)) => ("exclusions", exclusions),
+        None => return Vec::new(),
+    };
+
+    let mut issues = Vec::new();
+    for selector in selectors {
+        match BranchSelector::parse(selector) {
+            Ok(BranchSelector::Name(branch)) => {
+                if !modpack.branches.contains(&branch) {
+                    issues.push((
+                        Severity::Warning,
+                        format!(
+                            "the {kind} of {owner} contain branch {branch}, which doesn't exist"
+                        ),
+                    ));
+                }
+            }
+            Ok(BranchSelector::Rule(_)) => {}
+            Err(error) => issues.push((
+                Severity::Error,
+                format!(
+                    "the {kind} of {owner} are invalid: {}",
+                    error.message_and_tip().0
+                ),
+            )),
+        }
+    }
+    issues
+}
+
+fn branch_config_problems(branch_config: &BranchConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    if branch_config.mod_loader.is_some() && branch_config.loader_version.is_none() {
+        problems.push("a main mod loader is set, but loader_version is missing".to_string());
+    }
+    if let Some(datapack_directory) = &branch_config.datapack_directory
+        && !manual::is_instance_path(datapack_directory)
+    {
+        problems.push(format!(
+            "datapack_directory {datapack_directory} has to be a path inside the instance directory"
+        ));
+    }
+    if branch_config.datapack_loader.is_some() && branch_config.datapack_directory.is_none() {
+        problems.push("a data pack loader is set, but datapack_directory is missing".to_string());
+    }
+    problems.extend(validate_files(&branch_config.manual_files, "manual file"));
+    problems
+}
+
+fn validate_branch(modpack: &Modpack, branch: &str) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let config_file = ValidatedFile::BranchConfig(branch.to_string());
+    let files_file = ValidatedFile::BranchFiles(branch.to_string());
+
+    let branch_dir = modpack.directory.join(branch);
+    if !branch_dir.is_dir() {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            config_file,
+            format!(
+                "branch {branch} is declared in {MODPACK_CONFIG_FILE_NAME}, but its directory doesn't exist"
+            ),
+        ));
+        return issues;
+    }
+
+    if branch_dir.join(BRANCH_CONFIG_FILE_NAME).is_file() {
+        match BranchConfig::from_existing_directory(&modpack.directory, branch) {
+            Ok(branch_config) => issues.extend(
+                branch_config_problems(&branch_config)
+                    .into_iter()
+                    .map(|message| {
+                        ValidationIssue::new(Severity::Error, config_file.clone(), message)
+                    }),
+            ),
+            Err(error) => issues.push(ValidationIssue::new(
+                Severity::Error,
+                config_file,
+                error.message_and_tip().0,
+            )),
+        }
+    } else {
+        issues.push(ValidationIssue::new(
+            Severity::Warning,
+            config_file,
+            "the configuration file doesn't exist, so the default configuration will be used"
+                .to_string(),
+        ));
+    }
+
+    if !branch_dir.join(BRANCH_FILES_FILE_NAME).is_file() {
+        issues.push(ValidationIssue::new(
+            Severity::Warning,
+            files_file,
+            format!("branch {branch} hasn't been updated yet"),
+        ));
+        return issues;
+    }
+    match BranchFiles::from_directory(&modpack.directory, branch) {
+        Ok(branch_files) => {
+            let mut project_names: HashMap<&str, &str> = HashMap::new();
+            for project in &branch_files.projects {
+                if let Some(id) = &project.id
+                    && project_names.insert(id, &project.name).is_some()
+                {
+                    issues.push(ValidationIssue::new(
+                        Severity::Error,
+                        files_file.clone(),
+                        format!(
+                            "project {} ({id}) was added more than once, for example with both its slug and its ID",
+                            project.name
+                        ),
+                    ));
+                }
+            }
+            for message in validate_files(&branch_files.files, "file") {
+                issues.push(ValidationIssue::new(
+                    Severity::Error,
+                    files_file.clone(),
+                    message,
+                ));
+            }
+        }
+        Err(error) => issues.push(ValidationIssue::new(
+            Severity::Error,
+            files_file,
+            error.message_and_tip().0,
+        )),
+    }
+
+    issues
+}
+
+/// Checks that no project in the modpack configuration is added with both its slug and its ID.
+///
+/// This uses a single request to Modrinth to look up all projects, so it isn't part of
+/// [`validate_modpack`], which works offline.
+///
+/// # Errors
+/// - Errors of [`Project::from_ids`]
+pub fn validate_project_keys(modpack: &Modpack) -> PackrinthResult<Vec<ValidationIssue>> {
+    if modpack.projects.is_empty() {
+        return Ok(Vec::new());
+    }
+    let keys: Vec<String> = modpack.projects.keys().cloned().collect();
+    Ok(duplicate_project_issues(
+        modpack,
+        &Project::from_ids(&keys)?,
+    ))
+}
+
+fn duplicate_project_issues(modpack: &Modpack, projects: &[Project]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for project in projects {
+        if !seen_ids.insert(&project.id) {
+            continue;
+        }
+        let keys: Vec<&str> = modpack
+            .projects
+            .keys()
+            .filter(|key| **key == project.id || **key == project.slug)
+            .map(String::as_str)
+            .collect();
+        if keys.len() > 1 {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                ValidatedFile::Modpack,
+                format!(
+                    "project {} is added more than once, as {}",
+                    project.title,
+                    keys.join(" and ")
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+/// Checks that Git doesn't ignore the branch files, because they are needed to export the branch.
+fn validate_branch_files_are_tracked(modpack: &Modpack, branch: &str) -> Option<ValidationIssue> {
+    let branch_files_path = modpack.directory.join(branch).join(BRANCH_FILES_FILE_NAME);
+    match GitUtils::is_ignored(&branch_files_path) {
+        Ok(true) => Some(ValidationIssue::new(
+            Severity::Warning,
+            ValidatedFile::BranchFiles(branch.to_string()),
+            "the branch files are ignored by Git, so they won't be committed; remove them from .gitignore"
+                .to_string(),
+        )),
+        Ok(false) => None,
+        Err(error) => Some(ValidationIssue::new(
+            Severity::Warning,
+            ValidatedFile::BranchFiles(branch.to_string()),
+            format!(
+                "couldn't check whether Git ignores the branch files: {}",
+                error.message_and_tip().0
+            ),
+        )),
+    }
+}
+
+/// Checks files for duplicate paths, missing hashes and missing downloads.
+fn validate_files(files: &[File], kind: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut paths = HashSet::new();
+    for file in files {
+        if file.path.is_empty() {
+            messages.push(format!("a {kind} doesn't have a path"));
+        } else if !manual::is_instance_path(&file.path) {
+            messages.push(format!(
+                "{kind} {} has to be a path inside the instance directory",
+                file.path
+            ));
+        } else if !paths.insert(&file.path) {
+            messages.push(format!(
+                "path {} is used by more than one {kind}",
+                file.path
+            ));
+        }
+        if file.hashes.sha1.is_empty() || file.hashes.sha512.is_empty() {
+            messages.push(format!(
+                "{kind} {} is missing a sha1 or sha512 hash",
+                file.path
+            ));
+        }
+        if file.downloads.is_empty() {
+            messages.push(format!("{kind} {} doesn't have any downloads", file.path));
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProjectSettings;
+    use crate::modrinth::{ProjectType, SideSupport};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn validate_modpack_reports_all_issues() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        let mut branch_config = modpack.new_branch("1.21.8").unwrap();
+        branch_config.loader_version = None;
+        branch_config.save(directory, "1.21.8").unwrap();
+        modpack.branches.push("1.21.9".to_string());
+        modpack.branches.push("shared".to_string());
+        modpack.projects.insert(
+            "sodium".to_string(),
+            ProjectSettings {
+                groups: vec!["performance".to_string()],
+                include_or_exclude: Some(IncludeOrExclude::Include(vec![
+                    "1.20.1".to_string(),
+                    "loader = fabric".to_string(),
+                    "loader < fabric".to_string(),
+                ])),
+                ..ProjectSettings::default()
+            },
+        );
+
+        let issues = validate_modpack(&modpack);
+        assert_eq!(
+            vec![
+                (Severity::Error, ValidatedFile::Modpack),
+                (Severity::Error, ValidatedFile::Modpack),
+                (Severity::Warning, ValidatedFile::Modpack),
+                (Severity::Error, ValidatedFile::Modpack),
+                (
+                    Severity::Error,
+                    ValidatedFile::BranchConfig("1.21.8".to_string())
+                ),
+                (
+                    Severity::Warning,
+                    ValidatedFile::BranchFiles("1.21.8".to_string())
+                ),
+                (
+                    Severity::Error,
+                    ValidatedFile::BranchConfig("1.21.9".to_string())
+                ),
+                (
+                    Severity::Error,
+                    ValidatedFile::BranchConfig("shared".to_string())
+                ),
+            ],
+            issues
+                .iter()
+                .map(|issue| (issue.severity, issue.file.clone()))
+                .collect::<Vec<_>>()
+        );
+        assert!(issues[0].message.contains("has a reserved name"));
+        assert!(
+            issues[2]
+                .message
+                .contains("contain branch 1.20.1, which doesn't exist")
+        );
+        assert_eq!(Some("1.21.9"), issues[6].branch());
+        assert!(
+            !directory
+                .join("1.21.8")
+                .join(BRANCH_FILES_FILE_NAME)
+                .exists()
+        );
+    }
+
+    #[test]
+    fn branch_config_problems() {
+        let mut branch_config = BranchConfig {
+            datapack_directory: Some("../datapacks".to_string()),
+            ..BranchConfig::default()
+        };
+        assert_eq!(
+            vec![
+                "datapack_directory ../datapacks has to be a path inside the instance directory"
+                    .to_string()
+            ],
+            super::branch_config_problems(&branch_config)
+        );
+
+        branch_config.datapack_directory = None;
+        branch_config.datapack_loader = Some("paxi".to_string());
+        assert_eq!(
+            vec!["a data pack loader is set, but datapack_directory is missing".to_string()],
+            super::branch_config_problems(&branch_config)
+        );
+
+        branch_config.datapack_directory = Some("config/paxi/datapacks".to_string());
+        assert!(super::branch_config_problems(&branch_config).is_empty());
+
+        branch_config.manual_files = vec![crate::test_utils::file(
+            "mods/../../evil.jar",
+            "a",
+            "https://github.com/example/evil.jar",
+        )];
+        assert_eq!(
+            vec![
+                "manual file mods/../../evil.jar has to be a path inside the instance directory"
+                    .to_string()
+            ],
+            super::branch_config_problems(&branch_config)
+        );
+    }
+
+    #[test]
+    fn validate_modpack_reports_ignored_branch_files() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        modpack.new_branch("1.21.8").unwrap();
+        BranchFiles::default().save(directory, "1.21.8").unwrap();
+
+        GitUtils::initialize_modpack_repo(directory).unwrap();
+        assert!(validate_modpack(&modpack).is_empty());
+
+        std::fs::write(
+            directory.join(".gitignore"),
+            format!("target\n{BRANCH_FILES_FILE_NAME}\n"),
+        )
+        .unwrap();
+        let issues = validate_modpack(&modpack);
+        assert_eq!(1, issues.len());
+        assert_eq!(
+            ValidatedFile::BranchFiles("1.21.8".to_string()),
+            issues[0].file
+        );
+        assert!(issues[0].message.contains("ignored by Git"));
+
+        // Files that are already tracked aren't ignored.
+        let mut git_config = std::fs::OpenOptions::new()
+            .append(true)
+            .open(directory.join(".git").join("config"))
+            .unwrap();
+        std::io::Write::write_all(
+            &mut git_config,
+            b"[user]\n\tname = Packrinth\n\temail = packrinth@example.com\n",
+        )
+        .unwrap();
+        GitUtils::commit_files(
+            &[directory.join("1.21.8").join(BRANCH_FILES_FILE_NAME)],
+            "Add branch files",
+        )
+        .unwrap();
+        assert!(validate_modpack(&modpack).is_empty());
+    }
+
+    #[test]
+    fn validate_modpack_does_not_create_missing_branches() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let directory = temp_dir.path();
+        let mut modpack = Modpack::new(directory, false).unwrap();
+        modpack.new_branch("1.21.8").unwrap();
+        modpack
+            .new_branch_extending("1.21.8-server", "1.21.8")
+            .unwrap();
+        std::fs::remove_file(directory.join("1.21.8").join(BRANCH_CONFIG_FILE_NAME)).unwrap();
+
+        let issues = validate_modpack(&modpack);
+        assert!(
+            issues
+                .iter()
+                .any(|issue| issue.file == ValidatedFile::BranchConfig("1.21.8-server".to_string()))
+        );
+        assert!(
+            !directory
+                .join("1.21.8")
+                .join(BRANCH_CONFIG_FILE_NAME)
+                .exists()
+        );
+    }
+
+    #[test]
+    fn duplicate_project_issues() {
+        let temp_dir = tempdir::TempDir::new("packrinth").unwrap();
+        let mut modpack = Modpack::new(temp_dir.path(), false).unwrap();
+        for key in ["sodium", "AANobbMI", "lithium"] {
+            modpack
+                .projects
+                .insert(key.to_string(), ProjectSettings::default());
+        }
+        let project = |id: &str, slug: &str, title: &str| Project {
+            id: id.to_string(),
+            slug: slug.to_string(),
+            title: title.to_string(),
+            server_side: SideSupport::Optional,
+            client_side: SideSupport::Required,
+            project_type: ProjectType::Mod,
+            loaders: Vec::new(),
+            game_versions: Vec::new(),
+        };
+
+        let issues = super::duplicate_project_issues(
+            &modpack,
+            &[
+                project("AANobbMI", "sodium", "Sodium"),
+                project("AANobbMI", "sodium", "Sodium"),
+                project("gvQqBUqZ", "lithium", "Lithium"),
+            ],
+        );
+        assert_eq!(
+            vec!["project Sodium is added more than once, as sodium and AANobbMI".to_string()],
+            issues
+                .into_iter()
+                .map(|issue| issue.message)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,3 +1,6 @@
+// Allow because these tests predate the lint and are kept as they were written.
+#![allow(clippy::unnecessary_to_owned)]
+
 use packrinth::PackrinthError;
 use packrinth::config::{BranchConfig, MainLoader, ProjectSettings};
 use packrinth::modrinth::{Env, File, FileHashes, FileResult, Project, ProjectType, SideSupport};
@@ -11,8 +14,14 @@ fn project_from_id() -> Result<(), PackrinthError> {
         server_side: SideSupport::Optional,
         client_side: SideSupport::Optional,
         project_type: ProjectType::Mod,
+        loaders: vec!["fabric".to_string()],
+        game_versions: Vec::new(),
     };
     let project = Project::from_id("fabric-api")?;
+    let project = Project {
+        game_versions: Vec::new(),
+        ..project
+    };
 
     assert_eq!(expected_project, project);
     Ok(())
@@ -21,18 +30,19 @@ fn project_from_id() -> Result<(), PackrinthError> {
 #[test]
 fn file_from_project() {
     let branch_config = BranchConfig {
+        schema: None,
+        extends: None,
         version: "test".to_string(),
         minecraft_version: "1.17.1".to_string(),
         acceptable_minecraft_versions: vec![],
         mod_loader: Some(MainLoader::Fabric),
         loader_version: Some("0.17.2".to_string()),
         acceptable_loaders: vec![],
+        datapack_directory: None,
+        datapack_loader: None,
         manual_files: vec![],
     };
-    let project_settings = ProjectSettings {
-        version_overrides: None,
-        include_or_exclude: None,
-    };
+    let project_settings = ProjectSettings::default();
 
     // Test with all versions (alpha and beta included)
     let file = File::from_project(
@@ -42,6 +52,7 @@ fn file_from_project() {
         &project_settings,
         false,
         false,
+        None,
     );
     assert_eq!(FileResult::Ok {
         file: File {
@@ -67,6 +78,7 @@ fn file_from_project() {
         &project_settings,
         true,
         true,
+        None,
     );
     assert_eq!(FileResult::Ok {
         file: File {